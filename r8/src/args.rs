@@ -1,19 +1,361 @@
 //! r8 commandline arguments.
 
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use r8lib::{
+    Persistence, Platform, Quirks, Timing, Tone, Waveform, DEFAULT_ATTACK, DEFAULT_FREQUENCY, DEFAULT_RELEASE,
+    DEFAULT_VOLUME,
+};
+use std::{path::PathBuf, time::Duration};
+
+use crate::palette::{parse_color, Color};
 
 /// A simple CHIP-8 interpreter made with rust, winit and pixels.
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct CmdArgs {
+    /// A tool to run instead of the interpreter.
+    #[command(subcommand)]
+    pub command: Option<Command>,
     /// A ROM file to be loaded.
     #[arg(required = true, value_parser)]
+    pub rom: Option<PathBuf>,
+    /// A value to scale every pixel from the screen. Defaults to the window size of the last run, or 8.
+    #[arg(short, long)]
+    pub scale: Option<u32>,
+    /// A desirable speed of the interpreter.
+    #[arg(short('S'), long, default_value_t = 1.0)]
+    pub speed: f64,
+    /// The platform, or instruction set, the ROM was written for. Defaults to the ROM database one, or CHIP-8.
+    #[arg(short, long, value_enum)]
+    pub platform: Option<PlatformKind>,
+    /// The quirks preset used by the ambiguous instructions. Defaults to the ROM database or platform quirks.
+    #[arg(short, long, value_enum)]
+    pub quirks: Option<QuirksPreset>,
+    /// The instructions run per frame. Defaults to the ROM database tick rate, or 9.
+    #[arg(long)]
+    pub tickrate: Option<u16>,
+    /// The timing model charging the executed instructions. Movies always use the fixed timing.
+    #[arg(short, long, value_enum, default_value_t = TimingKind::Fixed, conflicts_with_all = ["record", "play"])]
+    pub timing: TimingKind,
+    /// The palette selected at start, a built-in one (default, octo, amber or green) or one from the config file. F2
+    /// cycles the palettes while running. Defaults to the config file one, or the ROM database colours.
+    #[arg(long)]
+    pub palette: Option<String>,
+    /// The `#rrggbb` colour replacing the foreground of the starting palette.
+    #[arg(long, value_parser = parse_color)]
+    pub foreground: Option<Color>,
+    /// The `#rrggbb` colour replacing the background of the starting palette.
+    #[arg(long, value_parser = parse_color)]
+    pub background: Option<Color>,
+    /// How the pixels turned off linger on the screen, hiding the flicker of sprites drawn with XOR.
+    #[arg(long, value_enum, default_value_t = PersistenceKind::Off)]
+    pub persistence: PersistenceKind,
+    /// The number of frames taken by the pixels to fade out with the `fade` persistence.
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..))]
+    pub fade_frames: u8,
+    /// The shape of the beep wave.
+    #[arg(long, value_enum, default_value_t = WaveformKind::Sine)]
+    pub waveform: WaveformKind,
+    /// The beep frequency, in Hz.
+    #[arg(long, default_value_t = DEFAULT_FREQUENCY, value_parser = parse_frequency)]
+    pub frequency: f32,
+    /// The beep volume, from 0 to 1.
+    #[arg(long, default_value_t = DEFAULT_VOLUME, value_parser = parse_volume)]
+    pub volume: f32,
+    /// The time taken by the beep to fade in, in milliseconds.
+    #[arg(long, default_value_t = DEFAULT_ATTACK.as_millis() as u64)]
+    pub attack: u64,
+    /// The time taken by the beep to fade out, in milliseconds.
+    #[arg(long, default_value_t = DEFAULT_RELEASE.as_millis() as u64)]
+    pub release: u64,
+    /// The number of frames kept to run the game backwards while holding Backspace. Zero disables rewinding.
+    #[arg(short, long, default_value_t = 600)]
+    pub rewind: usize,
+    /// The seed of the random number generator. A random one is used when missing.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// A movie file where the keys pressed on every frame are recorded, written on exit.
+    #[arg(long, conflicts_with = "play")]
+    pub record: Option<PathBuf>,
+    /// A movie file to be replayed. Its seed, platform and quirks replace the provided ones.
+    #[arg(long)]
+    pub play: Option<PathBuf>,
+    /// A file where every executed instruction is traced, with the machine state before executing it.
+    #[arg(long, value_parser)]
+    pub trace: Option<PathBuf>,
+    /// A TOML config file with the key bindings. Defaults to `config.toml` inside the user `r8` config directory.
+    #[arg(long, value_parser)]
+    pub config: Option<PathBuf>,
+    /// A ROM database in the chip-8-database `programs.json` layout, with the recommended settings of known ROMs.
+    /// Defaults to the config file one, or `programs.json` inside the user `r8` config directory.
+    #[arg(long, value_parser)]
+    pub database: Option<PathBuf>,
+}
+
+/// The tools available as subcommands.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Assembles a source file into a ROM.
+    Asm(AsmArgs),
+    /// Prints the disassembly listing of a ROM.
+    Disasm(DisasmArgs),
+    /// Runs a ROM without graphics or audio, dumping the final screen and registers.
+    Headless(HeadlessArgs),
+    /// Runs a ROM inside the terminal. F6 pauses and resumes, F7 steps a single instruction while paused.
+    Tui(TuiArgs),
+}
+
+/// The `asm` subcommand arguments.
+#[derive(Args, Debug)]
+pub struct AsmArgs {
+    /// A source file to be assembled.
+    #[arg(value_parser)]
+    pub source: PathBuf,
+    /// The ROM file to be written.
+    #[arg(short, long, value_parser)]
+    pub output: PathBuf,
+}
+
+/// The `disasm` subcommand arguments.
+#[derive(Args, Debug)]
+pub struct DisasmArgs {
+    /// A ROM file to be disassembled.
+    #[arg(value_parser)]
+    pub rom: PathBuf,
+    /// The memory address where the ROM is loaded, in decimal or `0x` prefixed hexadecimal.
+    #[arg(short, long, default_value = "0x200", value_parser = parse_address)]
+    pub origin: usize,
+}
+
+/// The `headless` subcommand arguments.
+#[derive(Args, Debug)]
+pub struct HeadlessArgs {
+    /// A ROM file to be run.
+    #[arg(value_parser)]
+    pub rom: PathBuf,
+    /// The number of instructions to be executed. The timers are updated every 9 instructions, or whenever the machine
+    /// waits for a key or the next frame, which doesn't count as an instruction.
+    #[arg(short, long)]
+    pub cycles: u64,
+    /// A script of the keys pressed, with lines like `<cycle> [key...]` holding the listed hexadecimal keys from that
+    /// executed instruction on. A machine waiting for a key skips to the next line.
+    #[arg(short, long, value_parser)]
+    pub keys: Option<PathBuf>,
+    /// A file where the final screen is written, as PBM, PNG or ASCII art depending on its `.pbm`, `.png` or other
+    /// extension.
+    #[arg(long, value_parser)]
+    pub dump_screen: Option<PathBuf>,
+    /// A file where the final registers are written as JSON. They are printed when missing.
+    #[arg(long, value_parser)]
+    pub dump_registers: Option<PathBuf>,
+    /// The platform, or instruction set, the ROM was written for.
+    #[arg(short, long, value_enum, default_value_t = PlatformKind::Chip8)]
+    pub platform: PlatformKind,
+    /// The quirks preset used by the ambiguous instructions. Defaults to the platform quirks.
+    #[arg(short, long, value_enum)]
+    pub quirks: Option<QuirksPreset>,
+    /// The seed of the random number generator.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+    /// The timing model charging the executed instructions.
+    #[arg(short, long, value_enum, default_value_t = TimingKind::Fixed)]
+    pub timing: TimingKind,
+    /// A file where every executed instruction is traced, with the machine state before executing it.
+    #[arg(long, value_parser)]
+    pub trace: Option<PathBuf>,
+}
+
+/// The `tui` subcommand arguments.
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    /// A ROM file to be loaded.
+    #[arg(value_parser)]
     pub rom: PathBuf,
-    /// A value to scale every pixel from the screen.
-    #[arg(short, long, default_value_t = 8)]
-    pub scale: u32,
+    /// The characters used to draw the screen.
+    #[arg(short, long, value_enum, default_value_t = Glyphs::HalfBlock)]
+    pub glyphs: Glyphs,
     /// A desirable speed of the interpreter.
     #[arg(short('S'), long, default_value_t = 1.0)]
     pub speed: f64,
+    /// The platform, or instruction set, the ROM was written for.
+    #[arg(short, long, value_enum, default_value_t = PlatformKind::Chip8)]
+    pub platform: PlatformKind,
+    /// The quirks preset used by the ambiguous instructions. Defaults to the platform quirks.
+    #[arg(short, long, value_enum)]
+    pub quirks: Option<QuirksPreset>,
+    /// The seed of the random number generator. A random one is used when missing.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// The timing model charging the executed instructions.
+    #[arg(short, long, value_enum, default_value_t = TimingKind::Fixed)]
+    pub timing: TimingKind,
+    /// A file where every executed instruction is traced, with the machine state before executing it.
+    #[arg(long, value_parser)]
+    pub trace: Option<PathBuf>,
+    /// A TOML config file with the key bindings. Defaults to `config.toml` inside the user `r8` config directory.
+    #[arg(long, value_parser)]
+    pub config: Option<PathBuf>,
+}
+
+/// The characters used to draw the screen in the terminal.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Glyphs {
+    /// Half-block characters, two pixels per character.
+    HalfBlock,
+    /// Braille characters, eight pixels per character.
+    Braille,
+}
+
+/// Parses a memory address, in decimal or `0x` prefixed hexadecimal.
+fn parse_address(value: &str) -> Result<usize, String> {
+    let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+
+    parsed.map_err(|err| format!("invalid address `{}`: {}", value, err))
+}
+
+impl CmdArgs {
+    /// Returns the beep tone configured by the arguments.
+    pub fn tone(&self) -> Tone {
+        Tone {
+            waveform: self.waveform.into(),
+            frequency: self.frequency,
+            volume: self.volume,
+            attack: Duration::from_millis(self.attack),
+            release: Duration::from_millis(self.release),
+        }
+    }
+}
+
+/// Parses a beep frequency, a positive number of Hz up to 20kHz.
+fn parse_frequency(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(frequency) if frequency > 0.0 && frequency <= 20_000.0 => Ok(frequency),
+        Ok(_) => Err(format!("frequency `{}` is outside 0 to 20000Hz", value)),
+        Err(err) => Err(format!("invalid frequency `{}`: {}", value, err)),
+    }
+}
+
+/// Parses a beep volume, from 0 to 1.
+fn parse_volume(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(volume) if (0.0..=1.0).contains(&volume) => Ok(volume),
+        Ok(_) => Err(format!("volume `{}` is outside 0 to 1", value)),
+        Err(err) => Err(format!("invalid volume `{}`: {}", value, err)),
+    }
+}
+
+/// The platforms supported by the interpreter.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum PlatformKind {
+    /// The original CHIP-8.
+    Chip8,
+    /// The SUPER-CHIP 1.1.
+    Schip,
+    /// The XO-CHIP extension.
+    Xochip,
+}
+
+impl From<PlatformKind> for Platform {
+    fn from(kind: PlatformKind) -> Self {
+        match kind {
+            PlatformKind::Chip8 => Platform::Chip8,
+            PlatformKind::Schip => Platform::SuperChip,
+            PlatformKind::Xochip => Platform::XoChip,
+        }
+    }
+}
+
+/// The quirks presets of the known CHIP-8 interpreters.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum QuirksPreset {
+    /// The original COSMAC VIP interpreter.
+    Vip,
+    /// The CHIP-48 interpreter.
+    Chip48,
+    /// The SUPER-CHIP 1.1 interpreter.
+    Schip,
+    /// The XO-CHIP extension.
+    Xochip,
+}
+
+impl From<QuirksPreset> for Quirks {
+    fn from(preset: QuirksPreset) -> Self {
+        match preset {
+            QuirksPreset::Vip => Quirks::cosmac_vip(),
+            QuirksPreset::Chip48 => Quirks::chip48(),
+            QuirksPreset::Schip => Quirks::super_chip(),
+            QuirksPreset::Xochip => Quirks::xo_chip(),
+        }
+    }
+}
+
+/// The screen persistence models supported by the interpreter.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum PersistenceKind {
+    /// Pixels turn off at once.
+    Off,
+    /// Pixels turned off fade out over `--fade-frames` frames, like a CRT phosphor.
+    Fade,
+    /// Every frame is blended with the previous one.
+    Blend,
+}
+
+impl PersistenceKind {
+    /// Returns the persistence, with pixels fading over `fade_frames` frames.
+    pub fn persistence(self, fade_frames: u8) -> Persistence {
+        match self {
+            PersistenceKind::Off => Persistence::Off,
+            PersistenceKind::Fade => Persistence::Fade(fade_frames),
+            PersistenceKind::Blend => Persistence::Blend,
+        }
+    }
+}
+
+/// The beep waveforms supported by the synthesizer.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum WaveformKind {
+    /// A square wave, the buzz of the original hardware.
+    Square,
+    /// A sine wave, the softest tone.
+    Sine,
+    /// A triangle wave.
+    Triangle,
+}
+
+impl From<WaveformKind> for Waveform {
+    fn from(kind: WaveformKind) -> Self {
+        match kind {
+            WaveformKind::Square => Waveform::Square,
+            WaveformKind::Sine => Waveform::Sine,
+            WaveformKind::Triangle => Waveform::Triangle,
+        }
+    }
+}
+
+/// The timing models supported by the interpreter.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum TimingKind {
+    /// A fixed number of instructions per frame.
+    Fixed,
+    /// The COSMAC VIP cycle costs, where drawing waits for the display interrupt.
+    Vip,
+}
+
+impl From<TimingKind> for Timing {
+    fn from(kind: TimingKind) -> Self {
+        match kind {
+            TimingKind::Fixed => Timing::Fixed,
+            TimingKind::Vip => Timing::CosmacVip,
+        }
+    }
 }
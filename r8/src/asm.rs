@@ -0,0 +1,22 @@
+//! The `asm` subcommand, assembling a source file into a ROM.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use log::debug;
+use r8lib::assemble;
+
+use crate::args::AsmArgs;
+
+pub fn run(args: &AsmArgs) -> Result<()> {
+    debug!("asm, source={:?}, output={:?}", args.source, args.output);
+
+    let source = fs::read_to_string(&args.source)
+        .with_context(|| format!("Missing or invalid source file provided: {}", args.source.display()))?;
+
+    let rom = assemble(&source).with_context(|| format!("Assembling {} failed", args.source.display()))?;
+
+    fs::write(&args.output, rom).with_context(|| format!("Writing {} failed", args.output.display()))?;
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! Screenshots and animated recordings of the screen, painted with the active palette.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use gif::{Encoder, Frame, Repeat};
+use log::debug;
+use r8lib::Vram;
+
+use crate::palette::Palette;
+
+/// The GIF frame delays, in hundredths of a second. GIF can't delay a 60th of a second, so they alternate to keep
+/// 3 frames every 5 hundredths.
+const GIF_FRAME_DELAYS: [u16; 3] = [2, 2, 1];
+
+/// Returns the palette index of every pixel of the screen scaled to `width` by `height`, picking the nearest pixel.
+fn scale_pixels(vram: &Vram, width: usize, height: usize) -> Vec<u8> {
+    let (src_width, src_height) = (vram.width(), vram.height());
+
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| vram[y * src_height / height * src_width + x * src_width / width] & 0b11)
+        .collect()
+}
+
+/// Saves the screen as a PNG image, every pixel scaled by `scale` and painted with `palette`.
+pub fn save_screenshot(path: &Path, vram: &Vram, palette: &Palette, scale: u32) -> Result<()> {
+    debug!("save_screenshot, path={:?}, scale={}", path, scale);
+
+    let (width, height) = (vram.width() * scale as usize, vram.height() * scale as usize);
+    let data: Vec<u8> = scale_pixels(vram, width, height)
+        .into_iter()
+        .flat_map(|pixel| palette.colors[pixel as usize])
+        .collect();
+
+    let file = File::create(path).with_context(|| format!("Creating {} failed", path.display()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+
+    Ok(())
+}
+
+/// An animated GIF recording of the screen, taking a frame every 60Hz tick.
+///
+/// Its size is fixed when started, so frames drawn in another resolution are scaled to fit.
+pub struct GifRecording {
+    /// The recorded file.
+    path: PathBuf,
+    /// The GIF encoder writing the file.
+    encoder: Encoder<BufWriter<File>>,
+    /// The GIF width, in pixels.
+    width: u16,
+    /// The GIF height, in pixels.
+    height: u16,
+    /// The number of recorded frames.
+    frames: usize,
+}
+
+impl GifRecording {
+    /// Starts recording to the file at `path`, with the size of the screen scaled by `scale`.
+    pub fn start(path: &Path, vram: &Vram, scale: u32) -> Result<Self> {
+        debug!("gif_recording_start, path={:?}, scale={}", path, scale);
+
+        let (Ok(width), Ok(height)) = (
+            u16::try_from(vram.width() * scale as usize),
+            u16::try_from(vram.height() * scale as usize),
+        ) else {
+            bail!("the scale {} is too large for a GIF", scale);
+        };
+
+        let file = File::create(path).with_context(|| format!("Creating {} failed", path.display()))?;
+        let mut encoder = Encoder::new(BufWriter::new(file), width, height, &[])?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            encoder,
+            width,
+            height,
+            frames: 0,
+        })
+    }
+
+    /// Adds the screen as the next frame, painted with `palette`.
+    pub fn add_frame(&mut self, vram: &Vram, palette: &Palette) -> Result<()> {
+        let pixels = scale_pixels(vram, self.width as usize, self.height as usize);
+        let colors: Vec<u8> = palette.colors.iter().flat_map(|color| &color[..3]).copied().collect();
+
+        let mut frame = Frame::from_palette_pixels(self.width, self.height, &pixels, &colors, None);
+        frame.delay = GIF_FRAME_DELAYS[self.frames % GIF_FRAME_DELAYS.len()];
+        self.encoder.write_frame(&frame)?;
+        self.frames += 1;
+
+        Ok(())
+    }
+
+    /// Finishes the recording, returning the recorded file path.
+    pub fn finish(self) -> Result<PathBuf> {
+        debug!("gif_recording_finish, path={:?}, frames={}", self.path, self.frames);
+
+        self.encoder.into_inner()?.flush()?;
+
+        Ok(self.path)
+    }
+}
+
+#[cfg(test)]
+mod test_capture {
+    use super::*;
+
+    #[test]
+    fn test_scale_pixels() {
+        let mut vram = Vram::default();
+        let (src_width, src_height) = (vram.width(), vram.height());
+        vram[0] = 0b01;
+        vram[src_width + 1] = 0b10;
+        vram[src_width * src_height - 1] = 0b11;
+
+        let (width, height) = (vram.width() * 2, vram.height() * 2);
+        let pixels = scale_pixels(&vram, width, height);
+        assert_eq!(pixels.len(), width * height, "every scaled pixel should be returned");
+        assert_eq!(
+            [pixels[0], pixels[1], pixels[width], pixels[width + 1]],
+            [0b01; 4],
+            "first pixel should be scaled to 2x2"
+        );
+        assert_eq!(pixels[2], 0, "background should be kept");
+        assert_eq!(
+            [pixels[width * 2 + 2], pixels[width * 3 + 3]],
+            [0b10; 2],
+            "second plane pixel should be scaled to 2x2"
+        );
+        assert_eq!(pixels[width * height - 1], 0b11, "last pixel should be scaled to 2x2");
+        assert_eq!(
+            pixels.iter().filter(|&&pixel| pixel != 0).count(),
+            12,
+            "other pixels should be background"
+        );
+
+        let pixels = scale_pixels(&vram, vram.width() / 2, vram.height() / 2);
+        assert_eq!(
+            pixels.len(),
+            vram.width() * vram.height() / 4,
+            "every shrunk pixel should be returned"
+        );
+        assert_eq!(pixels[0], 0b01, "shrunk pixels should pick the nearest pixel");
+        assert_eq!(
+            pixels.iter().filter(|&&pixel| pixel != 0).count(),
+            1,
+            "skipped pixels should not be picked"
+        );
+    }
+
+    #[test]
+    fn test_gif_frame_delays() {
+        let hundredths: u16 = GIF_FRAME_DELAYS.iter().sum();
+
+        assert_eq!(
+            hundredths as usize * 60,
+            GIF_FRAME_DELAYS.len() * 100,
+            "frame delays should average a 60th of a second"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! The user configuration, loaded from a TOML file.
+//!
+//! The file binds keyboard keys to the CHIP-8 keys, by their hexadecimal digit, with per-ROM overrides keyed by the
+//! ROM file name. It may also point to the ROM database, pick the starting palette and its colours, and add custom
+//! palettes of two to four colours, the background first:
+//!
+//! ```toml
+//! database = "/home/user/chip-8-database/database/programs.json"
+//! palette = "ice"
+//! foreground = "#e0f8ff"
+//!
+//! [palettes]
+//! ice = ["#102030", "#80c0ff", "#4060a0", "#c0e0ff"]
+//!
+//! [keys]
+//! 4 = "a"
+//! 6 = "e"
+//!
+//! [roms."PONG.ch8".keys]
+//! 1 = "up"
+//! 4 = "down"
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+use serde::Deserialize;
+
+use crate::layout::KeyBindings;
+use crate::palette::{parse_color, Color, Palette};
+
+/// The config file name, inside the `r8` user configuration directory.
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The user configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The ROM database file, in the chip-8-database `programs.json` layout.
+    pub database: Option<PathBuf>,
+    /// The name of the palette selected at start.
+    pub palette: Option<String>,
+    /// The `#rrggbb` colour replacing the foreground of the starting palette.
+    foreground: Option<String>,
+    /// The `#rrggbb` colour replacing the background of the starting palette.
+    background: Option<String>,
+    /// The custom palettes, with two to four `#rrggbb` colours each, the background first.
+    #[serde(default)]
+    palettes: BTreeMap<String, Vec<String>>,
+    /// The keyboard key names bound to the CHIP-8 keys, replacing the default ones.
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+    /// The per-ROM overrides, keyed by the ROM file name.
+    #[serde(default)]
+    roms: BTreeMap<String, RomConfig>,
+}
+
+/// The configuration overrides of a single ROM.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RomConfig {
+    /// The keyboard key names bound to the CHIP-8 keys, replacing the global ones.
+    #[serde(default)]
+    keys: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Loads the config file at `path`, or the default one when missing.
+    ///
+    /// A missing default config file is the same as an empty one, but a missing provided file is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        debug!("config_load, path={:?}", path);
+
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && !required => return Ok(Self::default()),
+            Err(err) => return Err(err).with_context(|| format!("Reading config file {} failed", path.display())),
+        };
+
+        Self::parse(&source).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// Parses the TOML config in `source`, checking its key bindings and colours.
+    fn parse(source: &str) -> Result<Self> {
+        let config: Self = toml::from_str(source)?;
+        config.key_bindings(None)?;
+        config.palettes().and(config.foreground()).and(config.background())?;
+        for name in config.roms.keys() {
+            config
+                .key_bindings(Some(name))
+                .with_context(|| format!("in [roms.\"{}\"]", name))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the key bindings of the provided ROM, applying the global bindings and then the ROM overrides.
+    pub fn rom_key_bindings(&self, rom: &Path) -> Result<KeyBindings> {
+        let mut bindings = self.global_key_bindings()?;
+        self.apply_rom_key_bindings(rom, &mut bindings)?;
+
+        Ok(bindings)
+    }
+
+    /// Returns the global key bindings, without the ROM overrides.
+    pub fn global_key_bindings(&self) -> Result<KeyBindings> {
+        self.key_bindings(None)
+    }
+
+    /// Applies the key binding overrides of the provided ROM.
+    pub fn apply_rom_key_bindings(&self, rom: &Path, bindings: &mut KeyBindings) -> Result<()> {
+        let name = rom.file_name().and_then(|name| name.to_str());
+
+        if let Some(rom) = name.and_then(|name| self.roms.get(name)) {
+            for (chip8_key, name) in rom.keys.iter() {
+                bindings.bind(chip8_key, name)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the custom palettes.
+    pub fn palettes(&self) -> Result<Vec<Palette>> {
+        self.palettes
+            .iter()
+            .map(|(name, colors)| {
+                if !(2..=4).contains(&colors.len()) {
+                    bail!("palette `{}` has {} colours, expected 2 to 4", name, colors.len());
+                }
+                let colors = colors
+                    .iter()
+                    .map(|color| parse_color(color))
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Palette::new(name, &colors))
+            })
+            .collect()
+    }
+
+    /// Returns the colour replacing the foreground of the starting palette.
+    pub fn foreground(&self) -> Result<Option<Color>> {
+        self.foreground.as_deref().map(parse_color).transpose()
+    }
+
+    /// Returns the colour replacing the background of the starting palette.
+    pub fn background(&self) -> Result<Option<Color>> {
+        self.background.as_deref().map(parse_color).transpose()
+    }
+
+    /// Returns the key bindings of the ROM file named `rom`, or the global ones when missing.
+    fn key_bindings(&self, rom: Option<&str>) -> Result<KeyBindings> {
+        let mut bindings = KeyBindings::default();
+
+        let overrides = rom.and_then(|name| self.roms.get(name)).map(|rom| &rom.keys);
+        for (chip8_key, name) in self.keys.iter().chain(overrides.into_iter().flatten()) {
+            bindings.bind(chip8_key, name)?;
+        }
+
+        Ok(bindings)
+    }
+}
+
+/// Returns the default config file path, inside the user configuration directory.
+pub fn default_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(CONFIG_FILE_NAME))
+}
+
+/// Returns the `r8` user configuration directory.
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("r8"))
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+
+    use r8lib::Key;
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r##"
+            database = "/tmp/programs.json"
+            palette = "ice"
+            foreground = "#e0f8ff"
+
+            [palettes]
+            ice = ["#102030", "#80c0ff"]
+
+            [keys]
+            4 = "a"
+            "##,
+        )
+        .expect("config should be parsed");
+
+        assert_eq!(
+            config.database,
+            Some(PathBuf::from("/tmp/programs.json")),
+            "database path should be parsed"
+        );
+        assert_eq!(config.palette.as_deref(), Some("ice"), "palette name should be parsed");
+        assert_eq!(
+            config.foreground().unwrap(),
+            Some([0xE0, 0xF8, 0xFF, 0xFF]),
+            "foreground should be parsed"
+        );
+        assert_eq!(config.background().unwrap(), None, "background should be missing");
+        assert_eq!(
+            config.palettes().unwrap(),
+            [Palette::new(
+                "ice",
+                &[[0x10, 0x20, 0x30, 0xFF], [0x80, 0xC0, 0xFF, 0xFF]]
+            )],
+            "custom palettes should be parsed"
+        );
+        assert_eq!(
+            config.global_key_bindings().unwrap().key("a"),
+            Some(Key::_4),
+            "a should be bound to key 4"
+        );
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let err = Config::parse("keys = {}\nspeed = 10").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `speed`"),
+            "unknown field should be rejected: {}",
+            err
+        );
+
+        let err = Config::parse("[roms.\"PONG.ch8\"]\npalette = \"amber\"").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `palette`"),
+            "unknown rom field should be rejected: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_rom_key_bindings() {
+        let config = Config::parse(
+            r#"
+            [keys]
+            4 = "a"
+            6 = "e"
+
+            [roms."PONG.ch8".keys]
+            4 = "up"
+            "#,
+        )
+        .expect("config should be parsed");
+
+        let bindings = config
+            .rom_key_bindings(Path::new("/roms/PONG.ch8"))
+            .expect("bindings should be valid");
+        assert_eq!(
+            bindings.key("up"),
+            Some(Key::_4),
+            "rom override should bind up to key 4"
+        );
+        assert_eq!(
+            bindings.key("a"),
+            None,
+            "rom override should replace the global binding"
+        );
+        assert_eq!(bindings.key("e"), Some(Key::_6), "global binding should be kept");
+        assert_eq!(bindings.key("1"), Some(Key::_1), "default binding should be kept");
+
+        let bindings = config
+            .rom_key_bindings(Path::new("/roms/TETRIS.ch8"))
+            .expect("bindings should be valid");
+        assert_eq!(
+            bindings.key("a"),
+            Some(Key::_4),
+            "other roms should keep the global binding"
+        );
+        assert_eq!(bindings.key("up"), None, "other roms should not get the override");
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let err = Config::parse("[keys]\n4 = \"mouse\"").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("unknown key name `mouse` bound to CHIP-8 key 4, expected a letter, a digit or one of: "),
+            "unknown key name should be rejected: {}",
+            err
+        );
+
+        let err = Config::parse("[roms.\"PONG.ch8\".keys]\nG = \"a\"").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "in [roms.\"PONG.ch8\"]: unknown CHIP-8 key `G`, expected a hexadecimal digit from 0 to F",
+            "unknown CHIP-8 key should be rejected with its rom"
+        );
+    }
+
+    #[test]
+    fn test_invalid_palette() {
+        let err = Config::parse("[palettes]\nmono = [\"#000000\"]").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "palette `mono` has 1 colours, expected 2 to 4",
+            "short palette should be rejected"
+        );
+
+        let err = Config::parse("background = \"black\"").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid colour `black`, expected `#rrggbb`",
+            "invalid colour should be rejected"
+        );
+    }
+}
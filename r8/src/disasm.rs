@@ -0,0 +1,22 @@
+//! The `disasm` subcommand, printing the disassembly listing of a ROM.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use log::debug;
+use r8lib::disassemble;
+
+use crate::args::DisasmArgs;
+
+pub fn run(args: &DisasmArgs) -> Result<()> {
+    debug!("disasm, rom={:?}, origin={:#06x}", args.rom, args.origin);
+
+    let rom =
+        fs::read(&args.rom).with_context(|| format!("Missing or invalid ROM file provided: {}", args.rom.display()))?;
+
+    for line in disassemble(&rom, args.origin) {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+//! The `headless` subcommand, running a ROM without graphics or audio and dumping its final state.
+
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use log::{debug, info};
+use r8lib::{Keys, Machine, StepOutcome, Timing, Vram};
+
+use crate::args::HeadlessArgs;
+use crate::{file_tracer, DEFAULT_INSTRUCTIONS_PER_FRAME};
+
+/// The grayscale value of every pixel value, matching the window palette.
+const GRAYSCALE: [u8; 4] = [0x00, 0xFF, 0x55, 0xAA];
+/// The ASCII art character of every pixel value.
+const ASCII: [char; 4] = ['.', '#', '+', '*'];
+
+pub fn run(args: &HeadlessArgs) -> Result<()> {
+    debug!("headless, args={:?}", args);
+
+    let rom =
+        fs::read(&args.rom).with_context(|| format!("Missing or invalid ROM file provided: {}", args.rom.display()))?;
+    let script = match &args.keys {
+        Some(path) => {
+            let source = fs::read_to_string(path)
+                .with_context(|| format!("Missing or invalid keys script provided: {}", path.display()))?;
+            parse_key_script(&source).with_context(|| format!("Invalid keys script {}", path.display()))?
+        }
+        None => Vec::new(),
+    };
+
+    let mut machine = Machine::with_seed(args.seed);
+    machine.set_platform(args.platform.into());
+    if let Some(preset) = args.quirks {
+        machine.set_quirks(preset.into());
+    }
+    machine.set_timing(args.timing.into());
+    if let Some(path) = &args.trace {
+        machine.set_tracer(Some(file_tracer(path)?));
+    }
+    machine
+        .load_rom(&rom)
+        .with_context(|| format!("Loading ROM {} failed", args.rom.display()))?;
+
+    let mut keys = Keys::default();
+    let mut events = script.iter().peekable();
+    let mut cycles = 0;
+    let mut frame_cycles = 0;
+
+    while cycles < args.cycles {
+        while let Some((_, pressed)) = events.next_if(|(cycle, _)| *cycle <= cycles) {
+            keys = *pressed;
+        }
+
+        let outcome = machine
+            .step(&keys)
+            .with_context(|| format!("Machine fault after {} cycles", cycles))?;
+
+        // Waiting steps don't execute anything, so like a frame of the window they end the frame instead.
+        let frame_end = match outcome {
+            StepOutcome::Executed | StepOutcome::Redraw => {
+                cycles += 1;
+                frame_cycles += 1;
+                // The COSMAC VIP timing decides itself when a frame ends, by waiting for the next one.
+                machine.timing() == Timing::Fixed && frame_cycles == DEFAULT_INSTRUCTIONS_PER_FRAME
+            }
+            StepOutcome::WaitingInput => {
+                // The keys won't change before the next script line, so it's pressed right away.
+                let Some((_, pressed)) = events.next() else {
+                    info!("machine waiting for a key after {} cycles, with no keys left", cycles);
+                    break;
+                };
+                keys = *pressed;
+                true
+            }
+            StepOutcome::WaitingDisplay => true,
+            StepOutcome::Exited => {
+                info!("machine exited after {} cycles", cycles);
+                break;
+            }
+        };
+        if frame_end {
+            machine.update_timers();
+            frame_cycles = 0;
+        }
+    }
+
+    if let Some(tracer) = machine.tracer_mut() {
+        tracer.flush().context("Writing the trace failed")?;
+    }
+
+    if let Some(path) = &args.dump_screen {
+        dump_screen(machine.vram_as_ref(), path).with_context(|| format!("Writing {} failed", path.display()))?;
+    }
+
+    let registers = registers_json(&machine, cycles);
+    match &args.dump_registers {
+        Some(path) => fs::write(path, registers).with_context(|| format!("Writing {} failed", path.display()))?,
+        None => print!("{}", registers),
+    }
+
+    Ok(())
+}
+
+/// Parses a keys script into the keys pressed from each cycle on, sorted by cycle.
+///
+/// Every line is a cycle followed by the hexadecimal keys held from that cycle on, releasing every other key. Lines are
+/// sorted by cycle, keeping their order on the same cycle. Comments start with `#`.
+fn parse_key_script(source: &str) -> Result<Vec<(u64, Keys)>> {
+    let mut events = Vec::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let mut tokens = line.split('#').next().unwrap_or_default().split_whitespace();
+
+        let Some(cycle) = tokens.next() else {
+            continue;
+        };
+        let Ok(cycle) = cycle.parse::<u64>() else {
+            bail!("line {}: invalid cycle `{}`", idx + 1, cycle);
+        };
+
+        let mut keys = Keys::default();
+        for token in tokens {
+            match u8::from_str_radix(token, 16) {
+                Ok(key) if token.len() == 1 => keys[key as usize] = true,
+                _ => bail!("line {}: invalid key `{}`", idx + 1, token),
+            }
+        }
+
+        events.push((cycle, keys));
+    }
+
+    events.sort_by_key(|(cycle, _)| *cycle);
+
+    Ok(events)
+}
+
+/// Writes the screen into the provided file, as PBM, PNG or ASCII art depending on its extension.
+fn dump_screen(vram: &Vram, path: &Path) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("pbm") => fs::write(path, screen_pbm(vram))?,
+        Some("png") => write_png(vram, path)?,
+        _ => fs::write(path, screen_ascii(vram))?,
+    }
+
+    Ok(())
+}
+
+/// Renders the screen as a plain PBM image, where any lit plane is a black pixel.
+fn screen_pbm(vram: &Vram) -> String {
+    let mut out = format!("P1\n{} {}\n", vram.width(), vram.height());
+
+    vram.chunks(vram.width()).take(vram.height()).for_each(|row| {
+        let line: Vec<&str> = row.iter().map(|&pixel| if pixel != 0 { "1" } else { "0" }).collect();
+        out.push_str(&line.join(" "));
+        out.push('\n');
+    });
+
+    out
+}
+
+/// Renders the screen as ASCII art, one character per pixel.
+fn screen_ascii(vram: &Vram) -> String {
+    let mut out = String::with_capacity((vram.width() + 1) * vram.height());
+
+    vram.chunks(vram.width()).take(vram.height()).for_each(|row| {
+        out.extend(row.iter().map(|&pixel| ASCII[pixel as usize & 0b11]));
+        out.push('\n');
+    });
+
+    out
+}
+
+/// Writes the screen as a grayscale PNG image.
+fn write_png(vram: &Vram, path: &Path) -> Result<()> {
+    let data: Vec<u8> = vram[..vram.width() * vram.height()]
+        .iter()
+        .map(|&pixel| GRAYSCALE[pixel as usize & 0b11])
+        .collect();
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        vram.width() as u32,
+        vram.height() as u32,
+    );
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+
+    Ok(())
+}
+
+/// Renders the machine registers as JSON.
+fn registers_json(machine: &Machine, cycles: u64) -> String {
+    let state = machine.state();
+    let v: Vec<String> = state.v().iter().map(u8::to_string).collect();
+
+    format!(
+        "{{\n  \"cycles\": {},\n  \"halted\": {},\n  \"pc\": {},\n  \"i\": {},\n  \"sp\": {},\n  \"dt\": {},\n  \"st\": {},\n  \"v\": [{}]\n}}\n",
+        cycles,
+        state.is_halted(),
+        state.pc(),
+        state.i(),
+        state.sp(),
+        state.dt(),
+        state.st(),
+        v.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod test_headless {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_script() {
+        let events = parse_key_script("# start\n\n120 5 a  # fire\n60 F\n120\n").expect("script should be parsed");
+
+        let mut fire = Keys::default();
+        fire[0x5] = true;
+        fire[0xA] = true;
+        let mut last = Keys::default();
+        last[0xF] = true;
+
+        assert_eq!(
+            events,
+            [(60, last), (120, fire), (120, Keys::default())],
+            "events should be sorted by cycle, keeping the line order"
+        );
+    }
+
+    #[test]
+    fn test_parse_key_script_errors() {
+        for (source, message) in [
+            ("10 1\nten 2", "line 2: invalid cycle `ten`"),
+            ("-1 1", "line 1: invalid cycle `-1`"),
+            ("10 G", "line 1: invalid key `G`"),
+            ("10 10", "line 1: invalid key `10`"),
+            ("10 1,2", "line 1: invalid key `1,2`"),
+        ] {
+            assert_eq!(
+                parse_key_script(source).unwrap_err().to_string(),
+                message,
+                "invalid script should be rejected"
+            );
+        }
+    }
+}
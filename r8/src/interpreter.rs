@@ -1,19 +1,34 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use log::debug;
-use r8lib::{Key, Keyboard, Machine};
+use r8lib::{
+    AudioPattern, FrameResult, Framebuffer, Key, Keyboard, Machine, MachineError, Movie, Persistence, Platform, Quirks,
+    Timing, Tracer, Vram,
+};
 use winit::event::VirtualKeyCode;
 use winit_input_helper::WinitInputHelper;
 
-const WHITE: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
-const BLACK: [u8; 4] = [0x00, 0x00, 0x00, 0xFF];
+use crate::layout::KeyBindings;
+use crate::palette::Palette;
+use crate::rewind::RewindBuffer;
 
 #[derive(Debug)]
 pub struct Interpreter {
     machine: Machine,
+    rom: Vec<u8>,
     keyboard: Keyboard,
     key_map: HashMap<VirtualKeyCode, Key>,
+    palette: Palette,
+    framebuffer: Framebuffer,
+    redraw: bool,
+    rewind: RewindBuffer,
+    recording: Option<Movie>,
+    playback: Option<(Movie, usize)>,
 }
 
 impl Interpreter {
@@ -22,29 +37,206 @@ impl Interpreter {
 
         let rom = fs::read(path)?;
 
-        self.machine.load_rom(&rom);
+        self.machine.load_rom(&rom)?;
+        self.rom = rom;
 
         Ok(())
     }
 
-    pub fn should_draw(&self) -> bool {
-        self.machine.should_draw()
+    pub fn save_state(&self, path: &Path) -> Result<()> {
+        debug!("interpreter_save_state, path={path:?}");
+
+        fs::write(path, self.machine.save_state())?;
+
+        Ok(())
+    }
+
+    pub fn load_state(&mut self, path: &Path) -> Result<()> {
+        debug!("interpreter_load_state, path={path:?}");
+
+        let state = fs::read(path)?;
+
+        self.machine.load_state(&state)?;
+        self.redraw = true;
+
+        Ok(())
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        debug!("interpreter_set_seed, seed={seed}");
+
+        self.machine.set_seed(seed);
+    }
+
+    pub fn start_recording(&mut self, seed: u64, instructions_per_frame: u16) {
+        debug!("interpreter_start_recording, seed={seed}, instructions_per_frame={instructions_per_frame}");
+
+        self.recording = Some(Movie::new(
+            seed,
+            &self.rom,
+            self.machine.platform(),
+            self.machine.quirks(),
+            instructions_per_frame,
+        ));
+    }
+
+    pub fn save_recording(&self, path: &Path) -> Result<()> {
+        debug!("interpreter_save_recording, path={path:?}");
+
+        if let Some(movie) = &self.recording {
+            fs::write(path, movie.to_bytes())?;
+        }
+
+        Ok(())
     }
 
-    pub fn should_beep(&self) -> bool {
-        self.machine.should_beep()
+    pub fn start_playback(&mut self, movie: Movie) -> Result<()> {
+        debug!("interpreter_start_playback, frames={}", movie.frames().len());
+
+        if !movie.matches_rom(&self.rom) {
+            bail!("the movie was recorded with another ROM");
+        }
+
+        self.playback = Some((movie, 0));
+
+        Ok(())
     }
 
-    pub fn update(&mut self) {
-        debug!("interpreter_update");
+    pub fn instructions_per_frame(&self) -> Option<u16> {
+        self.playback.as_ref().map(|(movie, _)| movie.instructions_per_frame())
+    }
+
+    pub fn run_frame(&mut self, instructions: u16) -> Result<FrameResult, MachineError> {
+        debug!("interpreter_run_frame, instructions={instructions}");
+
+        let keys = match &mut self.playback {
+            Some((movie, frame)) => match movie.frames().get(*frame) {
+                Some(keys) => {
+                    *frame += 1;
+                    *keys
+                }
+                None => {
+                    return Ok(FrameResult {
+                        exited: true,
+                        ..Default::default()
+                    })
+                }
+            },
+            None => *self.keyboard.keys_as_ref(),
+        };
+
+        if let Some(movie) = &mut self.recording {
+            movie.push_frame(&keys);
+        }
 
-        self.machine.step(self.keyboard.keys_as_ref());
+        let result = self.machine.run_frame(&keys, instructions)?;
+        self.redraw |= result.redraw;
+
+        Ok(result)
+    }
+
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        debug!("interpreter_set_tracer");
+
+        self.machine.set_tracer(Some(tracer));
+    }
+
+    pub fn flush_trace(&mut self) -> Result<()> {
+        if let Some(tracer) = self.machine.tracer_mut() {
+            tracer.flush()?;
+        }
+
+        Ok(())
     }
 
-    pub fn update_timers(&mut self) {
-        debug!("interpreter_update_timers");
+    pub fn set_rewind_capacity(&mut self, capacity: usize) {
+        debug!("interpreter_set_rewind_capacity, capacity={capacity}");
 
-        self.machine.update_timers()
+        self.rewind = RewindBuffer::new(capacity);
+    }
+
+    pub fn record_frame(&mut self) {
+        self.rewind.push(self.machine.save_state());
+    }
+
+    pub fn rewind_frame(&mut self) -> bool {
+        debug!("interpreter_rewind_frame");
+
+        let Some(state) = self.rewind.pop() else {
+            return false;
+        };
+
+        if let Err(err) = self.machine.load_state(state) {
+            debug!("rewind_error, error={:?}", err);
+            return false;
+        }
+        self.redraw = true;
+
+        true
+    }
+
+    pub fn set_platform(&mut self, platform: Platform) {
+        debug!("interpreter_set_platform, platform={platform:?}");
+
+        self.machine.set_platform(platform);
+    }
+
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        debug!("interpreter_set_quirks, quirks={quirks:?}");
+
+        self.machine.set_quirks(quirks);
+    }
+
+    pub fn set_timing(&mut self, timing: Timing) {
+        debug!("interpreter_set_timing, timing={timing:?}");
+
+        self.machine.set_timing(timing);
+    }
+
+    pub fn timing(&self) -> Timing {
+        self.machine.timing()
+    }
+
+    pub fn set_key_bindings(&mut self, bindings: &KeyBindings) {
+        debug!("interpreter_set_key_bindings, bindings={bindings:?}");
+
+        self.key_map = bindings.virtual_key_codes().collect();
+    }
+
+    pub fn set_palette(&mut self, palette: &Palette) {
+        debug!("interpreter_set_palette, palette={palette:?}");
+
+        self.palette = palette.clone();
+        self.redraw = true;
+    }
+
+    pub fn set_persistence(&mut self, persistence: Persistence) {
+        debug!("interpreter_set_persistence, persistence={persistence:?}");
+
+        self.framebuffer.set_persistence(persistence);
+        self.redraw = true;
+    }
+
+    pub fn audio(&self) -> Option<(AudioPattern, f32)> {
+        self.machine.audio()
+    }
+
+    pub fn vram(&self) -> &Vram {
+        self.machine.vram_as_ref()
+    }
+
+    pub fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    pub fn resolution(&self) -> (u32, u32) {
+        let vram = self.machine.vram_as_ref();
+
+        (vram.width() as u32, vram.height() as u32)
+    }
+
+    pub fn should_draw(&self) -> bool {
+        self.machine.should_draw() || self.redraw || !self.framebuffer.is_settled()
     }
 
     pub fn draw(&mut self, frame: &mut [u8]) {
@@ -54,9 +246,10 @@ impl Interpreter {
             return;
         }
 
-        for (dst, &src) in frame.chunks_exact_mut(4).zip(self.machine.vram_as_ref().iter()) {
-            dst.copy_from_slice(if src { WHITE.as_slice() } else { BLACK.as_slice() });
-        }
+        self.framebuffer.update(self.machine.vram_as_ref());
+        self.framebuffer.render(&self.palette.colors, frame);
+
+        self.redraw = false;
     }
 
     pub fn read_input(&mut self, input: &WinitInputHelper) {
@@ -77,25 +270,15 @@ impl Default for Interpreter {
     fn default() -> Self {
         Self {
             machine: Machine::default(),
+            rom: Vec::new(),
             keyboard: Keyboard::default(),
-            key_map: HashMap::from([
-                (VirtualKeyCode::Key1, Key::_1),
-                (VirtualKeyCode::Key2, Key::_2),
-                (VirtualKeyCode::Key3, Key::_3),
-                (VirtualKeyCode::Key4, Key::C),
-                (VirtualKeyCode::Q, Key::_4),
-                (VirtualKeyCode::W, Key::_5),
-                (VirtualKeyCode::E, Key::_6),
-                (VirtualKeyCode::R, Key::D),
-                (VirtualKeyCode::A, Key::_7),
-                (VirtualKeyCode::S, Key::_8),
-                (VirtualKeyCode::D, Key::_9),
-                (VirtualKeyCode::F, Key::E),
-                (VirtualKeyCode::Z, Key::A),
-                (VirtualKeyCode::X, Key::_0),
-                (VirtualKeyCode::C, Key::B),
-                (VirtualKeyCode::V, Key::F),
-            ]),
+            key_map: KeyBindings::default().virtual_key_codes().collect(),
+            palette: Palette::default(),
+            framebuffer: Framebuffer::default(),
+            redraw: false,
+            rewind: RewindBuffer::new(0),
+            recording: None,
+            playback: None,
         }
     }
 }
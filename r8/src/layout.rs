@@ -0,0 +1,309 @@
+//! The keyboard layout shared by the frontends, and the key bindings built over it.
+
+use anyhow::{bail, Result};
+use r8lib::Key;
+use winit::event::VirtualKeyCode;
+
+/// The CHIP-8 keypad laid over the `1234`, `QWER`, `ASDF` and `ZXCV` keys of a QWERTY keyboard.
+pub const KEY_LAYOUT: [(char, Key); 16] = [
+    ('1', Key::_1),
+    ('2', Key::_2),
+    ('3', Key::_3),
+    ('4', Key::C),
+    ('q', Key::_4),
+    ('w', Key::_5),
+    ('e', Key::_6),
+    ('r', Key::D),
+    ('a', Key::_7),
+    ('s', Key::_8),
+    ('d', Key::_9),
+    ('f', Key::E),
+    ('z', Key::A),
+    ('x', Key::_0),
+    ('c', Key::B),
+    ('v', Key::F),
+];
+
+/// Every CHIP-8 key, indexed by its value.
+const CHIP8_KEYS: [Key; 16] = [
+    Key::_0,
+    Key::_1,
+    Key::_2,
+    Key::_3,
+    Key::_4,
+    Key::_5,
+    Key::_6,
+    Key::_7,
+    Key::_8,
+    Key::_9,
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+];
+
+/// The keys with a name besides letters and digits, with their winit key code and the character they type, if any.
+const NAMED_KEYS: [(&str, VirtualKeyCode, Option<char>); 34] = [
+    ("space", VirtualKeyCode::Space, Some(' ')),
+    ("enter", VirtualKeyCode::Return, None),
+    ("tab", VirtualKeyCode::Tab, None),
+    ("up", VirtualKeyCode::Up, None),
+    ("down", VirtualKeyCode::Down, None),
+    ("left", VirtualKeyCode::Left, None),
+    ("right", VirtualKeyCode::Right, None),
+    ("home", VirtualKeyCode::Home, None),
+    ("end", VirtualKeyCode::End, None),
+    ("pageup", VirtualKeyCode::PageUp, None),
+    ("pagedown", VirtualKeyCode::PageDown, None),
+    ("insert", VirtualKeyCode::Insert, None),
+    ("delete", VirtualKeyCode::Delete, None),
+    ("comma", VirtualKeyCode::Comma, Some(',')),
+    ("period", VirtualKeyCode::Period, Some('.')),
+    ("slash", VirtualKeyCode::Slash, Some('/')),
+    ("semicolon", VirtualKeyCode::Semicolon, Some(';')),
+    ("apostrophe", VirtualKeyCode::Apostrophe, Some('\'')),
+    ("minus", VirtualKeyCode::Minus, Some('-')),
+    ("equals", VirtualKeyCode::Equals, Some('=')),
+    ("lbracket", VirtualKeyCode::LBracket, Some('[')),
+    ("rbracket", VirtualKeyCode::RBracket, Some(']')),
+    ("backslash", VirtualKeyCode::Backslash, Some('\\')),
+    ("grave", VirtualKeyCode::Grave, Some('`')),
+    ("numpad0", VirtualKeyCode::Numpad0, None),
+    ("numpad1", VirtualKeyCode::Numpad1, None),
+    ("numpad2", VirtualKeyCode::Numpad2, None),
+    ("numpad3", VirtualKeyCode::Numpad3, None),
+    ("numpad4", VirtualKeyCode::Numpad4, None),
+    ("numpad5", VirtualKeyCode::Numpad5, None),
+    ("numpad6", VirtualKeyCode::Numpad6, None),
+    ("numpad7", VirtualKeyCode::Numpad7, None),
+    ("numpad8", VirtualKeyCode::Numpad8, None),
+    ("numpad9", VirtualKeyCode::Numpad9, None),
+];
+
+/// The keyboard keys bound to every CHIP-8 key, by key name.
+///
+/// Key names are a single letter or digit, or one of the [`NAMED_KEYS`], ignoring their case. A keyboard key is bound to
+/// a single CHIP-8 key, while a CHIP-8 key may have several keyboard keys. The default bindings follow [`KEY_LAYOUT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// The key names bound to every CHIP-8 key, indexed by its value.
+    names: [Vec<String>; 16],
+}
+
+impl KeyBindings {
+    /// Binds the keyboard key named `name` to the CHIP-8 key written as the hexadecimal digit `chip8_key`, replacing
+    /// its other keyboard keys.
+    pub fn bind(&mut self, chip8_key: &str, name: &str) -> Result<()> {
+        let idx = match u8::from_str_radix(chip8_key, 16) {
+            Ok(idx) if chip8_key.len() == 1 => idx as usize,
+            _ => bail!(
+                "unknown CHIP-8 key `{}`, expected a hexadecimal digit from 0 to F",
+                chip8_key
+            ),
+        };
+
+        let name = checked_name(name, idx as u8)?;
+        self.names[idx].clear();
+        self.add(idx as u8, &name)
+    }
+
+    /// Binds the keyboard key named `name` to the CHIP-8 key `chip8_key`, keeping its other keyboard keys.
+    pub fn add(&mut self, chip8_key: u8, name: &str) -> Result<()> {
+        let name = checked_name(name, chip8_key)?;
+
+        for names in self.names.iter_mut() {
+            names.retain(|bound| *bound != name);
+        }
+        self.names[chip8_key as usize & 0xF].push(name);
+
+        Ok(())
+    }
+
+    /// Returns the CHIP-8 key bound to the keyboard key named `name`.
+    pub fn key(&self, name: &str) -> Option<Key> {
+        self.names
+            .iter()
+            .position(|names| names.iter().any(|bound| bound.eq_ignore_ascii_case(name)))
+            .map(|idx| CHIP8_KEYS[idx])
+    }
+
+    /// Returns the CHIP-8 key bound to the keyboard key typing the character `c`, ignoring its case.
+    pub fn char_key(&self, c: char) -> Option<Key> {
+        if c.is_ascii_alphanumeric() {
+            return self.key(c.encode_utf8(&mut [0; 4]));
+        }
+
+        let (name, _, _) = NAMED_KEYS.iter().find(|(_, _, typed)| *typed == Some(c))?;
+
+        self.key(name)
+    }
+
+    /// Returns the winit key codes bound to every CHIP-8 key.
+    pub fn virtual_key_codes(&self) -> impl Iterator<Item = (VirtualKeyCode, Key)> + '_ {
+        self.names
+            .iter()
+            .zip(CHIP8_KEYS)
+            .flat_map(|(names, key)| names.iter().map(move |name| (name, key)))
+            .filter_map(|(name, key)| Some((virtual_key_code(name)?, key)))
+    }
+}
+
+impl Default for KeyBindings {
+    /// Creates the bindings of [`KEY_LAYOUT`].
+    fn default() -> Self {
+        let mut names: [Vec<String>; 16] = Default::default();
+        for (c, key) in KEY_LAYOUT {
+            names[key as usize].push(c.to_string());
+        }
+
+        Self { names }
+    }
+}
+
+/// Returns the provided key name in lowercase, or an error naming the CHIP-8 key `chip8_key` when it's unknown.
+fn checked_name(name: &str, chip8_key: u8) -> Result<String> {
+    let name = name.to_ascii_lowercase();
+    if virtual_key_code(&name).is_none() {
+        bail!(
+            "unknown key name `{}` bound to CHIP-8 key {:X}, expected a letter, a digit or one of: {}",
+            name,
+            chip8_key,
+            NAMED_KEYS.map(|(name, _, _)| name).join(", ")
+        );
+    }
+
+    Ok(name)
+}
+
+/// Returns the winit key code of the provided key name, ignoring its case.
+pub fn virtual_key_code(name: &str) -> Option<VirtualKeyCode> {
+    let name = name.to_ascii_lowercase();
+    if let Some((_, code, _)) = NAMED_KEYS.iter().find(|(named, _, _)| *named == name) {
+        return Some(*code);
+    }
+
+    let mut chars = name.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+
+    let code = match c {
+        '0' => VirtualKeyCode::Key0,
+        '1' => VirtualKeyCode::Key1,
+        '2' => VirtualKeyCode::Key2,
+        '3' => VirtualKeyCode::Key3,
+        '4' => VirtualKeyCode::Key4,
+        '5' => VirtualKeyCode::Key5,
+        '6' => VirtualKeyCode::Key6,
+        '7' => VirtualKeyCode::Key7,
+        '8' => VirtualKeyCode::Key8,
+        '9' => VirtualKeyCode::Key9,
+        'a' => VirtualKeyCode::A,
+        'b' => VirtualKeyCode::B,
+        'c' => VirtualKeyCode::C,
+        'd' => VirtualKeyCode::D,
+        'e' => VirtualKeyCode::E,
+        'f' => VirtualKeyCode::F,
+        'g' => VirtualKeyCode::G,
+        'h' => VirtualKeyCode::H,
+        'i' => VirtualKeyCode::I,
+        'j' => VirtualKeyCode::J,
+        'k' => VirtualKeyCode::K,
+        'l' => VirtualKeyCode::L,
+        'm' => VirtualKeyCode::M,
+        'n' => VirtualKeyCode::N,
+        'o' => VirtualKeyCode::O,
+        'p' => VirtualKeyCode::P,
+        'q' => VirtualKeyCode::Q,
+        'r' => VirtualKeyCode::R,
+        's' => VirtualKeyCode::S,
+        't' => VirtualKeyCode::T,
+        'u' => VirtualKeyCode::U,
+        'v' => VirtualKeyCode::V,
+        'w' => VirtualKeyCode::W,
+        'x' => VirtualKeyCode::X,
+        'y' => VirtualKeyCode::Y,
+        'z' => VirtualKeyCode::Z,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+#[cfg(test)]
+mod test_layout {
+    use super::*;
+
+    #[test]
+    fn test_default() {
+        let bindings = KeyBindings::default();
+
+        for (c, key) in KEY_LAYOUT {
+            assert_eq!(bindings.char_key(c), Some(key), "{} should be bound by default", c);
+        }
+        assert_eq!(bindings.key("Q"), Some(Key::_4), "key names should ignore their case");
+        assert_eq!(bindings.key("space"), None, "space should not be bound by default");
+    }
+
+    #[test]
+    fn test_bind() {
+        let mut bindings = KeyBindings::default();
+
+        bindings.bind("a", "space").expect("space should be bound");
+        assert_eq!(bindings.char_key(' '), Some(Key::A), "space should be bound to key A");
+        assert_eq!(bindings.key("z"), None, "bind should replace the other keys of key A");
+
+        bindings.add(0xB, "Space").expect("space should be bound");
+        assert_eq!(
+            bindings.key("space"),
+            Some(Key::B),
+            "a keyboard key should have a single CHIP-8 key"
+        );
+
+        bindings.add(0xB, "up").expect("up should be bound");
+        assert_eq!(
+            bindings.key("c"),
+            Some(Key::B),
+            "add should keep the other keys of key B"
+        );
+        assert!(
+            bindings
+                .virtual_key_codes()
+                .any(|binding| binding == (VirtualKeyCode::Up, Key::B)),
+            "up should be bound to key B"
+        );
+    }
+
+    #[test]
+    fn test_bind_errors() {
+        let mut bindings = KeyBindings::default();
+
+        for chip8_key in ["G", "10", ""] {
+            assert_eq!(
+                bindings.bind(chip8_key, "a").unwrap_err().to_string(),
+                format!(
+                    "unknown CHIP-8 key `{}`, expected a hexadecimal digit from 0 to F",
+                    chip8_key
+                ),
+                "invalid CHIP-8 key should be rejected"
+            );
+        }
+
+        let err = bindings.bind("4", "Mouse").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "unknown key name `mouse` bound to CHIP-8 key 4, expected a letter, a digit or one of: {}",
+                NAMED_KEYS.map(|(name, _, _)| name).join(", ")
+            ),
+            "unknown key name should be rejected"
+        );
+        assert_eq!(
+            bindings,
+            KeyBindings::default(),
+            "failed bindings should keep the other keys"
+        );
+    }
+}
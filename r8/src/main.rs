@@ -1,99 +1,270 @@
 mod args;
+mod asm;
+mod capture;
+mod config;
+mod disasm;
 mod error;
+mod headless;
 mod interpreter;
+mod layout;
+mod palette;
+mod rewind;
+mod romdb;
 mod sound;
+mod terminal;
+mod window_size;
 
-use std::time::{Duration, Instant};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use capture::GifRecording;
 use clap::Parser;
+use config::Config;
 use interpreter::Interpreter;
-use log::{debug, error};
+use log::{debug, error, info};
+use palette::{Palette, Palettes};
 use pixels::{Pixels, SurfaceTexture};
+use r8lib::{Movie, Platform, Timing, Tracer};
+use romdb::{RomDatabase, RomSettings, DATABASE_FILE_NAME};
 use sound::Beep;
+use window_size::WindowSize;
 use winit::{
     dpi::LogicalSize,
     event::{Event, VirtualKeyCode},
     event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
+    window::{Fullscreen, WindowBuilder},
 };
 use winit_input_helper::WinitInputHelper;
 
-use crate::args::CmdArgs;
+use crate::args::{CmdArgs, Command};
 
 const SCREEN_WIDTH: u32 = 64;
 const SCREEN_HEIGHT: u32 = 32;
+const DEFAULT_SCALE: u32 = 8;
+/// The time without resizing before the window is snapped to a whole pixel scale.
+const SNAP_DELAY: Duration = Duration::from_millis(300);
 
 const DEFAULT_CYCLE_FREQUENCY: f64 = 540.0;
 const DEFAULT_TIMER_FREQUENCY: f64 = 60.0;
+const DEFAULT_INSTRUCTIONS_PER_FRAME: u16 = (DEFAULT_CYCLE_FREQUENCY / DEFAULT_TIMER_FREQUENCY) as u16;
+
+const SAVE_STATE_EXTENSION: &str = "state";
 
 fn main() -> Result<()> {
     env_logger::init();
 
     let args = CmdArgs::parse();
 
+    match &args.command {
+        Some(Command::Asm(asm_args)) => asm::run(asm_args),
+        Some(Command::Disasm(disasm_args)) => disasm::run(disasm_args),
+        Some(Command::Headless(headless_args)) => headless::run(headless_args),
+        Some(Command::Tui(tui_args)) => terminal::run(tui_args),
+        None => run(args),
+    }
+}
+
+fn run(args: CmdArgs) -> Result<()> {
+    let rom_path = args.rom.clone().expect("the ROM is required without a subcommand");
+
+    let movie = match &args.play {
+        Some(path) => Some(Movie::from_bytes(&fs::read(path)?)?),
+        None => None,
+    };
+    let seed = movie
+        .as_ref()
+        .map(Movie::seed)
+        .or(args.seed)
+        .unwrap_or_else(rand::random);
+    info!("seed: {}", seed);
+
+    let config = Config::load(args.config.as_deref())?;
+    let settings = match rom_settings(&args, &config, &rom_path)? {
+        Some(settings) => {
+            info!("ROM found in the database: {}", settings.title);
+            settings
+        }
+        None => RomSettings::default(),
+    };
+
     let mut interpreter = Interpreter::default();
-    if let Err(err) = interpreter.load(&args.rom) {
+    interpreter.set_seed(seed);
+    match &movie {
+        Some(movie) => {
+            interpreter.set_platform(movie.platform());
+            interpreter.set_quirks(movie.quirks());
+        }
+        None => {
+            let (platform, quirks) = match (args.platform, settings.platform) {
+                (Some(kind), _) => (kind.into(), Platform::from(kind).quirks()),
+                (None, Some(recommended)) => recommended,
+                (None, None) => (Platform::default(), Platform::default().quirks()),
+            };
+            interpreter.set_platform(platform);
+            interpreter.set_quirks(args.quirks.map_or(quirks, Into::into));
+            interpreter.set_timing(args.timing.into());
+        }
+    }
+    if let Some(path) = &args.trace {
+        interpreter.set_tracer(file_tracer(path)?);
+    }
+
+    let mut bindings = config.global_key_bindings()?;
+    settings.apply_key_bindings(&mut bindings)?;
+    config.apply_rom_key_bindings(&rom_path, &mut bindings)?;
+    interpreter.set_key_bindings(&bindings);
+    let mut palettes = palettes(&args, &config, &settings)?;
+    info!("palette: {}", palettes.current().name);
+    interpreter.set_palette(palettes.current());
+    interpreter.set_persistence(args.persistence.persistence(args.fade_frames));
+
+    if let Err(err) = interpreter.load(&rom_path) {
         debug!("load_error, error={:?}", err);
-        bail!("Missing or invalid ROM file provided: {}", args.rom.display())
+        bail!("Missing or invalid ROM file provided: {}: {}", rom_path.display(), err)
     }
 
+    let tickrate = args
+        .tickrate
+        .or(settings.tickrate)
+        .unwrap_or(DEFAULT_INSTRUCTIONS_PER_FRAME);
+
+    // Movies run a fixed number of instructions per frame, so they can be replayed exactly. Rewinding and loading
+    // states would break the recording, so they are disabled.
+    let movie_mode = movie.is_some() || args.record.is_some();
+    if let Some(movie) = movie {
+        interpreter.start_playback(movie)?;
+    } else if args.record.is_some() {
+        interpreter.start_recording(seed, tickrate);
+    } else {
+        interpreter.set_rewind_capacity(args.rewind);
+    }
+    // The COSMAC VIP timing runs until its frame cycles are spent, so the instructions count is only a bound.
+    let instructions_per_frame = match interpreter.instructions_per_frame() {
+        Some(instructions) => instructions,
+        None if interpreter.timing() != Timing::Fixed => u16::MAX,
+        None => tickrate,
+    };
+
+    let state_path = rom_path.with_extension(SAVE_STATE_EXTENSION);
+
     let event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
 
-    let size = LogicalSize::new((SCREEN_WIDTH * args.scale) as f64, (SCREEN_HEIGHT * args.scale) as f64);
+    let size = match (args.scale, WindowSize::load()) {
+        (None, Some(size)) => size.into(),
+        (scale, _) => {
+            let scale = scale.unwrap_or(DEFAULT_SCALE);
+            LogicalSize::new((SCREEN_WIDTH * scale) as f64, (SCREEN_HEIGHT * scale) as f64)
+        }
+    };
     let window = WindowBuilder::new()
         .with_title("rot8")
-        .with_resizable(false)
+        .with_resizable(true)
         .with_inner_size(size)
-        .with_min_inner_size(size)
-        .with_max_inner_size(size)
+        .with_min_inner_size(LogicalSize::new(SCREEN_WIDTH, SCREEN_HEIGHT))
         .build(&event_loop)
         .unwrap();
+    let mut saved_size = WindowSize::from(window.inner_size().to_logical::<f64>(window.scale_factor()));
+    let mut snap_time = None;
+    let mut gif_recording: Option<GifRecording> = None;
 
     let window_size = window.inner_size();
     let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
     let mut pixels = Pixels::new(SCREEN_WIDTH, SCREEN_HEIGHT, surface_texture)?;
+    let mut resolution = (SCREEN_WIDTH, SCREEN_HEIGHT);
 
-    let beep = Beep::new()?;
+    let beep = Beep::new(args.tone())?;
 
-    let target_frame_time = Duration::from_micros((1_000_000.0 / DEFAULT_CYCLE_FREQUENCY / args.speed) as u64);
-    let target_timer_time = Duration::from_micros((1_000_000.0 / DEFAULT_TIMER_FREQUENCY / args.speed) as u64);
+    let target_frame_time = Duration::from_micros((1_000_000.0 / DEFAULT_TIMER_FREQUENCY / args.speed) as u64);
     let mut frame_last_time = Instant::now();
-    let mut timer_last_time = Instant::now();
 
     event_loop.run(move |event, _, control_flow| {
         match event {
             Event::RedrawRequested(_) => {
-                if frame_last_time.elapsed() >= target_frame_time {
-                    interpreter.update();
-
-                    if interpreter.should_draw() {
-                        interpreter.draw(pixels.get_frame_mut());
+                if frame_last_time.elapsed() < target_frame_time {
+                    return;
+                }
+                frame_last_time = Instant::now();
 
-                        if pixels.render().map_err(|e| error!("rendering failed: {}", e)).is_err() {
+                let beeping = if !movie_mode && input.key_held(VirtualKeyCode::Back) {
+                    interpreter.rewind_frame();
+                    false
+                } else {
+                    match interpreter.run_frame(instructions_per_frame) {
+                        Ok(result) if result.exited => {
+                            if movie_mode {
+                                info!("movie finished");
+                            }
                             *control_flow = ControlFlow::Exit;
+                            return;
+                        }
+                        Ok(result) => {
+                            if !movie_mode {
+                                interpreter.record_frame();
+                            }
+                            result.beep
+                        }
+                        Err(err) => {
+                            error!("machine fault: {}", err);
+                            *control_flow = ControlFlow::Exit;
+                            return;
                         }
                     }
+                };
 
-                    frame_last_time = Instant::now();
-                }
+                beep.set_pattern(interpreter.audio());
+                beep.set_playing(beeping);
 
-                if timer_last_time.elapsed() >= target_timer_time {
-                    interpreter.update_timers();
+                if let Err(err) = render(&mut interpreter, &mut pixels, &mut resolution) {
+                    error!("{}", err);
+                    *control_flow = ControlFlow::Exit;
+                }
 
-                    if interpreter.should_beep() {
-                        beep.play();
-                    } else {
-                        beep.pause();
+                if let Some(recording) = &mut gif_recording {
+                    if let Err(err) = recording.add_frame(interpreter.vram(), interpreter.palette()) {
+                        error!("recording GIF failed: {}", err);
+                        gif_recording = None;
                     }
-
-                    timer_last_time = Instant::now();
                 }
             }
             Event::MainEventsCleared => {
+                // Snapping while the window is dragged would fight the user, so it waits for the resizing to stop.
+                if snap_time.is_some_and(|time: Instant| time.elapsed() >= SNAP_DELAY) {
+                    snap_time = None;
+
+                    let snapped = window_size::snap(window.inner_size(), resolution);
+                    if snapped != window.inner_size() {
+                        window.set_inner_size(snapped);
+                    }
+                }
+
                 window.request_redraw();
             }
+            Event::LoopDestroyed => {
+                if let Some(recording) = gif_recording.take() {
+                    match recording.finish() {
+                        Ok(path) => info!("GIF saved to {}", path.display()),
+                        Err(err) => error!("saving GIF failed: {}", err),
+                    }
+                }
+                if let Err(err) = saved_size.save() {
+                    error!("saving window size failed: {}", err);
+                }
+                if let Err(err) = interpreter.flush_trace() {
+                    error!("writing trace failed: {}", err);
+                }
+                if let Some(path) = &args.record {
+                    match interpreter.save_recording(path) {
+                        Ok(()) => info!("movie saved to {}", path.display()),
+                        Err(err) => error!("saving movie failed: {}", err),
+                    }
+                }
+            }
             _ => {}
         };
 
@@ -103,9 +274,153 @@ fn main() -> Result<()> {
                 return;
             }
 
+            if input.key_pressed(VirtualKeyCode::F5) {
+                match interpreter.save_state(&state_path) {
+                    Ok(()) => info!("state saved to {}", state_path.display()),
+                    Err(err) => error!("saving state failed: {}", err),
+                }
+            }
+
+            if let Some(size) = input.window_resized() {
+                if let Err(err) = pixels.resize_surface(size.width, size.height) {
+                    error!("resizing failed: {}", err);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
+                if let Err(err) = pixels.render() {
+                    error!("rendering failed: {}", err);
+                }
+
+                if window.fullscreen().is_none() && !window.is_maximized() {
+                    saved_size = size.to_logical::<f64>(window.scale_factor()).into();
+                    snap_time = Some(Instant::now());
+                }
+            }
+
+            if input.key_pressed(VirtualKeyCode::F11) {
+                let fullscreen = match window.fullscreen() {
+                    Some(_) => None,
+                    None => Some(Fullscreen::Borderless(None)),
+                };
+                window.set_fullscreen(fullscreen);
+            }
+
+            if input.key_pressed(VirtualKeyCode::F12) {
+                let path = capture_path(&rom_path, "png");
+                let scale = window_size::pixel_scale(window.inner_size(), resolution);
+                match capture::save_screenshot(&path, interpreter.vram(), interpreter.palette(), scale) {
+                    Ok(()) => info!("screenshot saved to {}", path.display()),
+                    Err(err) => error!("saving screenshot failed: {}", err),
+                }
+            }
+
+            if input.key_pressed(VirtualKeyCode::F10) {
+                match gif_recording.take() {
+                    Some(recording) => match recording.finish() {
+                        Ok(path) => info!("GIF saved to {}", path.display()),
+                        Err(err) => error!("saving GIF failed: {}", err),
+                    },
+                    None => {
+                        let path = capture_path(&rom_path, "gif");
+                        let scale = window_size::pixel_scale(window.inner_size(), resolution);
+                        match GifRecording::start(&path, interpreter.vram(), scale) {
+                            Ok(recording) => {
+                                info!("recording GIF to {}", path.display());
+                                gif_recording = Some(recording);
+                            }
+                            Err(err) => error!("recording GIF failed: {}", err),
+                        }
+                    }
+                }
+            }
+
+            if input.key_pressed(VirtualKeyCode::F2) {
+                let palette = palettes.cycle();
+                info!("palette: {}", palette.name);
+                interpreter.set_palette(palette);
+            }
+
+            if !movie_mode && input.key_pressed(VirtualKeyCode::F9) {
+                match interpreter.load_state(&state_path) {
+                    Ok(()) => info!("state loaded from {}", state_path.display()),
+                    Err(err) => error!("loading state failed: {}", err),
+                }
+            }
+
             interpreter.read_input(&input);
 
             window.request_redraw();
         }
     });
 }
+
+/// Builds the palettes cycled at runtime, selecting the one provided by the arguments or the config file, or else the
+/// ROM database colours.
+fn palettes(args: &CmdArgs, config: &Config, settings: &RomSettings) -> Result<Palettes> {
+    let mut palettes = Palettes::new(config.palettes()?);
+    match args.palette.as_ref().or(config.palette.as_ref()) {
+        Some(name) => palettes.select(name)?,
+        None if !settings.colors.is_empty() => palettes.insert_selected(Palette::new("rom", &settings.colors)),
+        None => {}
+    }
+    palettes.override_colors(
+        args.background.or(config.background()?),
+        args.foreground.or(config.foreground()?),
+    );
+
+    Ok(palettes)
+}
+
+/// Returns a capture file path next to the ROM, named after it and the current time so captures never overwrite each
+/// other.
+fn capture_path(rom_path: &Path, extension: &str) -> PathBuf {
+    let stem = rom_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("r8");
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+
+    rom_path.with_file_name(format!("{}-{}.{}", stem, millis, extension))
+}
+
+/// Looks the ROM up in the database provided by the arguments or the config file, or found in the config directory.
+fn rom_settings(args: &CmdArgs, config: &Config, rom_path: &Path) -> Result<Option<RomSettings>> {
+    let path = match args.database.as_ref().or(config.database.as_ref()) {
+        Some(path) => path.clone(),
+        None => match config::config_dir().map(|dir| dir.join(DATABASE_FILE_NAME)) {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        },
+    };
+
+    let database = RomDatabase::load(&path)?;
+
+    // A missing ROM is reported once it's loaded.
+    Ok(fs::read(rom_path).ok().and_then(|rom| database.lookup(&rom)))
+}
+
+/// Creates a tracer writing into the provided file.
+fn file_tracer(path: &Path) -> Result<Tracer> {
+    let file = File::create(path).with_context(|| format!("Creating trace file {} failed", path.display()))?;
+
+    Ok(Tracer::new(BufWriter::new(file)))
+}
+
+fn render(interpreter: &mut Interpreter, pixels: &mut Pixels, resolution: &mut (u32, u32)) -> Result<()> {
+    if interpreter.resolution() != *resolution {
+        *resolution = interpreter.resolution();
+
+        if let Err(err) = pixels.resize_buffer(resolution.0, resolution.1) {
+            bail!("resizing failed: {}", err);
+        }
+    }
+
+    if interpreter.should_draw() {
+        interpreter.draw(pixels.get_frame_mut());
+
+        if let Err(err) = pixels.render() {
+            bail!("rendering failed: {}", err);
+        }
+    }
+
+    Ok(())
+}
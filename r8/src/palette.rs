@@ -0,0 +1,292 @@
+//! The screen palettes, the colour of every pixel value.
+
+use anyhow::{bail, Result};
+
+/// An opaque RGBA colour.
+pub type Color = [u8; 4];
+
+/// The built-in palettes. Their colours are the background, the first plane, the second plane and both planes.
+const BUILTIN_PALETTES: [(&str, [Color; 4]); 4] = [
+    (
+        "default",
+        [
+            [0x00, 0x00, 0x00, 0xFF],
+            [0xFF, 0xFF, 0xFF, 0xFF],
+            [0x55, 0x55, 0x55, 0xFF],
+            [0xAA, 0xAA, 0xAA, 0xFF],
+        ],
+    ),
+    (
+        "octo",
+        [
+            [0x99, 0x66, 0x00, 0xFF],
+            [0xFF, 0xCC, 0x00, 0xFF],
+            [0xFF, 0x66, 0x00, 0xFF],
+            [0x66, 0x22, 0x00, 0xFF],
+        ],
+    ),
+    (
+        "amber",
+        [
+            [0x1A, 0x0F, 0x00, 0xFF],
+            [0xFF, 0xB0, 0x00, 0xFF],
+            [0x80, 0x58, 0x00, 0xFF],
+            [0xFF, 0xD8, 0x80, 0xFF],
+        ],
+    ),
+    (
+        "green",
+        [
+            [0x05, 0x14, 0x05, 0xFF],
+            [0x33, 0xFF, 0x33, 0xFF],
+            [0x1A, 0x80, 0x1A, 0xFF],
+            [0xB3, 0xFF, 0xB3, 0xFF],
+        ],
+    ),
+];
+
+/// A named set of colours, one for every pixel value.
+///
+/// Pixel values are plane bitmasks, so single plane modes only use the background and the first plane colours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// The palette name.
+    pub name: String,
+    /// The background, first plane, second plane and both planes colours.
+    pub colors: [Color; 4],
+}
+
+impl Palette {
+    /// Creates a palette from up to four colours, the missing ones taken from the default palette.
+    pub fn new(name: &str, colors: &[Color]) -> Self {
+        let mut palette = Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+        palette
+            .colors
+            .iter_mut()
+            .zip(colors)
+            .for_each(|(color, value)| *color = *value);
+
+        palette
+    }
+
+    /// Returns the built-in palettes.
+    pub fn builtins() -> Vec<Self> {
+        BUILTIN_PALETTES
+            .iter()
+            .map(|(name, colors)| Self::new(name, colors))
+            .collect()
+    }
+}
+
+impl Default for Palette {
+    /// Creates the `default` palette: white pixels over a black background.
+    fn default() -> Self {
+        let (name, colors) = BUILTIN_PALETTES[0];
+
+        Self {
+            name: name.to_string(),
+            colors,
+        }
+    }
+}
+
+/// The palettes available at runtime, cycled by a hotkey.
+#[derive(Debug, Clone)]
+pub struct Palettes {
+    /// The available palettes.
+    list: Vec<Palette>,
+    /// The index of the selected palette.
+    current: usize,
+}
+
+impl Palettes {
+    /// Creates the list of the built-in palettes followed by the provided ones, selecting the first.
+    pub fn new(custom: Vec<Palette>) -> Self {
+        let mut list = Palette::builtins();
+        for palette in custom {
+            match list.iter_mut().find(|builtin| builtin.name == palette.name) {
+                Some(builtin) => *builtin = palette,
+                None => list.push(palette),
+            }
+        }
+
+        Self { list, current: 0 }
+    }
+
+    /// Selects the palette named `name`.
+    pub fn select(&mut self, name: &str) -> Result<()> {
+        match self.list.iter().position(|palette| palette.name == name) {
+            Some(idx) => self.current = idx,
+            None => bail!(
+                "unknown palette `{}`, expected one of: {}",
+                name,
+                self.list
+                    .iter()
+                    .map(|palette| palette.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Adds a palette before the other ones and selects it.
+    pub fn insert_selected(&mut self, palette: Palette) {
+        self.list.insert(0, palette);
+        self.current = 0;
+    }
+
+    /// Replaces the background and foreground colours of the selected palette.
+    pub fn override_colors(&mut self, background: Option<Color>, foreground: Option<Color>) {
+        let palette = &mut self.list[self.current];
+
+        if let Some(color) = background {
+            palette.colors[0] = color;
+        }
+        if let Some(color) = foreground {
+            palette.colors[1] = color;
+        }
+    }
+
+    /// Returns the selected palette.
+    pub fn current(&self) -> &Palette {
+        &self.list[self.current]
+    }
+
+    /// Selects the next palette, going back to the first one after the last, and returns it.
+    pub fn cycle(&mut self) -> &Palette {
+        self.current = (self.current + 1) % self.list.len();
+
+        self.current()
+    }
+}
+
+/// Parses a `#rrggbb` colour.
+pub fn parse_color(color: &str) -> Result<Color> {
+    let value = match color.strip_prefix('#') {
+        // `from_str_radix` accepts a leading sign, so every digit is checked first.
+        Some(hex) if hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()) => u32::from_str_radix(hex, 16).ok(),
+        _ => None,
+    };
+    let Some(value) = value else {
+        bail!("invalid colour `{}`, expected `#rrggbb`", color);
+    };
+
+    let [_, r, g, b] = value.to_be_bytes();
+
+    Ok([r, g, b, 0xFF])
+}
+
+#[cfg(test)]
+mod test_palette {
+    use super::*;
+
+    const ICE: [Color; 2] = [[0x10, 0x20, 0x30, 0xFF], [0x80, 0xC0, 0xFF, 0xFF]];
+
+    fn names(palettes: &mut Palettes) -> Vec<String> {
+        (0..palettes.list.len())
+            .map(|_| palettes.cycle().name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            parse_color("#80c0FF").unwrap(),
+            [0x80, 0xC0, 0xFF, 0xFF],
+            "colour should be parsed ignoring its case"
+        );
+
+        for color in [
+            "80c0ff", "#80c0f", "#80c0ff0", "#+80c0f", "#-80c0f", "#80c0fg", "#80 0ff",
+        ] {
+            assert_eq!(
+                parse_color(color).unwrap_err().to_string(),
+                format!("invalid colour `{}`, expected `#rrggbb`", color),
+                "invalid colour should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let mut palettes = Palettes::new(vec![Palette::new("octo", &ICE), Palette::new("ice", &ICE)]);
+
+        assert_eq!(
+            names(&mut palettes),
+            ["octo", "amber", "green", "ice", "default"],
+            "custom palettes should follow the built-in ones"
+        );
+        palettes.select("octo").expect("octo should be selected");
+        assert_eq!(
+            palettes.current().colors[..2],
+            ICE,
+            "custom palette should override the built-in one with its name"
+        );
+        assert_eq!(
+            palettes.current().colors[2..],
+            Palette::default().colors[2..],
+            "missing colours should be taken from the default palette"
+        );
+    }
+
+    #[test]
+    fn test_select() {
+        let mut palettes = Palettes::new(Vec::new());
+
+        palettes.select("amber").expect("amber should be selected");
+        assert_eq!(palettes.current().name, "amber", "amber should be selected");
+
+        assert_eq!(
+            palettes.select("ice").unwrap_err().to_string(),
+            "unknown palette `ice`, expected one of: default, octo, amber, green",
+            "unknown palette should be rejected"
+        );
+        assert_eq!(palettes.current().name, "amber", "selection should be kept on errors");
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut palettes = Palettes::new(Vec::new());
+        palettes.select("green").expect("green should be selected");
+
+        assert_eq!(
+            palettes.cycle().name,
+            "default",
+            "cycle should wrap around after the last palette"
+        );
+        assert_eq!(palettes.cycle().name, "octo", "cycle should select the next palette");
+    }
+
+    #[test]
+    fn test_override_colors() {
+        let mut palettes = Palettes::new(Vec::new());
+        palettes.select("octo").expect("octo should be selected");
+
+        palettes.override_colors(None, Some(ICE[1]));
+        assert_eq!(
+            palettes.current().colors,
+            [
+                [0x99, 0x66, 0x00, 0xFF],
+                ICE[1],
+                [0xFF, 0x66, 0x00, 0xFF],
+                [0x66, 0x22, 0x00, 0xFF]
+            ],
+            "foreground should be replaced"
+        );
+
+        palettes.override_colors(Some(ICE[0]), None);
+        assert_eq!(palettes.current().colors[..2], ICE, "background should be replaced");
+
+        palettes.cycle();
+        assert_eq!(
+            palettes.current().colors,
+            BUILTIN_PALETTES[2].1,
+            "other palettes should keep their colours"
+        );
+    }
+}
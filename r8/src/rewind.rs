@@ -0,0 +1,222 @@
+//! A bounded history of machine save-states used to run the game backwards.
+
+use std::collections::VecDeque;
+
+use log::debug;
+
+/// A ring buffer of machine save-states.
+///
+/// Only the newest snapshot is kept in full. Every older one is stored as the XOR delta against the snapshot that
+/// followed it, with the unchanged byte runs compressed away, since consecutive frames barely differ.
+#[derive(Debug)]
+pub struct RewindBuffer {
+    capacity: usize,
+    latest: Option<Vec<u8>>,
+    deltas: VecDeque<Vec<u8>>,
+}
+
+impl RewindBuffer {
+    /// Creates a buffer holding at most `capacity` snapshots. A zero capacity disables rewinding.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            latest: None,
+            deltas: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Pushes a new snapshot, dropping the oldest one when the buffer is full.
+    pub fn push(&mut self, snapshot: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(latest) = self.latest.take() {
+            if latest.len() == snapshot.len() {
+                self.deltas.push_back(encode_delta(&latest, &snapshot));
+            } else {
+                // The snapshot layout changed, so the older ones can't be restored from it anymore.
+                self.deltas.clear();
+            }
+        }
+
+        while self.deltas.len() >= self.capacity {
+            self.deltas.pop_front();
+        }
+
+        self.latest = Some(snapshot);
+
+        debug!("rewind_push, deltas={}", self.deltas.len());
+    }
+
+    /// Removes the newest snapshot and returns the one before it, which becomes the newest.
+    pub fn pop(&mut self) -> Option<&[u8]> {
+        let delta = self.deltas.pop_back()?;
+        let latest = self.latest.as_mut()?;
+
+        apply_delta(latest, &delta);
+
+        debug!("rewind_pop, deltas={}", self.deltas.len());
+
+        Some(latest)
+    }
+}
+
+/// Encodes `new` as the XOR delta against `old`, both with the same length.
+///
+/// The delta is a list of chunks made of a little-endian `u16` count of unchanged bytes, a little-endian `u16` count
+/// of changed bytes, and the changed bytes XORed with the old ones.
+fn encode_delta(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+
+    while pos < old.len() {
+        let skip = (pos..old.len())
+            .take(u16::MAX as usize)
+            .take_while(|&i| old[i] == new[i])
+            .count();
+        pos += skip;
+
+        let start = pos;
+        let changed = (pos..old.len())
+            .take(u16::MAX as usize)
+            .take_while(|&i| old[i] != new[i])
+            .count();
+        pos += changed;
+
+        if changed == 0 && pos < old.len() {
+            // The unchanged run hit the `u16` limit, emit it and keep going.
+            out.extend_from_slice(&(skip as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+            continue;
+        }
+
+        if changed == 0 {
+            break;
+        }
+
+        out.extend_from_slice(&(skip as u16).to_le_bytes());
+        out.extend_from_slice(&(changed as u16).to_le_bytes());
+        out.extend((start..pos).map(|i| old[i] ^ new[i]));
+    }
+
+    out
+}
+
+/// Applies a delta created by [`encode_delta`] to `data`. XOR deltas are symmetric, so it turns either snapshot into
+/// the other one.
+fn apply_delta(data: &mut [u8], delta: &[u8]) {
+    let mut pos = 0;
+    let mut chunks = delta;
+
+    while chunks.len() >= 4 {
+        let skip = u16::from_le_bytes([chunks[0], chunks[1]]) as usize;
+        let changed = u16::from_le_bytes([chunks[2], chunks[3]]) as usize;
+        chunks = &chunks[4..];
+
+        pos += skip;
+        data[pos..pos + changed]
+            .iter_mut()
+            .zip(&chunks[..changed])
+            .for_each(|(dst, src)| *dst ^= src);
+        pos += changed;
+        chunks = &chunks[changed..];
+    }
+}
+
+#[cfg(test)]
+mod test_rewind {
+    use super::*;
+
+    fn frame(len: usize, seed: u8) -> Vec<u8> {
+        (0..len).map(|i| (i as u8).wrapping_mul(seed)).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let frames: Vec<Vec<u8>> = (1..=5).map(|seed| frame(64, seed)).collect();
+        let mut buffer = RewindBuffer::new(8);
+        frames.iter().for_each(|frame| buffer.push(frame.clone()));
+
+        for expected in frames[..4].iter().rev() {
+            assert_eq!(
+                buffer.pop(),
+                Some(&expected[..]),
+                "pop should restore the previous frame"
+            );
+        }
+        assert_eq!(buffer.pop(), None, "pop should stop at the oldest frame");
+    }
+
+    #[test]
+    fn test_long_runs() {
+        let len = u16::MAX as usize * 2 + 10;
+        let old = vec![0; len];
+        let mut new = vec![0; len];
+        new[len - 1] = 1;
+        let changed = vec![0xFF; len];
+
+        let delta = encode_delta(&old, &new);
+        assert!(delta.len() < 32, "unchanged runs should be compressed");
+        let mut data = old.clone();
+        apply_delta(&mut data, &delta);
+        assert_eq!(data, new, "delta should restore an unchanged run longer than u16::MAX");
+
+        let mut data = old.clone();
+        apply_delta(&mut data, &encode_delta(&old, &changed));
+        assert_eq!(data, changed, "delta should restore a changed run longer than u16::MAX");
+
+        let mut buffer = RewindBuffer::new(2);
+        buffer.push(old.clone());
+        buffer.push(new);
+        assert_eq!(buffer.pop(), Some(&old[..]), "pop should restore a large frame");
+    }
+
+    #[test]
+    fn test_eviction() {
+        let mut buffer = RewindBuffer::new(3);
+        (1..=5).for_each(|seed| buffer.push(frame(16, seed)));
+
+        assert_eq!(
+            buffer.pop(),
+            Some(&frame(16, 4)[..]),
+            "pop should restore the fourth frame"
+        );
+        assert_eq!(
+            buffer.pop(),
+            Some(&frame(16, 3)[..]),
+            "pop should restore the third frame"
+        );
+        assert_eq!(buffer.pop(), None, "older frames should be evicted at capacity");
+    }
+
+    #[test]
+    fn test_pop_empty() {
+        let mut buffer = RewindBuffer::new(4);
+        assert_eq!(buffer.pop(), None, "empty buffer should have nothing to pop");
+
+        buffer.push(frame(16, 1));
+        assert_eq!(buffer.pop(), None, "single frame buffer should have nothing to pop");
+
+        let mut buffer = RewindBuffer::new(0);
+        buffer.push(frame(16, 1));
+        buffer.push(frame(16, 2));
+        assert_eq!(buffer.pop(), None, "zero capacity buffer should keep nothing");
+    }
+
+    #[test]
+    fn test_size_change() {
+        let mut buffer = RewindBuffer::new(4);
+        buffer.push(frame(16, 1));
+        buffer.push(frame(16, 2));
+        buffer.push(frame(32, 3));
+        buffer.push(frame(32, 4));
+
+        assert_eq!(
+            buffer.pop(),
+            Some(&frame(32, 3)[..]),
+            "pop should restore the resized frame"
+        );
+        assert_eq!(buffer.pop(), None, "frames before the size change should be dropped");
+    }
+}
@@ -0,0 +1,301 @@
+//! The ROM database, with the recommended settings of known ROMs.
+//!
+//! The database is a `programs.json` file in the layout of the community
+//! [chip-8-database](https://github.com/chip-8/chip-8-database): a list of programs, each with its ROMs keyed by their
+//! SHA-1 digest, written as lowercase hexadecimal.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use r8lib::{rom_hash, Platform, Quirks};
+use serde::Deserialize;
+
+use crate::layout::KeyBindings;
+use crate::palette::{parse_color, Color};
+
+/// The database file name, inside the `r8` user configuration directory.
+pub const DATABASE_FILE_NAME: &str = "programs.json";
+
+/// The keyboard keys bound to the database key roles. Second player roles are left to the config file.
+const ROLE_KEYS: [(&str, &str); 6] = [
+    ("up", "up"),
+    ("down", "down"),
+    ("left", "left"),
+    ("right", "right"),
+    ("a", "space"),
+    ("b", "enter"),
+];
+
+/// A program of the database.
+#[derive(Debug, Deserialize)]
+struct Program {
+    /// The program title.
+    title: String,
+    /// The program ROMs, keyed by their SHA-1 digest.
+    #[serde(default)]
+    roms: HashMap<String, RomEntry>,
+}
+
+/// A ROM of the database.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RomEntry {
+    /// The platforms the ROM runs on, the preferred one first.
+    #[serde(default)]
+    platforms: Vec<String>,
+    /// The instructions per frame.
+    tickrate: Option<u32>,
+    /// The quirks differing from the ones of each platform.
+    #[serde(default)]
+    quirky_platforms: HashMap<String, QuirkOverrides>,
+    /// The ROM colours.
+    colors: Option<Colors>,
+    /// The CHIP-8 keys used for each role, like `up` or `a`.
+    #[serde(default)]
+    keys: HashMap<String, u8>,
+}
+
+/// The quirks differing from the ones of a platform.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuirkOverrides {
+    /// Shifts `Vx` in place, ignoring `Vy`.
+    shift: Option<bool>,
+    /// `Fx55` and `Fx65` leave `I` untouched.
+    memory_leave_i_unchanged: Option<bool>,
+    /// Sprites wrap around the screen edges.
+    wrap: Option<bool>,
+    /// `Bnnn` jumps to `xnn + Vx`.
+    jump: Option<bool>,
+    /// `Dxyn` waits for the display refresh.
+    vblank: Option<bool>,
+    /// `8xy1`, `8xy2` and `8xy3` reset `VF`.
+    logic: Option<bool>,
+}
+
+/// The ROM colours.
+#[derive(Debug, Default, Deserialize)]
+struct Colors {
+    /// The `#rrggbb` colour of every pixel value, the background first.
+    #[serde(default)]
+    pixels: Vec<String>,
+}
+
+/// The recommended settings of a ROM found in the database.
+#[derive(Debug, Default)]
+pub struct RomSettings {
+    /// The program title.
+    pub title: String,
+    /// The recommended platform and its quirks.
+    pub platform: Option<(Platform, Quirks)>,
+    /// The recommended instructions per frame.
+    pub tickrate: Option<u16>,
+    /// The RGBA colour of every pixel value, the background first.
+    pub colors: Vec<Color>,
+    /// The CHIP-8 keys used for each role, like `up` or `a`.
+    keys: HashMap<String, u8>,
+}
+
+impl RomSettings {
+    /// Binds the arrow keys, `Space` and `Enter` to the CHIP-8 keys used for the `up`, `down`, `left`, `right`, `a` and
+    /// `b` roles, keeping the other bindings.
+    pub fn apply_key_bindings(&self, bindings: &mut KeyBindings) -> Result<()> {
+        for (role, name) in ROLE_KEYS {
+            if let Some(&key) = self.keys.get(role) {
+                bindings.add(key, name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The ROM database.
+#[derive(Debug, Default)]
+pub struct RomDatabase {
+    /// The database programs.
+    programs: Vec<Program>,
+}
+
+impl RomDatabase {
+    /// Loads the database file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        debug!("rom_database_load, path={:?}", path);
+
+        let source = fs::read_to_string(path).with_context(|| format!("Reading database {} failed", path.display()))?;
+        let programs = serde_json::from_str(&source).with_context(|| format!("Invalid database {}", path.display()))?;
+
+        Ok(Self { programs })
+    }
+
+    /// Looks the provided ROM up by its SHA-1 digest, returning its recommended settings.
+    pub fn lookup(&self, rom: &[u8]) -> Option<RomSettings> {
+        let hash: String = rom_hash(rom).iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        debug!("rom_database_lookup, hash={}", hash);
+
+        self.programs.iter().find_map(|program| {
+            let entry = program.roms.get(&hash)?;
+
+            Some(RomSettings {
+                title: program.title.clone(),
+                platform: entry.platforms.iter().find_map(|name| {
+                    let (platform, quirks) = platform_quirks(name)?;
+                    let overrides = entry.quirky_platforms.get(name);
+
+                    Some((platform, overrides.map_or(quirks, |overrides| overrides.apply(quirks))))
+                }),
+                // Some entries ask for more instructions per frame than the interpreter runs.
+                tickrate: entry.tickrate.map(|tickrate| tickrate.clamp(1, u16::MAX as u32) as u16),
+                colors: entry
+                    .colors
+                    .iter()
+                    .flat_map(|colors| colors.pixels.iter())
+                    .filter_map(|color| {
+                        let parsed = parse_color(color);
+                        if let Err(err) = &parsed {
+                            warn!("{} of {}", err, program.title);
+                        }
+                        parsed.ok()
+                    })
+                    .collect(),
+                keys: entry.keys.clone(),
+            })
+        })
+    }
+}
+
+impl QuirkOverrides {
+    /// Applies the overrides to the provided quirks.
+    fn apply(&self, quirks: Quirks) -> Quirks {
+        Quirks {
+            shift_uses_vy: self.shift.map_or(quirks.shift_uses_vy, |shift| !shift),
+            load_store_increments_i: self
+                .memory_leave_i_unchanged
+                .map_or(quirks.load_store_increments_i, |unchanged| !unchanged),
+            sprite_wrap: self.wrap.unwrap_or(quirks.sprite_wrap),
+            jump_uses_vx: self.jump.unwrap_or(quirks.jump_uses_vx),
+            display_wait: self.vblank.unwrap_or(quirks.display_wait),
+            vf_reset: self.logic.unwrap_or(quirks.vf_reset),
+            ..quirks
+        }
+    }
+}
+
+/// Returns the platform and quirks of a database platform identifier, or `None` when it isn't supported.
+fn platform_quirks(name: &str) -> Option<(Platform, Quirks)> {
+    let platform = match name {
+        "originalChip8" | "hybridVIP" => (Platform::Chip8, Quirks::cosmac_vip()),
+        "modernChip8" => (Platform::Chip8, Quirks::default()),
+        "chip48" => (Platform::Chip8, Quirks::chip48()),
+        "superchip1" | "superchip" => (Platform::SuperChip, Quirks::super_chip()),
+        "xochip" => (Platform::XoChip, Quirks::xo_chip()),
+        _ => return None,
+    };
+
+    Some(platform)
+}
+
+#[cfg(test)]
+mod test_romdb {
+    use super::*;
+
+    // A ROM clearing the screen in a loop. Its last instruction alone is the second database ROM.
+    const ROM: [u8; 4] = [0x00, 0xE0, 0x12, 0x00];
+
+    const DATABASE: &str = r##"[
+        {
+            "title": "Quirky",
+            "roms": {
+                "2cdd5bd3f4e30a4d56d9a8841ffcd5fbc2d0f735": {
+                    "platforms": ["unknownPlatform", "originalChip8", "xochip"],
+                    "tickrate": 100000,
+                    "quirkyPlatforms": {
+                        "originalChip8": {
+                            "shift": true,
+                            "memoryLeaveIUnchanged": true,
+                            "wrap": true,
+                            "jump": true,
+                            "vblank": false,
+                            "logic": false
+                        }
+                    },
+                    "colors": { "pixels": ["#102030", "invalid", "#AABBCC"] },
+                    "keys": { "up": 5, "a": 6 }
+                }
+            }
+        },
+        {
+            "title": "Plain",
+            "roms": {
+                "92a5652d382a18e89c4881ec57041fc7d885ca80": {
+                    "platforms": ["superchip"],
+                    "tickrate": 0
+                }
+            }
+        }
+    ]"##;
+
+    fn database() -> RomDatabase {
+        RomDatabase {
+            programs: serde_json::from_str(DATABASE).expect("database should be parsed"),
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let database = database();
+
+        let settings = database.lookup(&ROM).expect("rom should be found");
+        assert_eq!(settings.title, "Quirky", "title should be the program one");
+        assert_eq!(
+            settings.tickrate,
+            Some(u16::MAX),
+            "tickrate should be clamped to the largest instructions per frame"
+        );
+        assert_eq!(
+            settings.colors,
+            [[0x10, 0x20, 0x30, 0xFF], [0xAA, 0xBB, 0xCC, 0xFF]],
+            "invalid colours should be skipped"
+        );
+        assert_eq!(settings.keys.get("up"), Some(&5), "up key should be kept");
+
+        let settings = database.lookup(&ROM[2..]).expect("rom should be found");
+        assert_eq!(settings.title, "Plain", "title should be the program one");
+        assert_eq!(
+            settings.platform,
+            Some((Platform::SuperChip, Quirks::super_chip())),
+            "platform should keep its quirks without overrides"
+        );
+        assert_eq!(
+            settings.tickrate,
+            Some(1),
+            "tickrate should run at least an instruction"
+        );
+
+        assert!(database.lookup(&ROM[..2]).is_none(), "unknown rom should not be found");
+    }
+
+    #[test]
+    fn test_quirk_overrides() {
+        let settings = database().lookup(&ROM).expect("rom should be found");
+
+        assert_eq!(
+            settings.platform,
+            Some((
+                Platform::Chip8,
+                Quirks {
+                    shift_uses_vy: false,
+                    load_store_increments_i: false,
+                    jump_uses_vx: true,
+                    vf_reset: false,
+                    sprite_wrap: true,
+                    display_wait: false,
+                    key_wait_release: true,
+                }
+            )),
+            "first supported platform should be picked with its quirks overridden"
+        );
+    }
+}
@@ -1,65 +1,99 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
 use anyhow::Result;
 use cpal::{
     default_host,
     traits::{DeviceTrait, HostTrait, StreamTrait},
     Device, Sample, SampleFormat, Stream, StreamConfig,
 };
+use r8lib::{AudioPattern, Synth, Tone};
 
 use crate::error::InternalError;
 
+/// The beep output. The stream always runs, rendering silence while the beep is off, so toggling it never clicks.
 pub struct Beep {
-    stream: Stream,
+    /// The output stream, which stops when dropped.
+    _stream: Stream,
+    /// Tells the stream if the beep is on.
+    playing: Arc<AtomicBool>,
+    /// The XO-CHIP audio pattern and playback rate played instead of the tone.
+    pattern: Arc<Mutex<Option<(AudioPattern, f32)>>>,
 }
 
 impl Beep {
-    pub fn new() -> Result<Self> {
+    pub fn new(tone: Tone) -> Result<Self> {
         let device = default_host()
             .default_output_device()
             .ok_or(InternalError::InvalidAudioOutput)?;
 
         let config = device.default_output_config()?;
+        let playing = Arc::new(AtomicBool::new(false));
+        let pattern = Arc::new(Mutex::new(None));
 
         let stream = match config.sample_format() {
-            SampleFormat::I16 => Self::build_stream::<i16>(&device, &config.into()),
-            SampleFormat::U16 => Self::build_stream::<u16>(&device, &config.into()),
-            SampleFormat::F32 => Self::build_stream::<f32>(&device, &config.into()),
+            SampleFormat::I16 => {
+                Self::build_stream::<i16>(&device, &config.into(), tone, playing.clone(), pattern.clone())
+            }
+            SampleFormat::U16 => {
+                Self::build_stream::<u16>(&device, &config.into(), tone, playing.clone(), pattern.clone())
+            }
+            SampleFormat::F32 => {
+                Self::build_stream::<f32>(&device, &config.into(), tone, playing.clone(), pattern.clone())
+            }
         }?;
+        stream.play()?;
 
-        Ok(Self { stream })
+        Ok(Self {
+            _stream: stream,
+            playing,
+            pattern,
+        })
     }
 
-    pub fn play(&self) {
-        self.stream.play().ok();
+    pub fn set_playing(&self, playing: bool) {
+        self.playing.store(playing, Ordering::Relaxed);
     }
 
-    pub fn pause(&self) {
-        self.stream.pause().ok();
+    pub fn set_pattern(&self, pattern: Option<(AudioPattern, f32)>) {
+        if let Ok(mut current) = self.pattern.lock() {
+            *current = pattern;
+        }
     }
 
-    fn build_stream<T: Sample>(device: &Device, config: &StreamConfig) -> Result<Stream> {
-        let sample_rate = config.sample_rate.0 as f32;
+    fn build_stream<T: Sample>(
+        device: &Device,
+        config: &StreamConfig,
+        tone: Tone,
+        playing: Arc<AtomicBool>,
+        pattern: Arc<Mutex<Option<(AudioPattern, f32)>>>,
+    ) -> Result<Stream> {
         let channels = config.channels as usize;
-
-        let mut sample_clock = 0f32;
-        let mut next_value = move || {
-            sample_clock = (sample_clock + 1.0) % sample_rate;
-            (sample_clock * 440.0 * 2.0 * std::f32::consts::PI / sample_rate).sin()
-        };
+        let mut synth = Synth::new(tone, config.sample_rate.0);
 
         let err_fn = |err| eprintln!("an error occurred on stream: {}", err);
 
         let stream = device.build_output_stream(
             config,
-            move |data: &mut [T], _: &cpal::OutputCallbackInfo| Self::write_data(data, channels, &mut next_value),
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                synth.set_gate(playing.load(Ordering::Relaxed));
+                // The audio thread must not block, so the pattern is updated on the next buffer when it's being set.
+                if let Ok(pattern) = pattern.try_lock() {
+                    synth.set_pattern(*pattern);
+                }
+                Self::write_data(data, channels, &mut synth)
+            },
             err_fn,
         )?;
 
         Ok(stream)
     }
 
-    fn write_data<T: Sample>(output: &mut [T], channels: usize, next_sample: &mut dyn FnMut() -> f32) {
+    fn write_data<T: Sample>(output: &mut [T], channels: usize, synth: &mut Synth) {
         for frame in output.chunks_mut(channels) {
-            let value: T = cpal::Sample::from::<f32>(&next_sample());
+            let value: T = cpal::Sample::from::<f32>(&synth.next_sample());
             for sample in frame.iter_mut() {
                 *sample = value;
             }
@@ -0,0 +1,400 @@
+//! The `tui` subcommand, running a ROM inside a terminal.
+
+use std::{
+    fs,
+    io::{self, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use crossterm::{
+    cursor,
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use log::debug;
+use r8lib::{Key, Keys, Machine, StepOutcome, Timing, Vram};
+
+use crate::args::{Glyphs, TuiArgs};
+use crate::config::Config;
+use crate::{file_tracer, DEFAULT_INSTRUCTIONS_PER_FRAME, DEFAULT_TIMER_FREQUENCY};
+
+/// How long a key stays pressed after a key press, on terminals that don't report key releases.
+const KEY_HOLD_TIME: Duration = Duration::from_millis(150);
+/// The braille dot bits of every pixel in a 2x4 cell, indexed by column and row.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+pub fn run(args: &TuiArgs) -> Result<()> {
+    debug!("tui, args={:?}", args);
+
+    let rom =
+        fs::read(&args.rom).with_context(|| format!("Missing or invalid ROM file provided: {}", args.rom.display()))?;
+
+    let mut machine = Machine::with_seed(args.seed.unwrap_or_else(rand::random));
+    machine.set_platform(args.platform.into());
+    if let Some(preset) = args.quirks {
+        machine.set_quirks(preset.into());
+    }
+    machine.set_timing(args.timing.into());
+    if let Some(path) = &args.trace {
+        machine.set_tracer(Some(file_tracer(path)?));
+    }
+    machine
+        .load_rom(&rom)
+        .with_context(|| format!("Loading ROM {} failed", args.rom.display()))?;
+
+    let bindings = Config::load(args.config.as_deref())?.rom_key_bindings(&args.rom)?;
+
+    let guard = TerminalGuard::new()?;
+    let mut stdout = io::stdout();
+    let mut keys = TerminalKeys::new(guard.enhanced);
+
+    let frame_time = Duration::from_secs_f64(1.0 / DEFAULT_TIMER_FREQUENCY / args.speed);
+    let mut next_frame = Instant::now();
+    let mut paused = false;
+    let mut redraw = true;
+    let mut beeping = false;
+
+    loop {
+        while event::poll(next_frame.saturating_duration_since(Instant::now()))? {
+            match event::read()? {
+                Event::Key(KeyEvent { code: KeyCode::Esc, .. }) => return Ok(()),
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers,
+                    ..
+                }) if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                Event::Key(KeyEvent {
+                    code: KeyCode::F(6),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) => {
+                    paused = !paused;
+                    redraw = true;
+                }
+                Event::Key(KeyEvent {
+                    code: KeyCode::F(7),
+                    kind: KeyEventKind::Press,
+                    ..
+                }) if paused => {
+                    if machine.step(keys.as_ref())? == StepOutcome::Exited {
+                        return Ok(());
+                    }
+                    redraw = true;
+                }
+                Event::Key(KeyEvent { code, kind, .. }) => {
+                    let key = match code {
+                        KeyCode::Char(c) => bindings.char_key(c),
+                        KeyCode::Enter => bindings.key("enter"),
+                        KeyCode::Tab => bindings.key("tab"),
+                        KeyCode::Up => bindings.key("up"),
+                        KeyCode::Down => bindings.key("down"),
+                        KeyCode::Left => bindings.key("left"),
+                        KeyCode::Right => bindings.key("right"),
+                        KeyCode::Home => bindings.key("home"),
+                        KeyCode::End => bindings.key("end"),
+                        KeyCode::PageUp => bindings.key("pageup"),
+                        KeyCode::PageDown => bindings.key("pagedown"),
+                        KeyCode::Insert => bindings.key("insert"),
+                        KeyCode::Delete => bindings.key("delete"),
+                        _ => None,
+                    };
+                    if let Some(key) = key {
+                        keys.update(key, kind);
+                    }
+                }
+                Event::Resize(..) => {
+                    queue!(stdout, terminal::Clear(ClearType::All))?;
+                    redraw = true;
+                }
+                _ => (),
+            }
+        }
+
+        let now = Instant::now();
+        next_frame = (next_frame + frame_time).max(now);
+        keys.expire(now);
+
+        if !paused {
+            // The COSMAC VIP timing runs until the frame cycles are spent.
+            let instructions = match machine.timing() {
+                Timing::Fixed => DEFAULT_INSTRUCTIONS_PER_FRAME,
+                _ => u16::MAX,
+            };
+            let frame = machine.run_frame(keys.as_ref(), instructions)?;
+            if frame.exited {
+                return Ok(());
+            }
+            redraw |= frame.redraw;
+
+            // The terminal bell is the only sound available, so it rings when the sound timer starts.
+            if frame.beep && !beeping {
+                execute!(stdout, Print('\x07'))?;
+            }
+            beeping = frame.beep;
+        }
+
+        if redraw {
+            draw(&mut stdout, &machine, args.glyphs, paused)?;
+            redraw = false;
+        }
+    }
+}
+
+/// Puts the terminal in raw mode on an alternate screen, restoring it when dropped.
+struct TerminalGuard {
+    /// Tells if the terminal reports key releases.
+    enhanced: bool,
+}
+
+impl TerminalGuard {
+    fn new() -> Result<Self> {
+        terminal::enable_raw_mode()?;
+
+        let enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        let mut stdout = io::stdout();
+
+        execute!(
+            stdout,
+            terminal::EnterAlternateScreen,
+            cursor::Hide,
+            terminal::Clear(ClearType::All)
+        )?;
+        if enhanced {
+            execute!(
+                stdout,
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+        }
+
+        Ok(Self { enhanced })
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let mut stdout = io::stdout();
+
+        if self.enhanced {
+            let _ = execute!(stdout, PopKeyboardEnhancementFlags);
+        }
+        let _ = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// The CHIP-8 keys pressed in the terminal.
+///
+/// Most terminals only report key presses, so a pressed key is held for [`KEY_HOLD_TIME`], renewed by the key repeat.
+/// Terminals supporting key release reports hold it until it's released.
+struct TerminalKeys {
+    /// The keys state.
+    keys: Keys,
+    /// When each key is going to be released, on terminals that don't report key releases.
+    release_at: [Option<Instant>; 16],
+    /// Tells if the terminal reports key releases.
+    enhanced: bool,
+}
+
+impl TerminalKeys {
+    fn new(enhanced: bool) -> Self {
+        Self {
+            keys: Keys::default(),
+            release_at: [None; 16],
+            enhanced,
+        }
+    }
+
+    /// Updates a key state from a terminal key event.
+    fn update(&mut self, key: Key, kind: KeyEventKind) {
+        let idx = key as usize;
+
+        match kind {
+            KeyEventKind::Press | KeyEventKind::Repeat => {
+                self.keys[idx] = true;
+                self.release_at[idx] = (!self.enhanced).then(|| Instant::now() + KEY_HOLD_TIME);
+            }
+            KeyEventKind::Release => {
+                self.keys[idx] = false;
+                self.release_at[idx] = None;
+            }
+        }
+    }
+
+    /// Releases the keys held for longer than [`KEY_HOLD_TIME`].
+    fn expire(&mut self, now: Instant) {
+        for (key, release_at) in self.keys.iter_mut().zip(self.release_at.iter_mut()) {
+            if release_at.is_some_and(|at| at <= now) {
+                *key = false;
+                *release_at = None;
+            }
+        }
+    }
+}
+
+impl AsRef<Keys> for TerminalKeys {
+    fn as_ref(&self) -> &Keys {
+        &self.keys
+    }
+}
+
+/// Draws the screen and a status line with the machine registers.
+fn draw(stdout: &mut Stdout, machine: &Machine, glyphs: Glyphs, paused: bool) -> Result<()> {
+    let vram = machine.vram_as_ref();
+    let lines = match glyphs {
+        Glyphs::HalfBlock => half_block_lines(vram),
+        Glyphs::Braille => braille_lines(vram),
+    };
+
+    // A lower resolution draws fewer and shorter lines, so the rest of a higher resolution frame is cleared.
+    for (row, line) in lines.iter().enumerate() {
+        queue!(
+            stdout,
+            cursor::MoveTo(0, row as u16),
+            Print(line),
+            terminal::Clear(ClearType::UntilNewLine)
+        )?;
+    }
+
+    let state = machine.state();
+    let v: Vec<String> = state.v().iter().map(|value| format!("{:02X}", value)).collect();
+    let status = format!(
+        "PC {:#06x}  I {:#06x}  SP {}  DT {:3}  ST {:3}  V {}{}",
+        state.pc(),
+        state.i(),
+        state.sp(),
+        state.dt(),
+        state.st(),
+        v.join(" "),
+        if paused { "  [PAUSED: F6 resume, F7 step]" } else { "" }
+    );
+    queue!(
+        stdout,
+        cursor::MoveTo(0, lines.len() as u16),
+        Print(status),
+        terminal::Clear(ClearType::UntilNewLine),
+        cursor::MoveToNextLine(1),
+        terminal::Clear(ClearType::FromCursorDown)
+    )?;
+
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// Tells if the pixel at `(x, y)` has any plane lit, returning `false` outside the screen.
+fn is_lit(vram: &Vram, x: usize, y: usize) -> bool {
+    x < vram.width() && y < vram.height() && vram[y * vram.width() + x] != 0
+}
+
+/// Renders the screen with half-block characters, two pixels per character.
+fn half_block_lines(vram: &Vram) -> Vec<String> {
+    (0..vram.height())
+        .step_by(2)
+        .map(|y| {
+            (0..vram.width())
+                .map(|x| match (is_lit(vram, x, y), is_lit(vram, x, y + 1)) {
+                    (false, false) => ' ',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (true, true) => '█',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Renders the screen with braille characters, eight pixels per character.
+fn braille_lines(vram: &Vram) -> Vec<String> {
+    (0..vram.height())
+        .step_by(4)
+        .map(|y| {
+            (0..vram.width())
+                .step_by(2)
+                .map(|x| {
+                    let bits = (0..2)
+                        .flat_map(|dx| (0..4).map(move |dy| (dx, dy)))
+                        .filter(|&(dx, dy)| is_lit(vram, x + dx, y + dy))
+                        .fold(0, |bits, (dx, dy)| bits | BRAILLE_DOTS[dx][dy]);
+
+                    char::from_u32(0x2800 + bits).unwrap_or(' ')
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_terminal {
+    use super::*;
+
+    fn vram(pixels: &[(usize, usize, u8)]) -> Vram {
+        let mut vram = Vram::default();
+        let width = vram.width();
+        for &(x, y, value) in pixels {
+            vram[y * width + x] = value;
+        }
+
+        vram
+    }
+
+    #[test]
+    fn test_half_block_lines() {
+        let vram = vram(&[(0, 0, 0b01), (0, 1, 0b01), (1, 1, 0b10), (2, 0, 0b11), (63, 31, 0b01)]);
+        let lines = half_block_lines(&vram);
+
+        assert_eq!(lines.len(), 16, "every line should have two pixel rows");
+        assert!(
+            lines.iter().all(|line| line.chars().count() == 64),
+            "every character should have a pixel column"
+        );
+        assert!(
+            lines[0].starts_with("█▄▀ "),
+            "lit pixels should be drawn as half blocks"
+        );
+        assert!(
+            lines[15].ends_with(" ▄"),
+            "last pixel should be drawn as a lower half block"
+        );
+        assert!(
+            lines[1..15].iter().all(|line| line.trim().is_empty()),
+            "unlit pixels should be blank"
+        );
+    }
+
+    #[test]
+    fn test_braille_lines() {
+        let vram = vram(&[
+            (0, 0, 0b01),
+            (1, 1, 0b10),
+            (0, 3, 0b11),
+            (3, 0, 0b01),
+            (3, 3, 0b01),
+            (63, 31, 0b01),
+        ]);
+        let lines = braille_lines(&vram);
+
+        assert_eq!(lines.len(), 8, "every line should have four pixel rows");
+        assert!(
+            lines.iter().all(|line| line.chars().count() == 32),
+            "every character should have two pixel columns"
+        );
+        assert!(
+            lines[0].starts_with("⡑⢈⠀"),
+            "lit pixels should be drawn as braille dots"
+        );
+        assert!(
+            lines[7].ends_with("⠀⢀"),
+            "last pixel should be drawn as the bottom right dot"
+        );
+        assert!(
+            lines[1..7].iter().all(|line| line.chars().all(|c| c == '⠀')),
+            "unlit pixels should be blank braille"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! The window size, snapped to whole pixel scales and remembered between runs.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use winit::dpi::{LogicalSize, PhysicalSize};
+
+use crate::config::config_dir;
+
+/// The file where the last window size is kept, inside the `r8` user configuration directory.
+const WINDOW_SIZE_FILE_NAME: &str = "window.toml";
+
+/// The logical size of the window, independent of the display scale factor.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowSize {
+    /// The window width, in logical pixels.
+    pub width: f64,
+    /// The window height, in logical pixels.
+    pub height: f64,
+}
+
+impl WindowSize {
+    /// Loads the size of the window on the last run.
+    ///
+    /// A missing file means no size was saved yet, while an invalid one is ignored with a warning.
+    pub fn load() -> Option<Self> {
+        let path = config_dir()?.join(WINDOW_SIZE_FILE_NAME);
+
+        debug!("window_size_load, path={:?}", path);
+
+        let source = fs::read_to_string(&path).ok()?;
+        match toml::from_str(&source) {
+            Ok(size) => Some(size),
+            Err(err) => {
+                warn!("ignoring invalid window size file {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// Saves the window size, so the next run starts with it.
+    pub fn save(&self) -> Result<()> {
+        let Some(dir) = config_dir() else {
+            return Ok(());
+        };
+        let path = dir.join(WINDOW_SIZE_FILE_NAME);
+
+        debug!("window_size_save, path={:?}, size={:?}", path, self);
+
+        fs::create_dir_all(&dir).with_context(|| format!("Creating directory {} failed", dir.display()))?;
+        fs::write(&path, toml::to_string(self)?)
+            .with_context(|| format!("Writing window size file {} failed", path.display()))
+    }
+}
+
+impl From<LogicalSize<f64>> for WindowSize {
+    fn from(size: LogicalSize<f64>) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+impl From<WindowSize> for LogicalSize<f64> {
+    fn from(size: WindowSize) -> Self {
+        LogicalSize::new(size.width, size.height)
+    }
+}
+
+/// Returns the largest whole scale showing the screen `resolution` inside a window of the provided `size`, at least 1.
+pub fn pixel_scale(size: PhysicalSize<u32>, resolution: (u32, u32)) -> u32 {
+    (size.width / resolution.0).min(size.height / resolution.1).max(1)
+}
+
+/// Returns the window size closest to `size` showing the screen `resolution` at a whole scale, at least 1, so there
+/// are no letterbox borders.
+pub fn snap(size: PhysicalSize<u32>, resolution: (u32, u32)) -> PhysicalSize<u32> {
+    let (width, height) = resolution;
+    let scale = (size.width as f64 / width as f64)
+        .min(size.height as f64 / height as f64)
+        .round()
+        .max(1.0) as u32;
+
+    PhysicalSize::new(width * scale, height * scale)
+}
+
+#[cfg(test)]
+mod test_window_size {
+    use super::*;
+
+    const LOW: (u32, u32) = (64, 32);
+    const HIGH: (u32, u32) = (128, 64);
+
+    #[test]
+    fn test_pixel_scale() {
+        assert_eq!(
+            pixel_scale(PhysicalSize::new(640, 320), LOW),
+            10,
+            "exact multiple should be kept"
+        );
+        assert_eq!(
+            pixel_scale(PhysicalSize::new(700, 320), LOW),
+            10,
+            "scale should fit the smallest side"
+        );
+        assert_eq!(
+            pixel_scale(PhysicalSize::new(639, 319), LOW),
+            9,
+            "scale should be rounded down"
+        );
+        assert_eq!(
+            pixel_scale(PhysicalSize::new(40, 20), LOW),
+            1,
+            "scale should be at least 1"
+        );
+        assert_eq!(
+            pixel_scale(PhysicalSize::new(640, 320), HIGH),
+            5,
+            "high resolution should halve the scale"
+        );
+    }
+
+    #[test]
+    fn test_snap() {
+        assert_eq!(
+            snap(PhysicalSize::new(640, 320), LOW),
+            PhysicalSize::new(640, 320),
+            "exact multiple should be kept"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(660, 330), LOW),
+            PhysicalSize::new(640, 320),
+            "size should snap down to the nearest scale"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(680, 340), LOW),
+            PhysicalSize::new(704, 352),
+            "size should snap up to the nearest scale"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(1000, 320), LOW),
+            PhysicalSize::new(640, 320),
+            "size should snap to the smallest side"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(20, 10), LOW),
+            PhysicalSize::new(64, 32),
+            "size should be at least 1x"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(660, 330), HIGH),
+            PhysicalSize::new(640, 320),
+            "high resolution should keep the window size at half the scale"
+        );
+        assert_eq!(
+            snap(PhysicalSize::new(740, 370), HIGH),
+            PhysicalSize::new(768, 384),
+            "high resolution should snap to its own scales"
+        );
+    }
+}
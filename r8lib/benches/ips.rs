@@ -0,0 +1,52 @@
+//! Measures the machine speed, in instructions per second.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use r8lib::{assemble, Keys, Machine};
+
+/// The number of instructions executed on every benchmark iteration.
+const INSTRUCTIONS: u64 = 10_000;
+
+/// A loop mixing arithmetic, memory, key and drawing instructions.
+const SOURCE: &str = "
+    CLS
+loop:
+    ADD V0, 1
+    LD V1, V0
+    SHR V1
+    XOR V2, V1
+    SE V1, 3
+    ADD V3, V2
+    LD I, sprite
+    ADD I, V4
+    LD [I], V3
+    SKP V0
+    LD V4, 0
+    RND V5, 0x1F
+    DRW V5, V1, 2
+    LD F, V0
+    JP loop
+sprite:
+    :byte 0x3C 0x42
+";
+
+fn bench_ips(c: &mut Criterion) {
+    let rom = assemble(SOURCE).expect("source should be assembled");
+    let keys = Keys::default();
+
+    let mut group = c.benchmark_group("machine");
+    group.throughput(Throughput::Elements(INSTRUCTIONS));
+    group.bench_function("step", |b| {
+        let mut machine = Machine::with_seed(42);
+        machine.load_rom(&rom).expect("rom should be loaded");
+
+        b.iter(|| {
+            for _ in 0..INSTRUCTIONS {
+                machine.step(&keys).expect("step should succeed");
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ips);
+criterion_main!(benches);
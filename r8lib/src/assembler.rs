@@ -0,0 +1,528 @@
+//! CHIP-8's assembler, building ROMs from the instruction mnemonics.
+
+use std::collections::HashMap;
+
+use crate::error::AssembleError;
+use crate::instruction::Instruction;
+use crate::machine::ROM_INITIAL_ADDRESS;
+
+/// The known instruction mnemonics, the same ones written by the [`Instruction`] `Display` implementation.
+const MNEMONICS: [&str; 31] = [
+    "ADD", "AND", "AUDIO", "CALL", "CLS", "DB", "DRW", "DW", "EXIT", "HIGH", "JP", "LD", "LOW", "OR", "PITCH", "PLANE",
+    "RET", "RND", "SCD", "SCL", "SCR", "SCU", "SE", "SHL", "SHR", "SKNP", "SKP", "SNE", "SUB", "SUBN", "XOR",
+];
+
+/// An instruction operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Operand {
+    /// A `Vx` register.
+    Register(u8),
+    /// A `Vx-Vy` registers range.
+    Range(u8, u8),
+    /// The `I` register.
+    I,
+    /// The memory starting at location `I`, `[I]`.
+    IndirectI,
+    /// The delay timer, `DT`.
+    Dt,
+    /// The sound timer, `ST`.
+    St,
+    /// A key press, `K`.
+    K,
+    /// The small font sprites, `F`.
+    F,
+    /// The big font sprites, `HF`.
+    Hf,
+    /// The BCD representation, `B`.
+    B,
+    /// The RPL user flags, `R`.
+    R,
+    /// A 16-bit `LONG` number, label or constant.
+    Long(String),
+    /// A number, label or constant.
+    Value(String),
+}
+
+/// A source line item that emits bytes, assembled once every label is known.
+#[derive(Debug)]
+enum Item {
+    /// An instruction, with its uppercase mnemonic.
+    Instruction { mnemonic: String, operands: Vec<Operand> },
+    /// Raw bytes, from `:byte` or `DB`.
+    Bytes(Vec<String>),
+}
+
+/// Assembles the provided source into a ROM loaded at `0x200`.
+///
+/// The source has one statement per line, and comments start with `;` or `#`:
+///
+/// - Instructions use the mnemonics written by the disassembler, like `LD V1, 0x20` or `DRW V0, V1, 5`. Mnemonics and
+///   registers are case insensitive. `SHR Vx` and `SHL Vx` shift `Vx` in place.
+/// - `name:` defines a label at the current address, and can precede a statement on the same line.
+/// - `:const name value` defines a constant.
+/// - `:byte 0x3C 0x42 ...` and `DB 0x3C, 0x42, ...` emit raw bytes, and `DW 0x1234` a raw 16-bit word.
+///
+/// Numbers are decimal, `0x` prefixed hexadecimal or `0b` prefixed binary. Anywhere a number is expected, a label or
+/// constant can be used. Labels can be used before their definition, constants can't.
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssembleError> {
+    let mut symbols = HashMap::new();
+    let mut items = Vec::new();
+    let mut addr = ROM_INITIAL_ADDRESS as u32;
+
+    for (idx, text) in source.lines().enumerate() {
+        let line = idx + 1;
+        let mut text = text.split([';', '#']).next().unwrap_or_default().trim();
+
+        while let Some((name, rest)) = split_label(text) {
+            define(&mut symbols, line, name, addr)?;
+            text = rest.trim_start();
+        }
+
+        if text.is_empty() {
+            continue;
+        }
+
+        let (head, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+
+        if let Some(directive) = head.strip_prefix(':') {
+            match directive.to_ascii_lowercase().as_str() {
+                "byte" => {
+                    let values = split_values(rest);
+                    if values.is_empty() {
+                        return Err(invalid_operands(line, head));
+                    }
+
+                    addr += values.len() as u32;
+                    items.push((line, Item::Bytes(values)));
+                }
+                "const" => match rest.split_whitespace().collect::<Vec<_>>()[..] {
+                    [name, value] => {
+                        let value = resolve(&symbols, line, value, 16)?;
+                        define(&mut symbols, line, name, value)?;
+                    }
+                    _ => return Err(invalid_operands(line, head)),
+                },
+                _ => {
+                    return Err(AssembleError::UnknownDirective {
+                        line,
+                        directive: directive.to_string(),
+                    })
+                }
+            }
+
+            continue;
+        }
+
+        let mnemonic = head.to_ascii_uppercase();
+        if !MNEMONICS.contains(&mnemonic.as_str()) {
+            return Err(AssembleError::UnknownMnemonic {
+                line,
+                mnemonic: head.to_string(),
+            });
+        }
+
+        if mnemonic == "DB" {
+            let values = split_values(rest);
+            if values.is_empty() {
+                return Err(invalid_operands(line, &mnemonic));
+            }
+
+            addr += values.len() as u32;
+            items.push((line, Item::Bytes(values)));
+            continue;
+        }
+
+        let operands = parse_operands(line, &mnemonic, rest)?;
+        addr += match (mnemonic.as_str(), operands.as_slice()) {
+            ("LD", [Operand::I, Operand::Long(_)]) => 4,
+            _ => 2,
+        };
+        items.push((line, Item::Instruction { mnemonic, operands }));
+    }
+
+    let mut rom = Vec::with_capacity((addr as usize).saturating_sub(ROM_INITIAL_ADDRESS));
+
+    for (line, item) in items {
+        match item {
+            Item::Instruction { mnemonic, operands } => {
+                rom.extend_from_slice(&encode(&symbols, line, &mnemonic, &operands)?);
+            }
+            Item::Bytes(values) => {
+                for value in values {
+                    rom.push(resolve(&symbols, line, &value, 8)? as u8);
+                }
+            }
+        }
+    }
+
+    Ok(rom)
+}
+
+/// Splits a `name:` label from the start of a line.
+fn split_label(text: &str) -> Option<(&str, &str)> {
+    let (name, rest) = text.split_once(':')?;
+
+    if is_identifier(name) {
+        Some((name, rest))
+    } else {
+        None
+    }
+}
+
+/// Splits the values of a `:byte` or `DB` statement, separated by commas or whitespaces.
+fn split_values(text: &str) -> Vec<String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Tells if the provided text is a valid label or constant name.
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Defines a label or constant.
+fn define(symbols: &mut HashMap<String, u32>, line: usize, name: &str, value: u32) -> Result<(), AssembleError> {
+    if !is_identifier(name) || parse_operand(name) != Operand::Value(name.to_string()) {
+        return Err(AssembleError::InvalidName {
+            line,
+            name: name.to_string(),
+        });
+    }
+
+    if symbols.insert(name.to_string(), value).is_some() {
+        return Err(AssembleError::DuplicatedSymbol {
+            line,
+            name: name.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parses the comma separated operands of an instruction.
+fn parse_operands(line: usize, mnemonic: &str, text: &str) -> Result<Vec<Operand>, AssembleError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    text.split(',')
+        .map(str::trim)
+        .map(|operand| match operand {
+            "" => Err(invalid_operands(line, mnemonic)),
+            _ => Ok(parse_operand(operand)),
+        })
+        .collect()
+}
+
+/// Parses a single instruction operand.
+fn parse_operand(text: &str) -> Operand {
+    let upper = text.to_ascii_uppercase();
+    let register = |text: &str| match text.as_bytes() {
+        [b'V', digit] => (*digit as char).to_digit(16).map(|x| x as u8),
+        _ => None,
+    };
+
+    match upper.as_str() {
+        "I" => Operand::I,
+        "[I]" => Operand::IndirectI,
+        "DT" => Operand::Dt,
+        "ST" => Operand::St,
+        "K" => Operand::K,
+        "F" => Operand::F,
+        "HF" => Operand::Hf,
+        "B" => Operand::B,
+        "R" => Operand::R,
+        _ if upper.starts_with("LONG") && upper[4..].starts_with(char::is_whitespace) => {
+            Operand::Long(text[4..].trim().to_string())
+        }
+        _ => {
+            if let Some(x) = register(&upper) {
+                return Operand::Register(x);
+            }
+
+            match upper.split_once('-') {
+                Some((x, y)) => match (register(x.trim()), register(y.trim())) {
+                    (Some(x), Some(y)) => Operand::Range(x, y),
+                    _ => Operand::Value(text.to_string()),
+                },
+                None => Operand::Value(text.to_string()),
+            }
+        }
+    }
+}
+
+/// Resolves a number, label or constant, checking it fits in `bits` bits.
+fn resolve(symbols: &HashMap<String, u32>, line: usize, text: &str, bits: u8) -> Result<u32, AssembleError> {
+    let value = if text.starts_with(|c: char| c.is_ascii_digit()) {
+        let parsed = match (text.get(..2), text.get(2..)) {
+            (Some("0x" | "0X"), Some(hex)) => u32::from_str_radix(hex, 16),
+            (Some("0b" | "0B"), Some(bin)) => u32::from_str_radix(bin, 2),
+            _ => text.parse(),
+        };
+
+        parsed.map_err(|_| AssembleError::InvalidNumber {
+            line,
+            text: text.to_string(),
+        })?
+    } else if is_identifier(text) {
+        *symbols.get(text).ok_or_else(|| AssembleError::UndefinedSymbol {
+            line,
+            name: text.to_string(),
+        })?
+    } else {
+        return Err(AssembleError::InvalidNumber {
+            line,
+            text: text.to_string(),
+        });
+    };
+
+    if value >> bits != 0 {
+        return Err(AssembleError::ValueOutOfRange { line, value, bits });
+    }
+
+    Ok(value)
+}
+
+/// Builds an [`AssembleError::InvalidOperands`].
+fn invalid_operands(line: usize, mnemonic: &str) -> AssembleError {
+    AssembleError::InvalidOperands {
+        line,
+        mnemonic: mnemonic.to_string(),
+    }
+}
+
+/// Encodes an instruction into its bytes.
+fn encode(
+    symbols: &HashMap<String, u32>,
+    line: usize,
+    mnemonic: &str,
+    operands: &[Operand],
+) -> Result<Vec<u8>, AssembleError> {
+    use Operand::*;
+
+    let nibble = |text: &str| resolve(symbols, line, text, 4).map(|value| value as u8);
+    let byte = |text: &str| resolve(symbols, line, text, 8).map(|value| value as u8);
+    let addr = |text: &str| resolve(symbols, line, text, 12).map(|value| value as u16);
+    let word = |text: &str| resolve(symbols, line, text, 16).map(|value| value as u16);
+
+    let instruction = match (mnemonic, operands) {
+        ("CLS", []) => Instruction::Cls,
+        ("RET", []) => Instruction::Ret,
+        ("SCR", []) => Instruction::Scr,
+        ("SCL", []) => Instruction::Scl,
+        ("EXIT", []) => Instruction::Exit,
+        ("LOW", []) => Instruction::Low,
+        ("HIGH", []) => Instruction::High,
+        ("AUDIO", []) => Instruction::Audio,
+        ("SCD", [Value(n)]) => Instruction::Scd { n: nibble(n)? },
+        ("SCU", [Value(n)]) => Instruction::Scu { n: nibble(n)? },
+        ("PLANE", [Value(n)]) => Instruction::Plane { n: nibble(n)? },
+        ("JP", [Value(nnn)]) => Instruction::Jp { nnn: addr(nnn)? },
+        ("JP", [Register(0), Value(nnn)]) => Instruction::JpV0 { nnn: addr(nnn)? },
+        ("CALL", [Value(nnn)]) => Instruction::Call { nnn: addr(nnn)? },
+        ("SE", [Register(x), Value(kk)]) => Instruction::SeByte { x: *x, kk: byte(kk)? },
+        ("SE", [Register(x), Register(y)]) => Instruction::SeReg { x: *x, y: *y },
+        ("SNE", [Register(x), Value(kk)]) => Instruction::SneByte { x: *x, kk: byte(kk)? },
+        ("SNE", [Register(x), Register(y)]) => Instruction::SneReg { x: *x, y: *y },
+        ("LD", [Register(x), Value(kk)]) => Instruction::LdByte { x: *x, kk: byte(kk)? },
+        ("LD", [Register(x), Register(y)]) => Instruction::LdReg { x: *x, y: *y },
+        ("LD", [I, Value(nnn)]) => Instruction::LdI { nnn: addr(nnn)? },
+        ("LD", [I, Long(nnnn)]) => {
+            let [high, low] = word(nnnn)?.to_be_bytes();
+            return Ok(vec![0xF0, 0x00, high, low]);
+        }
+        ("LD", [Register(x), Dt]) => Instruction::LdVxDt { x: *x },
+        ("LD", [Register(x), K]) => Instruction::LdVxK { x: *x },
+        ("LD", [Dt, Register(x)]) => Instruction::LdDtVx { x: *x },
+        ("LD", [St, Register(x)]) => Instruction::LdStVx { x: *x },
+        ("LD", [F, Register(x)]) => Instruction::LdF { x: *x },
+        ("LD", [Hf, Register(x)]) => Instruction::LdHf { x: *x },
+        ("LD", [B, Register(x)]) => Instruction::LdB { x: *x },
+        ("LD", [IndirectI, Register(x)]) => Instruction::LdIVx { x: *x },
+        ("LD", [Register(x), IndirectI]) => Instruction::LdVxI { x: *x },
+        ("LD", [R, Register(x)]) => Instruction::LdRVx { x: *x },
+        ("LD", [Register(x), R]) => Instruction::LdVxR { x: *x },
+        ("LD", [IndirectI, Range(x, y)]) => Instruction::SaveRange { x: *x, y: *y },
+        ("LD", [Range(x, y), IndirectI]) => Instruction::LoadRange { x: *x, y: *y },
+        ("ADD", [Register(x), Value(kk)]) => Instruction::AddByte { x: *x, kk: byte(kk)? },
+        ("ADD", [Register(x), Register(y)]) => Instruction::AddReg { x: *x, y: *y },
+        ("ADD", [I, Register(x)]) => Instruction::AddI { x: *x },
+        ("OR", [Register(x), Register(y)]) => Instruction::Or { x: *x, y: *y },
+        ("AND", [Register(x), Register(y)]) => Instruction::And { x: *x, y: *y },
+        ("XOR", [Register(x), Register(y)]) => Instruction::Xor { x: *x, y: *y },
+        ("SUB", [Register(x), Register(y)]) => Instruction::Sub { x: *x, y: *y },
+        ("SUBN", [Register(x), Register(y)]) => Instruction::Subn { x: *x, y: *y },
+        ("SHR", [Register(x)]) => Instruction::Shr { x: *x, y: *x },
+        ("SHR", [Register(x), Register(y)]) => Instruction::Shr { x: *x, y: *y },
+        ("SHL", [Register(x)]) => Instruction::Shl { x: *x, y: *x },
+        ("SHL", [Register(x), Register(y)]) => Instruction::Shl { x: *x, y: *y },
+        ("RND", [Register(x), Value(kk)]) => Instruction::Rnd { x: *x, kk: byte(kk)? },
+        ("DRW", [Register(x), Register(y), Value(n)]) => Instruction::Drw {
+            x: *x,
+            y: *y,
+            n: nibble(n)?,
+        },
+        ("SKP", [Register(x)]) => Instruction::Skp { x: *x },
+        ("SKNP", [Register(x)]) => Instruction::Sknp { x: *x },
+        ("PITCH", [Register(x)]) => Instruction::Pitch { x: *x },
+        ("DW", [Value(opcode)]) => Instruction::Invalid { opcode: word(opcode)? },
+        _ => return Err(invalid_operands(line, mnemonic)),
+    };
+
+    Ok(instruction.encode().to_be_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod test_assembler {
+    use crate::disassemble;
+
+    use super::*;
+
+    #[test]
+    fn test_assemble() {
+        let source = "
+            ; Draws a sprite forever.
+            :const X 10
+            start:
+                CLS
+                ld v0, X          # lowercase works too
+                LD V1, 0x05
+                LD I, sprite
+                DRW V0, V1, 2
+            loop: JP loop
+            sprite:
+                :byte 0b00111100 0x42
+        ";
+
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                0x00, 0xE0, 0x60, 0x0A, 0x61, 0x05, 0xA2, 0x0C, 0xD0, 0x12, 0x12, 0x0A, 0x3C, 0x42
+            ]),
+            "rom should match"
+        );
+    }
+
+    #[test]
+    fn test_assemble_forward_labels() {
+        let source = "CALL sub\nLD I, LONG data\nsub: RET\ndata: DB 0xAB, 0xCD\nDW sub";
+
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                0x22, 0x06, 0xF0, 0x00, 0x02, 0x08, 0x00, 0xEE, 0xAB, 0xCD, 0x02, 0x06
+            ]),
+            "rom should match"
+        );
+    }
+
+    #[test]
+    fn test_assemble_ranges_and_shifts() {
+        let source = "LD [I], V1-V3\nLD V1-V3, [I]\nSHR V4\nSHL V4, V5";
+
+        assert_eq!(
+            assemble(source),
+            Ok(vec![0x51, 0x32, 0x51, 0x33, 0x84, 0x46, 0x84, 0x5E]),
+            "rom should match"
+        );
+    }
+
+    #[test]
+    fn test_assemble_every_instruction() {
+        for opcode in (0..=u16::MAX).filter(|&opcode| opcode != 0xF000) {
+            let source = Instruction::decode(opcode).to_string();
+
+            assert_eq!(
+                assemble(&source),
+                Ok(opcode.to_be_bytes().to_vec()),
+                "`{}` should be assembled as {:#06x}",
+                source,
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn test_assemble_disassembled_rom() {
+        let rom = [0x61, 0x20, 0xF0, 0x00, 0x12, 0x34, 0xD0, 0x15, 0xAB];
+
+        let source: Vec<String> = disassemble(&rom, 0x200)
+            .iter()
+            .map(|line| line.to_string()[19..].to_string())
+            .collect();
+
+        assert_eq!(assemble(&source.join("\n")), Ok(rom.to_vec()), "rom should be the same");
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let cases = [
+            (
+                "CLS\nFOO V1",
+                AssembleError::UnknownMnemonic {
+                    line: 2,
+                    mnemonic: "FOO".to_string(),
+                },
+            ),
+            (
+                ":org 0x300",
+                AssembleError::UnknownDirective {
+                    line: 1,
+                    directive: "org".to_string(),
+                },
+            ),
+            (
+                "CLS\n\nLD V1",
+                AssembleError::InvalidOperands {
+                    line: 3,
+                    mnemonic: "LD".to_string(),
+                },
+            ),
+            (
+                "JP 0x1G",
+                AssembleError::InvalidNumber {
+                    line: 1,
+                    text: "0x1G".to_string(),
+                },
+            ),
+            (
+                "JP nowhere",
+                AssembleError::UndefinedSymbol {
+                    line: 1,
+                    name: "nowhere".to_string(),
+                },
+            ),
+            (
+                "a: CLS\na: CLS",
+                AssembleError::DuplicatedSymbol {
+                    line: 2,
+                    name: "a".to_string(),
+                },
+            ),
+            (
+                "DT: CLS",
+                AssembleError::InvalidName {
+                    line: 1,
+                    name: "DT".to_string(),
+                },
+            ),
+            (
+                "CLS\nLD V0, 256",
+                AssembleError::ValueOutOfRange {
+                    line: 2,
+                    value: 256,
+                    bits: 8,
+                },
+            ),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(assemble(source), Err(expected.clone()), "should return {:?}", expected);
+        }
+    }
+}
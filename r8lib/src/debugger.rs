@@ -0,0 +1,418 @@
+//! CHIP-8's debugger, pausing the machine on breakpoints, memory watchpoints and register changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use log::debug;
+
+use crate::error::MachineError;
+use crate::instruction::Instruction;
+use crate::keyboard::Keys;
+use crate::machine::{Machine, StepOutcome, AUDIO_PATTERN_SIZE};
+use crate::vram::ALL_PLANES;
+
+/// A memory access done by an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// The instruction read the memory.
+    Read,
+    /// The instruction wrote the memory.
+    Write,
+}
+
+/// The memory accesses that trigger a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Triggers on reads.
+    Read,
+    /// Triggers on writes.
+    Write,
+    /// Triggers on reads and writes.
+    ReadWrite,
+}
+
+impl WatchKind {
+    /// Tells if the provided access triggers the watchpoint.
+    fn matches(&self, access: Access) -> bool {
+        matches!(
+            (self, access),
+            (WatchKind::Read | WatchKind::ReadWrite, Access::Read)
+                | (WatchKind::Write | WatchKind::ReadWrite, Access::Write)
+        )
+    }
+}
+
+/// A machine register that can be watched for changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Register {
+    /// A `Vx` general use register.
+    V(u8),
+    /// The `I` register.
+    I,
+}
+
+/// The reason the debugger returned control to its caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugEvent {
+    /// The requested step finished, or the machine can't go on without a key press, a display refresh or after
+    /// exiting. Carries the outcome of the last machine step.
+    Completed(StepOutcome),
+    /// The instructions budget ran out before the requested step finished.
+    Running,
+    /// The machine reached a breakpoint, before executing the instruction at `pc`.
+    Breakpoint { pc: usize },
+    /// The instruction at `pc` accessed a watched memory address.
+    Watchpoint { pc: usize, addr: usize, access: Access },
+    /// The instruction at `pc` changed a watched register.
+    RegisterChanged {
+        pc: usize,
+        register: Register,
+        old: usize,
+        new: usize,
+    },
+}
+
+/// A debugger wrapping [`Machine::step`].
+///
+/// Breakpoints pause the machine before executing the instruction at their address. Watchpoints and register watches
+/// pause it right after the instruction accessing the memory or changing the register.
+#[derive(Debug, Default, Clone)]
+pub struct Debugger {
+    /// The breakpoint addresses.
+    breakpoints: BTreeSet<usize>,
+    /// The watched memory addresses.
+    watchpoints: BTreeMap<usize, WatchKind>,
+    /// The watched registers.
+    registers: BTreeSet<Register>,
+}
+
+impl Debugger {
+    /// Creates a debugger without breakpoints or watches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a breakpoint at the provided address.
+    pub fn add_breakpoint(&mut self, addr: usize) {
+        self.breakpoints.insert(addr);
+    }
+
+    /// Removes the breakpoint at the provided address, telling if it existed.
+    pub fn remove_breakpoint(&mut self, addr: usize) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+
+    /// Returns the breakpoint addresses, in ascending order.
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Adds a watchpoint at the provided memory address, replacing any previous one.
+    pub fn add_watchpoint(&mut self, addr: usize, kind: WatchKind) {
+        self.watchpoints.insert(addr, kind);
+    }
+
+    /// Removes the watchpoint at the provided memory address, telling if it existed.
+    pub fn remove_watchpoint(&mut self, addr: usize) -> bool {
+        self.watchpoints.remove(&addr).is_some()
+    }
+
+    /// Returns the watched memory addresses, in ascending order.
+    pub fn watchpoints(&self) -> impl Iterator<Item = (usize, WatchKind)> + '_ {
+        self.watchpoints.iter().map(|(&addr, &kind)| (addr, kind))
+    }
+
+    /// Watches the provided register for changes.
+    pub fn watch_register(&mut self, register: Register) {
+        self.registers.insert(register);
+    }
+
+    /// Stops watching the provided register, telling if it was watched.
+    pub fn unwatch_register(&mut self, register: Register) -> bool {
+        self.registers.remove(&register)
+    }
+
+    /// Returns the watched registers.
+    pub fn watched_registers(&self) -> impl Iterator<Item = Register> + '_ {
+        self.registers.iter().copied()
+    }
+
+    /// Executes a single instruction. Breakpoints are ignored, but watchpoints and register watches are checked.
+    pub fn step(&mut self, machine: &mut Machine, keys: &Keys) -> Result<DebugEvent, MachineError> {
+        let pc = machine.pc;
+        let access = memory_access(machine);
+        let (v, i) = (machine.v, machine.i);
+
+        let outcome = machine.step(keys)?;
+
+        // Waiting or exited machines didn't execute anything.
+        if !matches!(outcome, StepOutcome::Executed | StepOutcome::Redraw) {
+            return Ok(DebugEvent::Completed(outcome));
+        }
+
+        if let Some((access, range)) = access {
+            let watched = self.watchpoints.range(range).find(|(_, kind)| kind.matches(access));
+
+            if let Some((&addr, _)) = watched {
+                debug!(
+                    "debugger_watchpoint, pc={:#06x}, addr={:#06x}, access={:?}",
+                    pc, addr, access
+                );
+                return Ok(DebugEvent::Watchpoint { pc, addr, access });
+            }
+        }
+
+        for &register in &self.registers {
+            let (old, new) = match register {
+                Register::V(x) => (v[x as usize] as usize, machine.v[x as usize] as usize),
+                Register::I => (i, machine.i),
+            };
+
+            if old != new {
+                debug!("debugger_register_changed, pc={:#06x}, register={:?}", pc, register);
+                return Ok(DebugEvent::RegisterChanged { pc, register, old, new });
+            }
+        }
+
+        Ok(DebugEvent::Completed(outcome))
+    }
+
+    /// Runs up to `max` instructions, pausing on breakpoints and watches. A breakpoint at the current address doesn't
+    /// pause the machine, so it can be resumed from a breakpoint.
+    pub fn run(&mut self, machine: &mut Machine, keys: &Keys, max: usize) -> Result<DebugEvent, MachineError> {
+        self.run_until(machine, keys, max, |_| false)
+    }
+
+    /// Executes the current instruction, running a called subroutine until it returns. Other instructions are
+    /// executed like in [`Debugger::step`].
+    pub fn step_over(&mut self, machine: &mut Machine, keys: &Keys, max: usize) -> Result<DebugEvent, MachineError> {
+        let depth = machine.sp;
+
+        match current_instruction(machine) {
+            Some(Instruction::Call { .. }) => self.run_until(machine, keys, max, |machine| machine.sp <= depth),
+            _ => self.step(machine, keys),
+        }
+    }
+
+    /// Runs until the current subroutine returns to its caller.
+    pub fn step_out(&mut self, machine: &mut Machine, keys: &Keys, max: usize) -> Result<DebugEvent, MachineError> {
+        let depth = machine.sp;
+
+        self.run_until(machine, keys, max, |machine| machine.sp < depth)
+    }
+
+    /// Runs up to `max` instructions, until `done` tells the requested step finished.
+    fn run_until(
+        &mut self,
+        machine: &mut Machine,
+        keys: &Keys,
+        max: usize,
+        done: impl Fn(&Machine) -> bool,
+    ) -> Result<DebugEvent, MachineError> {
+        for n in 0..max {
+            if n > 0 && self.breakpoints.contains(&machine.pc) {
+                debug!("debugger_breakpoint, pc={:#06x}", machine.pc);
+                return Ok(DebugEvent::Breakpoint { pc: machine.pc });
+            }
+
+            match self.step(machine, keys)? {
+                DebugEvent::Completed(StepOutcome::Executed | StepOutcome::Redraw) if !done(machine) => (),
+                event => return Ok(event),
+            }
+        }
+
+        Ok(DebugEvent::Running)
+    }
+}
+
+/// Returns the instruction at the machine program counter.
+fn current_instruction(machine: &Machine) -> Option<Instruction> {
+    let opcode = (*machine.ram.get(machine.pc)? as u16) << 8 | *machine.ram.get(machine.pc + 1)? as u16;
+    let instruction = Instruction::decode(opcode);
+
+    machine.platform.supports(&instruction).then_some(instruction)
+}
+
+/// Returns the memory range the instruction at the machine program counter is going to access.
+fn memory_access(machine: &Machine) -> Option<(Access, Range<usize>)> {
+    let i = machine.i;
+
+    match current_instruction(machine)? {
+        Instruction::Drw { n, .. } => {
+            let len = if n == 0 && machine.platform.supports_super_chip() {
+                32
+            } else {
+                n as usize
+            };
+            let planes = (machine.planes & ALL_PLANES).count_ones() as usize;
+
+            Some((Access::Read, i..i + len * planes))
+        }
+        Instruction::SaveRange { x, y } => Some((Access::Write, i..i + x.abs_diff(y) as usize + 1)),
+        Instruction::LoadRange { x, y } => Some((Access::Read, i..i + x.abs_diff(y) as usize + 1)),
+        Instruction::Audio => Some((Access::Read, i..i + AUDIO_PATTERN_SIZE)),
+        Instruction::LdB { .. } => Some((Access::Write, i..i + 3)),
+        Instruction::LdIVx { x } => Some((Access::Write, i..i + x as usize + 1)),
+        Instruction::LdVxI { x } => Some((Access::Read, i..i + x as usize + 1)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_debugger {
+    use crate::assemble;
+
+    use super::*;
+
+    /// No key pressed.
+    const KEYS: Keys = [false; 16];
+
+    /// Creates a machine running the provided source.
+    fn machine_with(source: &str) -> Machine {
+        let mut machine = Machine::default();
+        machine
+            .load_rom(&assemble(source).expect("source should be assembled"))
+            .expect("rom should be loaded");
+        machine
+    }
+
+    #[test]
+    fn test_run_breakpoint() {
+        let mut machine = machine_with("LD V0, 1\nLD V1, 2\nLD V2, 3\nJP 0x206");
+        let mut debugger = Debugger::new();
+        debugger.add_breakpoint(0x204);
+
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Breakpoint { pc: 0x204 }),
+            "should pause at the breakpoint"
+        );
+        assert_eq!(machine.v[2], 0, "breakpoint instruction should not be executed");
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Running),
+            "should resume from the breakpoint"
+        );
+        assert_eq!(machine.v[2], 3, "breakpoint instruction should be executed");
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut machine = machine_with("LD I, 0x300\nLD V0, 123\nLD B, V0\nDRW V0, V0, 3");
+        let mut debugger = Debugger::new();
+        debugger.add_watchpoint(0x302, WatchKind::Write);
+        debugger.add_watchpoint(0x300, WatchKind::Read);
+
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Watchpoint {
+                pc: 0x204,
+                addr: 0x302,
+                access: Access::Write
+            }),
+            "should pause after the write"
+        );
+        assert_eq!(machine.ram[0x302], 3, "write should be done");
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Watchpoint {
+                pc: 0x206,
+                addr: 0x300,
+                access: Access::Read
+            }),
+            "should pause after the read"
+        );
+    }
+
+    #[test]
+    fn test_register_watch() {
+        let mut machine = machine_with("LD V1, 5\nLD V0, 1\nLD V0, 1\nLD I, 0x300");
+        let mut debugger = Debugger::new();
+        debugger.watch_register(Register::V(0));
+        debugger.watch_register(Register::I);
+
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::RegisterChanged {
+                pc: 0x202,
+                register: Register::V(0),
+                old: 0,
+                new: 1
+            }),
+            "should pause after V0 changes"
+        );
+        assert_eq!(
+            debugger.run(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::RegisterChanged {
+                pc: 0x206,
+                register: Register::I,
+                old: 0,
+                new: 0x300
+            }),
+            "should pause after I changes, ignoring the unchanged V0"
+        );
+    }
+
+    #[test]
+    fn test_step_over() {
+        let mut machine = machine_with("CALL sub\nLD V0, 1\nexit: JP exit\nsub: LD V1, 1\nCALL inner\nRET\ninner: RET");
+        let mut debugger = Debugger::new();
+
+        assert_eq!(
+            debugger.step_over(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Completed(StepOutcome::Executed)),
+            "should complete the call"
+        );
+        assert_eq!(machine.pc, 0x202, "should stop after the call");
+        assert_eq!(machine.v[1], 1, "subroutine should be executed");
+
+        debugger
+            .step_over(&mut machine, &KEYS, 100)
+            .expect("step should succeed");
+
+        assert_eq!(machine.pc, 0x204, "should step a single instruction");
+    }
+
+    #[test]
+    fn test_step_over_breakpoint() {
+        let mut machine = machine_with("CALL sub\nexit: JP exit\nsub: LD V1, 1\nRET");
+        let mut debugger = Debugger::new();
+        debugger.add_breakpoint(0x206);
+
+        assert_eq!(
+            debugger.step_over(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Breakpoint { pc: 0x206 }),
+            "should pause inside the subroutine"
+        );
+    }
+
+    #[test]
+    fn test_step_out() {
+        let mut machine = machine_with("CALL sub\nexit: JP exit\nsub: CALL inner\nLD V1, 1\nRET\ninner: RET");
+        let mut debugger = Debugger::new();
+
+        debugger.step(&mut machine, &KEYS).expect("step should succeed");
+
+        assert_eq!(
+            debugger.step_out(&mut machine, &KEYS, 100),
+            Ok(DebugEvent::Completed(StepOutcome::Executed)),
+            "should complete the subroutine"
+        );
+        assert_eq!(machine.pc, 0x202, "should stop after the call");
+        assert_eq!(machine.v[1], 1, "subroutine should be executed");
+    }
+
+    #[test]
+    fn test_step_out_budget() {
+        let mut machine = machine_with("CALL sub\nRET\nsub: JP sub");
+        let mut debugger = Debugger::new();
+
+        debugger.step(&mut machine, &KEYS).expect("step should succeed");
+
+        assert_eq!(
+            debugger.step_out(&mut machine, &KEYS, 10),
+            Ok(DebugEvent::Running),
+            "should run out of instructions"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! CHIP-8's ROM disassembler.
+
+use std::fmt;
+
+use crate::instruction::Instruction;
+use crate::machine::Rom;
+
+/// A line of a disassembled ROM listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingLine {
+    /// The line address in memory.
+    pub addr: usize,
+    /// The line raw bytes: 2 for an instruction, 4 for `F000 nnnn` and 1 for a trailing byte.
+    pub bytes: Vec<u8>,
+    /// The decoded instruction, missing for a trailing byte.
+    pub instruction: Option<Instruction>,
+}
+
+impl fmt::Display for ListingLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex: Vec<String> = self
+            .bytes
+            .chunks(2)
+            .map(|chunk| chunk.iter().map(|b| format!("{:02X}", b)).collect())
+            .collect();
+
+        write!(f, "{:#06x}  {:<9}  ", self.addr, hex.join(" "))?;
+
+        match self.instruction {
+            Some(Instruction::LdLongI) if self.bytes.len() == 4 => {
+                write!(
+                    f,
+                    "LD I, LONG {:#06x}",
+                    (self.bytes[2] as u16) << 8 | self.bytes[3] as u16
+                )
+            }
+            Some(instruction) => write!(f, "{}", instruction),
+            None => write!(f, "DB {:#04x}", self.bytes[0]),
+        }
+    }
+}
+
+/// Disassembles the provided ROM, loaded in memory at `origin`, into a listing of its instructions.
+///
+/// The whole ROM is decoded as a sequence of instructions, so sprites and other data are listed as the instructions
+/// matching their bytes. A `F000 nnnn` instruction takes its following 16-bit word as an operand.
+pub fn disassemble(rom: &Rom, origin: usize) -> Vec<ListingLine> {
+    let mut lines = Vec::with_capacity(rom.len() / 2 + 1);
+    let mut offset = 0;
+
+    while offset < rom.len() {
+        let addr = origin + offset;
+
+        if offset + 1 >= rom.len() {
+            lines.push(ListingLine {
+                addr,
+                bytes: vec![rom[offset]],
+                instruction: None,
+            });
+            break;
+        }
+
+        let opcode = (rom[offset] as u16) << 8 | rom[offset + 1] as u16;
+        let instruction = Instruction::decode(opcode);
+        let len = if instruction == Instruction::LdLongI && offset + 4 <= rom.len() {
+            4
+        } else {
+            2
+        };
+
+        lines.push(ListingLine {
+            addr,
+            bytes: rom[offset..offset + len].to_vec(),
+            instruction: Some(instruction),
+        });
+        offset += len;
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test_disassembler {
+    use super::*;
+
+    #[test]
+    fn test_disassemble() {
+        let rom = [0x61, 0x20, 0xF0, 0x00, 0x12, 0x34, 0xD0, 0x15, 0xAB];
+
+        let lines: Vec<String> = disassemble(&rom, 0x200).iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(
+            lines,
+            [
+                "0x0200  6120       LD V1, 0x20",
+                "0x0202  F000 1234  LD I, LONG 0x1234",
+                "0x0206  D015       DRW V0, V1, 5",
+                "0x0208  AB         DB 0xab",
+            ],
+            "listing should match"
+        );
+    }
+
+    #[test]
+    fn test_disassemble_truncated_long_load() {
+        let rom = [0xF0, 0x00, 0x12];
+
+        let lines = disassemble(&rom, 0x200);
+
+        assert_eq!(lines.len(), 2, "listing should have 2 lines");
+        assert_eq!(
+            lines[0].instruction,
+            Some(Instruction::LdLongI),
+            "first line should be the long load"
+        );
+        assert_eq!(lines[1].instruction, None, "last line should be a trailing byte");
+    }
+}
@@ -0,0 +1,276 @@
+//! CHIP-8's screen persistence, turning the vram into RGBA frames that hide the sprite flicker.
+//!
+//! CHIP-8 programs move sprites by erasing and drawing them again with XOR, so a sprite is often missing from the
+//! frames shown between both draws. Like the phosphor of the original CRT screens, a [`Framebuffer`] can keep the
+//! pixels turned off glowing for a few frames, or blend every frame with the previous one.
+
+use crate::vram::{Resolution, Vram};
+
+/// How the pixels turned off linger on the screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Persistence {
+    /// Pixels are shown as they are in the vram.
+    #[default]
+    Off,
+    /// Pixels turned off fade linearly to their new colour over the provided number of frames.
+    Fade(u8),
+    /// Every frame is the average of the vram and its state in the previous frame.
+    Blend,
+}
+
+/// The CPU-side framebuffer stage, keeping the screen state across frames to apply the [`Persistence`].
+///
+/// [`Framebuffer::update`] must be called once per 60Hz frame, while [`Framebuffer::render`] paints the result with
+/// a four colour palette, indexed by pixel value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framebuffer {
+    /// The persistence applied to the frames.
+    persistence: Persistence,
+    /// The resolution of the stored frame.
+    resolution: Resolution,
+    /// The pixel values of the current frame.
+    current: Vec<u8>,
+    /// The pixel values of the previous frame, blended with [`Persistence::Blend`].
+    previous: Vec<u8>,
+    /// The last lit value of every pixel, faded with [`Persistence::Fade`].
+    lit: Vec<u8>,
+    /// The frames left for every pixel to fade out.
+    glow: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// Creates a framebuffer applying the provided persistence.
+    pub fn new(persistence: Persistence) -> Self {
+        Self {
+            persistence,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the persistence applied to the frames.
+    pub fn persistence(&self) -> Persistence {
+        self.persistence
+    }
+
+    /// Changes the persistence applied to the frames, dropping the pixels still fading.
+    pub fn set_persistence(&mut self, persistence: Persistence) {
+        self.persistence = persistence;
+        self.previous.copy_from_slice(&self.current);
+        self.glow.fill(0);
+    }
+
+    /// Returns the width and height of the stored frame.
+    pub fn size(&self) -> (usize, usize) {
+        (self.resolution.width(), self.resolution.height())
+    }
+
+    /// Tells if the next updates would render the same frame while the vram is unchanged.
+    pub fn is_settled(&self) -> bool {
+        match self.persistence {
+            Persistence::Off => true,
+            Persistence::Fade(_) => self
+                .current
+                .iter()
+                .zip(self.glow.iter())
+                .all(|(&pixel, &glow)| pixel != 0 || glow == 0),
+            Persistence::Blend => self.previous == self.current,
+        }
+    }
+
+    /// Advances a frame, taking the current vram state. A resolution change drops the previous frames.
+    pub fn update(&mut self, vram: &Vram) {
+        if vram.resolution() != self.resolution {
+            self.resolution = vram.resolution();
+            self.current = vram.to_vec();
+            self.previous = vram.to_vec();
+            self.lit = vram.to_vec();
+            self.glow = vec![0; vram.len()];
+
+            return;
+        }
+
+        self.previous.copy_from_slice(&self.current);
+        self.current.copy_from_slice(vram);
+
+        if let Persistence::Fade(frames) = self.persistence {
+            for ((&pixel, lit), glow) in self.current.iter().zip(self.lit.iter_mut()).zip(self.glow.iter_mut()) {
+                if pixel != 0 {
+                    *lit = pixel;
+                    *glow = frames;
+                } else {
+                    *glow = glow.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    /// Paints the frame into `frame`, four RGBA bytes per pixel, with the colour of every pixel value.
+    pub fn render(&self, colors: &[[u8; 4]; 4], frame: &mut [u8]) {
+        let pixels = frame.chunks_exact_mut(4).zip(self.current.iter().enumerate());
+
+        for (dst, (idx, &pixel)) in pixels {
+            let color = colors[pixel as usize & 0b11];
+
+            let color = match self.persistence {
+                Persistence::Off => color,
+                Persistence::Fade(frames) if self.glow[idx] > 0 && pixel == 0 => mix(
+                    color,
+                    colors[self.lit[idx] as usize & 0b11],
+                    self.glow[idx] as u32,
+                    frames as u32,
+                ),
+                Persistence::Fade(_) => color,
+                Persistence::Blend => mix(color, colors[self.previous[idx] as usize & 0b11], 1, 2),
+            };
+
+            dst.copy_from_slice(&color);
+        }
+    }
+}
+
+impl Default for Framebuffer {
+    /// Creates a framebuffer without persistence, holding a blank low resolution frame.
+    fn default() -> Self {
+        let len = Resolution::Low.width() * Resolution::Low.height();
+
+        Self {
+            persistence: Persistence::default(),
+            resolution: Resolution::Low,
+            current: vec![0; len],
+            previous: vec![0; len],
+            lit: vec![0; len],
+            glow: vec![0; len],
+        }
+    }
+}
+
+/// Mixes `weight / total` of the `over` colour into the `base` colour, rounding to the nearest value.
+fn mix(base: [u8; 4], over: [u8; 4], weight: u32, total: u32) -> [u8; 4] {
+    let mut color = [0; 4];
+    for (channel, (&base, &over)) in color.iter_mut().zip(base.iter().zip(over.iter())) {
+        let (base, over) = (base as u32, over as u32);
+        *channel = ((base * (total - weight) + over * weight + total / 2) / total) as u8;
+    }
+
+    color
+}
+
+#[cfg(test)]
+mod test_display {
+    use super::*;
+
+    const COLORS: [[u8; 4]; 4] = [
+        [0x00, 0x00, 0x00, 0xFF],
+        [0xFF, 0xFF, 0xFF, 0xFF],
+        [0x55, 0x55, 0x55, 0xFF],
+        [0xAA, 0xAA, 0xAA, 0xFF],
+    ];
+
+    fn render(framebuffer: &Framebuffer) -> Vec<u8> {
+        let mut frame = vec![0; framebuffer.current.len() * 4];
+        framebuffer.render(&COLORS, &mut frame);
+
+        frame
+    }
+
+    #[test]
+    fn test_off() {
+        let mut framebuffer = Framebuffer::default();
+        let mut vram = Vram::default();
+
+        vram[0] = 1;
+        framebuffer.update(&vram);
+        assert_eq!(render(&framebuffer)[..4], COLORS[1], "lit pixel should be painted");
+        assert!(framebuffer.is_settled(), "no persistence should always be settled");
+
+        vram[0] = 0;
+        framebuffer.update(&vram);
+        assert_eq!(render(&framebuffer)[..4], COLORS[0], "pixel should turn off at once");
+    }
+
+    #[test]
+    fn test_fade() {
+        let mut framebuffer = Framebuffer::new(Persistence::Fade(4));
+        let mut vram = Vram::default();
+
+        vram[0] = 1;
+        framebuffer.update(&vram);
+        assert_eq!(
+            render(&framebuffer)[..4],
+            COLORS[1],
+            "lit pixel should be painted at once"
+        );
+        assert!(framebuffer.is_settled(), "lit pixels should be settled");
+
+        vram[0] = 0;
+        let mut levels = Vec::new();
+        for _ in 0..4 {
+            framebuffer.update(&vram);
+            levels.push(render(&framebuffer)[0]);
+        }
+        assert_eq!(levels, [0xBF, 0x80, 0x40, 0x00], "pixel should fade over the frames");
+        assert!(framebuffer.is_settled(), "faded pixel should be settled");
+    }
+
+    #[test]
+    fn test_fade_relit() {
+        let mut framebuffer = Framebuffer::new(Persistence::Fade(4));
+        let mut vram = Vram::default();
+
+        vram[0] = 1;
+        framebuffer.update(&vram);
+        vram[0] = 0;
+        framebuffer.update(&vram);
+        assert!(!framebuffer.is_settled(), "fading pixel should not be settled");
+
+        vram[0] = 2;
+        framebuffer.update(&vram);
+        assert_eq!(
+            render(&framebuffer)[..4],
+            COLORS[2],
+            "relit pixel should take its new colour"
+        );
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut framebuffer = Framebuffer::new(Persistence::Blend);
+        let mut vram = Vram::default();
+
+        vram[0] = 1;
+        framebuffer.update(&vram);
+        vram[0] = 0;
+        framebuffer.update(&vram);
+        assert_eq!(
+            render(&framebuffer)[0],
+            0x80,
+            "frame should be blended with the previous one"
+        );
+        assert!(!framebuffer.is_settled(), "changed frame should not be settled");
+
+        framebuffer.update(&vram);
+        assert_eq!(render(&framebuffer)[0], 0x00, "unchanged frame should be painted as is");
+        assert!(framebuffer.is_settled(), "unchanged frame should be settled");
+    }
+
+    #[test]
+    fn test_resolution_change() {
+        let mut framebuffer = Framebuffer::new(Persistence::Blend);
+        let mut vram = Vram::default();
+
+        vram[0] = 1;
+        framebuffer.update(&vram);
+        vram.set_resolution(Resolution::High);
+        framebuffer.update(&vram);
+
+        assert_eq!(
+            framebuffer.size(),
+            (128, 64),
+            "framebuffer should follow the resolution"
+        );
+        assert!(
+            framebuffer.is_settled(),
+            "resolution change should drop the previous frame"
+        );
+    }
+}
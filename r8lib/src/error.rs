@@ -0,0 +1,89 @@
+//! CHIP-8's machine errors.
+
+use thiserror::Error;
+
+/// Faults the machine can run into while loading or executing a ROM.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MachineError {
+    /// A subroutine was called while the call stack was full.
+    #[error("stack overflow at {pc:#06x}")]
+    StackOverflow { pc: usize },
+    /// A subroutine returned while the call stack was empty.
+    #[error("stack underflow at {pc:#06x}")]
+    StackUnderflow { pc: usize },
+    /// An instruction tried to access a memory address outside the machine RAM.
+    #[error("memory access out of bounds at {addr:#06x} (pc={pc:#06x})")]
+    MemoryOutOfBounds { pc: usize, addr: usize },
+    /// The fetched opcode does not map to any known operation.
+    #[error("invalid opcode {opcode:#06x} at {pc:#06x}")]
+    InvalidOpcode { pc: usize, opcode: u16 },
+    /// The ROM doesn't fit in the machine RAM after the interpreter area.
+    #[error("ROM of {len} bytes is too large, the platform fits up to {max} bytes")]
+    RomTooLarge { len: usize, max: usize },
+}
+
+/// Failures while loading a machine save-state.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The data doesn't start with the save-state magic header.
+    #[error("invalid save-state header")]
+    InvalidMagic,
+    /// The save-state was written with an unknown format version.
+    #[error("unsupported save-state version {version}")]
+    UnsupportedVersion { version: u16 },
+    /// The data ended before the whole save-state was read.
+    #[error("truncated save-state")]
+    Truncated,
+    /// The data has bytes after the end of the save-state.
+    #[error("{len} unexpected bytes after the save-state")]
+    TrailingBytes { len: usize },
+    /// A save-state field has a value the machine can't use.
+    #[error("invalid save-state `{field}` value")]
+    InvalidValue { field: &'static str },
+}
+
+/// Failures while loading an input movie.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovieError {
+    /// The data doesn't start with the movie magic header.
+    #[error("invalid movie header")]
+    InvalidMagic,
+    /// The movie was written with an unknown format version.
+    #[error("unsupported movie version {version}")]
+    UnsupportedVersion { version: u16 },
+    /// The data length doesn't match the movie frame count.
+    #[error("movie has {len} bytes, expected {expected}")]
+    InvalidLength { len: usize, expected: usize },
+    /// A movie field has a value the machine can't use.
+    #[error("invalid movie `{field}` value")]
+    InvalidValue { field: &'static str },
+}
+
+/// Failures while assembling a source file, with the 1-based number of the line they were found.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AssembleError {
+    /// The line has an unknown mnemonic.
+    #[error("line {line}: unknown mnemonic `{mnemonic}`")]
+    UnknownMnemonic { line: usize, mnemonic: String },
+    /// The line has an unknown directive.
+    #[error("line {line}: unknown directive `:{directive}`")]
+    UnknownDirective { line: usize, directive: String },
+    /// The mnemonic or directive doesn't accept the provided operands.
+    #[error("line {line}: invalid operands for `{mnemonic}`")]
+    InvalidOperands { line: usize, mnemonic: String },
+    /// A number couldn't be parsed.
+    #[error("line {line}: invalid number `{text}`")]
+    InvalidNumber { line: usize, text: String },
+    /// A label or constant name isn't a valid identifier.
+    #[error("line {line}: invalid name `{name}`")]
+    InvalidName { line: usize, name: String },
+    /// A label or constant is used but never defined.
+    #[error("line {line}: undefined symbol `{name}`")]
+    UndefinedSymbol { line: usize, name: String },
+    /// A label or constant is defined more than once.
+    #[error("line {line}: duplicated symbol `{name}`")]
+    DuplicatedSymbol { line: usize, name: String },
+    /// An operand value doesn't fit in its instruction field.
+    #[error("line {line}: value {value:#x} doesn't fit in {bits} bits")]
+    ValueOutOfRange { line: usize, value: u32, bits: u8 },
+}
@@ -0,0 +1,386 @@
+//! CHIP-8's instruction decoding and mnemonics.
+
+use std::fmt;
+
+/// A decoded CHIP-8, SUPER-CHIP or XO-CHIP instruction.
+///
+/// Decoding doesn't depend on the machine platform, so instructions not supported by a platform are still decoded.
+/// The [`Display`](fmt::Display) implementation writes the instruction mnemonic, like `LD V1, 0x20`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    /// 00Cn (SCD nibble), scroll the display down by `n` pixels.
+    Scd { n: u8 },
+    /// 00Dn (SCU nibble), scroll the display up by `n` pixels.
+    Scu { n: u8 },
+    /// 00E0 (CLS), clear the display.
+    Cls,
+    /// 00EE (RET), return from a subroutine.
+    Ret,
+    /// 00FB (SCR), scroll the display right by 4 pixels.
+    Scr,
+    /// 00FC (SCL), scroll the display left by 4 pixels.
+    Scl,
+    /// 00FD (EXIT), exit the interpreter.
+    Exit,
+    /// 00FE (LOW), disable the high resolution mode.
+    Low,
+    /// 00FF (HIGH), enable the high resolution mode.
+    High,
+    /// 1nnn (JP addr), jump to location `nnn`.
+    Jp { nnn: u16 },
+    /// 2nnn (CALL addr), call subroutine at `nnn`.
+    Call { nnn: u16 },
+    /// 3xkk (SE Vx, byte), skip next instruction if `Vx = kk`.
+    SeByte { x: u8, kk: u8 },
+    /// 4xkk (SNE Vx, byte), skip next instruction if `Vx != kk`.
+    SneByte { x: u8, kk: u8 },
+    /// 5xy0 (SE Vx, Vy), skip next instruction if `Vx = Vy`.
+    SeReg { x: u8, y: u8 },
+    /// 5xy2 (LD [I], Vx-Vy), store registers `Vx` through `Vy` in memory starting at location `I`.
+    SaveRange { x: u8, y: u8 },
+    /// 5xy3 (LD Vx-Vy, [I]), read registers `Vx` through `Vy` from memory starting at location `I`.
+    LoadRange { x: u8, y: u8 },
+    /// 6xkk (LD Vx, byte), set `Vx = kk`.
+    LdByte { x: u8, kk: u8 },
+    /// 7xkk (ADD Vx, byte), set `Vx = Vx + kk`.
+    AddByte { x: u8, kk: u8 },
+    /// 8xy0 (LD Vx, Vy), set `Vx = Vy`.
+    LdReg { x: u8, y: u8 },
+    /// 8xy1 (OR Vx, Vy), set `Vx = Vx OR Vy`.
+    Or { x: u8, y: u8 },
+    /// 8xy2 (AND Vx, Vy), set `Vx = Vx AND Vy`.
+    And { x: u8, y: u8 },
+    /// 8xy3 (XOR Vx, Vy), set `Vx = Vx XOR Vy`.
+    Xor { x: u8, y: u8 },
+    /// 8xy4 (ADD Vx, Vy), set `Vx = Vx + Vy`, set `VF = carry`.
+    AddReg { x: u8, y: u8 },
+    /// 8xy5 (SUB Vx, Vy), set `Vx = Vx - Vy`, set `VF = NOT borrow`.
+    Sub { x: u8, y: u8 },
+    /// 8xy6 (SHR Vx, Vy), set `Vx = Vx SHR 1`.
+    Shr { x: u8, y: u8 },
+    /// 8xy7 (SUBN Vx, Vy), set `Vx = Vy - Vx`, set `VF = NOT borrow`.
+    Subn { x: u8, y: u8 },
+    /// 8xyE (SHL Vx, Vy), set `Vx = Vx SHL 1`.
+    Shl { x: u8, y: u8 },
+    /// 9xy0 (SNE Vx, Vy), skip next instruction if `Vx != Vy`.
+    SneReg { x: u8, y: u8 },
+    /// Annn (LD I, addr), set `I = nnn`.
+    LdI { nnn: u16 },
+    /// Bnnn (JP V0, addr), jump to location `nnn + V0`.
+    JpV0 { nnn: u16 },
+    /// Cxkk (RND Vx, byte), set `Vx = random byte AND kk`.
+    Rnd { x: u8, kk: u8 },
+    /// Dxyn (DRW Vx, Vy, nibble), display a n-byte sprite starting at memory location `I` at `(Vx, Vy)`.
+    Drw { x: u8, y: u8, n: u8 },
+    /// Ex9E (SKP Vx), skip next instruction if key with the value of `Vx` is pressed.
+    Skp { x: u8 },
+    /// ExA1 (SKNP Vx), skip next instruction if key with the value of `Vx` is not pressed.
+    Sknp { x: u8 },
+    /// F000 nnnn (LD I, LONG nnnn), set `I` to the 16-bit word following the instruction.
+    LdLongI,
+    /// Fn01 (PLANE n), select the drawing planes.
+    Plane { n: u8 },
+    /// F002 (AUDIO), load the audio pattern buffer from memory starting at location `I`.
+    Audio,
+    /// Fx07 (LD Vx, DT), set `Vx = delay timer value`.
+    LdVxDt { x: u8 },
+    /// Fx0A (LD Vx, K), wait for a key press, store the value of the key in `Vx`.
+    LdVxK { x: u8 },
+    /// Fx15 (LD DT, Vx), set `delay timer = Vx`.
+    LdDtVx { x: u8 },
+    /// Fx18 (LD ST, Vx), set `sound timer = Vx`.
+    LdStVx { x: u8 },
+    /// Fx1E (ADD I, Vx), set `I = I + Vx`.
+    AddI { x: u8 },
+    /// Fx29 (LD F, Vx), set `I = location of sprite for digit Vx`.
+    LdF { x: u8 },
+    /// Fx30 (LD HF, Vx), set `I = location of big sprite for digit Vx`.
+    LdHf { x: u8 },
+    /// Fx33 (LD B, Vx), store BCD representation of `Vx` in memory locations `I`, `I+1`, and `I+2`.
+    LdB { x: u8 },
+    /// Fx3A (PITCH Vx), set the audio pattern playback pitch to `Vx`.
+    Pitch { x: u8 },
+    /// Fx55 (LD [I], Vx), store registers `V0` through `Vx` in memory starting at location `I`.
+    LdIVx { x: u8 },
+    /// Fx65 (LD Vx, [I]), read registers `V0` through `Vx` from memory starting at location `I`.
+    LdVxI { x: u8 },
+    /// Fx75 (LD R, Vx), store registers `V0` through `Vx` in the RPL user flags.
+    LdRVx { x: u8 },
+    /// Fx85 (LD Vx, R), read registers `V0` through `Vx` from the RPL user flags.
+    LdVxR { x: u8 },
+    /// An opcode that doesn't map to any known instruction.
+    Invalid { opcode: u16 },
+}
+
+impl Instruction {
+    /// Decodes the provided opcode.
+    pub fn decode(opcode: u16) -> Self {
+        let nibbles = (
+            ((opcode & 0xF000) >> 12) as u8,
+            ((opcode & 0x0F00) >> 8) as u8,
+            ((opcode & 0x00F0) >> 4) as u8,
+            (opcode & 0x000F) as u8,
+        );
+
+        let nnn = opcode & 0x0FFF;
+        let kk = (opcode & 0x00FF) as u8;
+        let x = nibbles.1;
+        let y = nibbles.2;
+        let n = nibbles.3;
+
+        match nibbles {
+            (0x0, 0x0, 0xC, _) => Instruction::Scd { n },
+            (0x0, 0x0, 0xD, _) => Instruction::Scu { n },
+            (0x0, 0x0, 0xE, 0x0) => Instruction::Cls,
+            (0x0, 0x0, 0xE, 0xE) => Instruction::Ret,
+            (0x0, 0x0, 0xF, 0xB) => Instruction::Scr,
+            (0x0, 0x0, 0xF, 0xC) => Instruction::Scl,
+            (0x0, 0x0, 0xF, 0xD) => Instruction::Exit,
+            (0x0, 0x0, 0xF, 0xE) => Instruction::Low,
+            (0x0, 0x0, 0xF, 0xF) => Instruction::High,
+            (0x1, _, _, _) => Instruction::Jp { nnn },
+            (0x2, _, _, _) => Instruction::Call { nnn },
+            (0x3, _, _, _) => Instruction::SeByte { x, kk },
+            (0x4, _, _, _) => Instruction::SneByte { x, kk },
+            (0x5, _, _, 0x0) => Instruction::SeReg { x, y },
+            (0x5, _, _, 0x2) => Instruction::SaveRange { x, y },
+            (0x5, _, _, 0x3) => Instruction::LoadRange { x, y },
+            (0x6, _, _, _) => Instruction::LdByte { x, kk },
+            (0x7, _, _, _) => Instruction::AddByte { x, kk },
+            (0x8, _, _, 0x0) => Instruction::LdReg { x, y },
+            (0x8, _, _, 0x1) => Instruction::Or { x, y },
+            (0x8, _, _, 0x2) => Instruction::And { x, y },
+            (0x8, _, _, 0x3) => Instruction::Xor { x, y },
+            (0x8, _, _, 0x4) => Instruction::AddReg { x, y },
+            (0x8, _, _, 0x5) => Instruction::Sub { x, y },
+            (0x8, _, _, 0x6) => Instruction::Shr { x, y },
+            (0x8, _, _, 0x7) => Instruction::Subn { x, y },
+            (0x8, _, _, 0xE) => Instruction::Shl { x, y },
+            (0x9, _, _, 0x0) => Instruction::SneReg { x, y },
+            (0xA, _, _, _) => Instruction::LdI { nnn },
+            (0xB, _, _, _) => Instruction::JpV0 { nnn },
+            (0xC, _, _, _) => Instruction::Rnd { x, kk },
+            (0xD, _, _, _) => Instruction::Drw { x, y, n },
+            (0xE, _, 0x9, 0xE) => Instruction::Skp { x },
+            (0xE, _, 0xA, 0x1) => Instruction::Sknp { x },
+            (0xF, 0x0, 0x0, 0x0) => Instruction::LdLongI,
+            (0xF, _, 0x0, 0x1) => Instruction::Plane { n: x },
+            (0xF, 0x0, 0x0, 0x2) => Instruction::Audio,
+            (0xF, _, 0x0, 0x7) => Instruction::LdVxDt { x },
+            (0xF, _, 0x0, 0xA) => Instruction::LdVxK { x },
+            (0xF, _, 0x1, 0x5) => Instruction::LdDtVx { x },
+            (0xF, _, 0x1, 0x8) => Instruction::LdStVx { x },
+            (0xF, _, 0x1, 0xE) => Instruction::AddI { x },
+            (0xF, _, 0x2, 0x9) => Instruction::LdF { x },
+            (0xF, _, 0x3, 0x0) => Instruction::LdHf { x },
+            (0xF, _, 0x3, 0x3) => Instruction::LdB { x },
+            (0xF, _, 0x3, 0xA) => Instruction::Pitch { x },
+            (0xF, _, 0x5, 0x5) => Instruction::LdIVx { x },
+            (0xF, _, 0x6, 0x5) => Instruction::LdVxI { x },
+            (0xF, _, 0x7, 0x5) => Instruction::LdRVx { x },
+            (0xF, _, 0x8, 0x5) => Instruction::LdVxR { x },
+            _ => Instruction::Invalid { opcode },
+        }
+    }
+
+    /// Encodes the instruction back into its opcode. `F000 nnnn` is encoded without its operand word.
+    pub fn encode(&self) -> u16 {
+        let xy = |op: u16, x: u8, y: u8, n: u16| op | (x as u16) << 8 | (y as u16) << 4 | n;
+        let xkk = |op: u16, x: u8, kk: u8| op | (x as u16) << 8 | kk as u16;
+        let fx = |x: u8, kk: u16| 0xF000 | (x as u16) << 8 | kk;
+
+        match *self {
+            Instruction::Scd { n } => 0x00C0 | n as u16,
+            Instruction::Scu { n } => 0x00D0 | n as u16,
+            Instruction::Cls => 0x00E0,
+            Instruction::Ret => 0x00EE,
+            Instruction::Scr => 0x00FB,
+            Instruction::Scl => 0x00FC,
+            Instruction::Exit => 0x00FD,
+            Instruction::Low => 0x00FE,
+            Instruction::High => 0x00FF,
+            Instruction::Jp { nnn } => 0x1000 | nnn,
+            Instruction::Call { nnn } => 0x2000 | nnn,
+            Instruction::SeByte { x, kk } => xkk(0x3000, x, kk),
+            Instruction::SneByte { x, kk } => xkk(0x4000, x, kk),
+            Instruction::SeReg { x, y } => xy(0x5000, x, y, 0x0),
+            Instruction::SaveRange { x, y } => xy(0x5000, x, y, 0x2),
+            Instruction::LoadRange { x, y } => xy(0x5000, x, y, 0x3),
+            Instruction::LdByte { x, kk } => xkk(0x6000, x, kk),
+            Instruction::AddByte { x, kk } => xkk(0x7000, x, kk),
+            Instruction::LdReg { x, y } => xy(0x8000, x, y, 0x0),
+            Instruction::Or { x, y } => xy(0x8000, x, y, 0x1),
+            Instruction::And { x, y } => xy(0x8000, x, y, 0x2),
+            Instruction::Xor { x, y } => xy(0x8000, x, y, 0x3),
+            Instruction::AddReg { x, y } => xy(0x8000, x, y, 0x4),
+            Instruction::Sub { x, y } => xy(0x8000, x, y, 0x5),
+            Instruction::Shr { x, y } => xy(0x8000, x, y, 0x6),
+            Instruction::Subn { x, y } => xy(0x8000, x, y, 0x7),
+            Instruction::Shl { x, y } => xy(0x8000, x, y, 0xE),
+            Instruction::SneReg { x, y } => xy(0x9000, x, y, 0x0),
+            Instruction::LdI { nnn } => 0xA000 | nnn,
+            Instruction::JpV0 { nnn } => 0xB000 | nnn,
+            Instruction::Rnd { x, kk } => xkk(0xC000, x, kk),
+            Instruction::Drw { x, y, n } => xy(0xD000, x, y, n as u16),
+            Instruction::Skp { x } => xkk(0xE000, x, 0x9E),
+            Instruction::Sknp { x } => xkk(0xE000, x, 0xA1),
+            Instruction::LdLongI => 0xF000,
+            Instruction::Plane { n } => fx(n, 0x01),
+            Instruction::Audio => 0xF002,
+            Instruction::LdVxDt { x } => fx(x, 0x07),
+            Instruction::LdVxK { x } => fx(x, 0x0A),
+            Instruction::LdDtVx { x } => fx(x, 0x15),
+            Instruction::LdStVx { x } => fx(x, 0x18),
+            Instruction::AddI { x } => fx(x, 0x1E),
+            Instruction::LdF { x } => fx(x, 0x29),
+            Instruction::LdHf { x } => fx(x, 0x30),
+            Instruction::LdB { x } => fx(x, 0x33),
+            Instruction::Pitch { x } => fx(x, 0x3A),
+            Instruction::LdIVx { x } => fx(x, 0x55),
+            Instruction::LdVxI { x } => fx(x, 0x65),
+            Instruction::LdRVx { x } => fx(x, 0x75),
+            Instruction::LdVxR { x } => fx(x, 0x85),
+            Instruction::Invalid { opcode } => opcode,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Instruction::Scd { n } => write!(f, "SCD {}", n),
+            Instruction::Scu { n } => write!(f, "SCU {}", n),
+            Instruction::Cls => write!(f, "CLS"),
+            Instruction::Ret => write!(f, "RET"),
+            Instruction::Scr => write!(f, "SCR"),
+            Instruction::Scl => write!(f, "SCL"),
+            Instruction::Exit => write!(f, "EXIT"),
+            Instruction::Low => write!(f, "LOW"),
+            Instruction::High => write!(f, "HIGH"),
+            Instruction::Jp { nnn } => write!(f, "JP {:#05x}", nnn),
+            Instruction::Call { nnn } => write!(f, "CALL {:#05x}", nnn),
+            Instruction::SeByte { x, kk } => write!(f, "SE V{:X}, {:#04x}", x, kk),
+            Instruction::SneByte { x, kk } => write!(f, "SNE V{:X}, {:#04x}", x, kk),
+            Instruction::SeReg { x, y } => write!(f, "SE V{:X}, V{:X}", x, y),
+            Instruction::SaveRange { x, y } => write!(f, "LD [I], V{:X}-V{:X}", x, y),
+            Instruction::LoadRange { x, y } => write!(f, "LD V{:X}-V{:X}, [I]", x, y),
+            Instruction::LdByte { x, kk } => write!(f, "LD V{:X}, {:#04x}", x, kk),
+            Instruction::AddByte { x, kk } => write!(f, "ADD V{:X}, {:#04x}", x, kk),
+            Instruction::LdReg { x, y } => write!(f, "LD V{:X}, V{:X}", x, y),
+            Instruction::Or { x, y } => write!(f, "OR V{:X}, V{:X}", x, y),
+            Instruction::And { x, y } => write!(f, "AND V{:X}, V{:X}", x, y),
+            Instruction::Xor { x, y } => write!(f, "XOR V{:X}, V{:X}", x, y),
+            Instruction::AddReg { x, y } => write!(f, "ADD V{:X}, V{:X}", x, y),
+            Instruction::Sub { x, y } => write!(f, "SUB V{:X}, V{:X}", x, y),
+            Instruction::Shr { x, y } => write!(f, "SHR V{:X}, V{:X}", x, y),
+            Instruction::Subn { x, y } => write!(f, "SUBN V{:X}, V{:X}", x, y),
+            Instruction::Shl { x, y } => write!(f, "SHL V{:X}, V{:X}", x, y),
+            Instruction::SneReg { x, y } => write!(f, "SNE V{:X}, V{:X}", x, y),
+            Instruction::LdI { nnn } => write!(f, "LD I, {:#05x}", nnn),
+            Instruction::JpV0 { nnn } => write!(f, "JP V0, {:#05x}", nnn),
+            Instruction::Rnd { x, kk } => write!(f, "RND V{:X}, {:#04x}", x, kk),
+            Instruction::Drw { x, y, n } => write!(f, "DRW V{:X}, V{:X}, {}", x, y, n),
+            Instruction::Skp { x } => write!(f, "SKP V{:X}", x),
+            Instruction::Sknp { x } => write!(f, "SKNP V{:X}", x),
+            Instruction::LdLongI => write!(f, "LD I, LONG"),
+            Instruction::Plane { n } => write!(f, "PLANE {}", n),
+            Instruction::Audio => write!(f, "AUDIO"),
+            Instruction::LdVxDt { x } => write!(f, "LD V{:X}, DT", x),
+            Instruction::LdVxK { x } => write!(f, "LD V{:X}, K", x),
+            Instruction::LdDtVx { x } => write!(f, "LD DT, V{:X}", x),
+            Instruction::LdStVx { x } => write!(f, "LD ST, V{:X}", x),
+            Instruction::AddI { x } => write!(f, "ADD I, V{:X}", x),
+            Instruction::LdF { x } => write!(f, "LD F, V{:X}", x),
+            Instruction::LdHf { x } => write!(f, "LD HF, V{:X}", x),
+            Instruction::LdB { x } => write!(f, "LD B, V{:X}", x),
+            Instruction::Pitch { x } => write!(f, "PITCH V{:X}", x),
+            Instruction::LdIVx { x } => write!(f, "LD [I], V{:X}", x),
+            Instruction::LdVxI { x } => write!(f, "LD V{:X}, [I]", x),
+            Instruction::LdRVx { x } => write!(f, "LD R, V{:X}", x),
+            Instruction::LdVxR { x } => write!(f, "LD V{:X}, R", x),
+            Instruction::Invalid { opcode } => write!(f, "DW {:#06x}", opcode),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_instruction {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let cases = [
+            (0x00C3, Instruction::Scd { n: 3 }),
+            (0x00E0, Instruction::Cls),
+            (0x00EE, Instruction::Ret),
+            (0x00FF, Instruction::High),
+            (0x1345, Instruction::Jp { nnn: 0x345 }),
+            (0x3120, Instruction::SeByte { x: 1, kk: 0x20 }),
+            (0x5122, Instruction::SaveRange { x: 1, y: 2 }),
+            (0x812E, Instruction::Shl { x: 1, y: 2 }),
+            (0xA123, Instruction::LdI { nnn: 0x123 }),
+            (0xD015, Instruction::Drw { x: 0, y: 1, n: 5 }),
+            (0xE19E, Instruction::Skp { x: 1 }),
+            (0xF000, Instruction::LdLongI),
+            (0xF201, Instruction::Plane { n: 2 }),
+            (0xF30A, Instruction::LdVxK { x: 3 }),
+            (0xF485, Instruction::LdVxR { x: 4 }),
+            (0x5121, Instruction::Invalid { opcode: 0x5121 }),
+            (0x0123, Instruction::Invalid { opcode: 0x0123 }),
+        ];
+
+        for (opcode, expected) in cases {
+            assert_eq!(
+                Instruction::decode(opcode),
+                expected,
+                "{:#06x} should be decoded as {:?}",
+                opcode,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let cases = [
+            (0x00C3, "SCD 3"),
+            (0x00E0, "CLS"),
+            (0x1345, "JP 0x345"),
+            (0x2200, "CALL 0x200"),
+            (0x6120, "LD V1, 0x20"),
+            (0x5AB3, "LD VA-VB, [I]"),
+            (0x8126, "SHR V1, V2"),
+            (0xB300, "JP V0, 0x300"),
+            (0xD015, "DRW V0, V1, 5"),
+            (0xF000, "LD I, LONG"),
+            (0xF107, "LD V1, DT"),
+            (0xFF55, "LD [I], VF"),
+            (0x5121, "DW 0x5121"),
+        ];
+
+        for (opcode, expected) in cases {
+            assert_eq!(
+                Instruction::decode(opcode).to_string(),
+                expected,
+                "{:#06x} should be displayed as {}",
+                opcode,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        for opcode in 0..=u16::MAX {
+            let instruction = Instruction::decode(opcode);
+
+            assert_eq!(
+                instruction.encode(),
+                opcode,
+                "{:?} should be encoded as {:#06x}",
+                instruction,
+                opcode
+            );
+        }
+    }
+}
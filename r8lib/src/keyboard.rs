@@ -7,7 +7,7 @@ pub(crate) const NUM_KEYS: usize = 16;
 pub type Keys = [bool; NUM_KEYS];
 
 /// CHIP-8's keys.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(usize)]
 pub enum Key {
     _0 = 0x0,
@@ -29,17 +29,69 @@ pub enum Key {
 }
 
 /// CHIP-8's keyboard.
-#[derive(Debug)]
+///
+/// Besides the current keys state, it keeps the state before the last [`Keyboard::latch`] or [`Keyboard::update`], so
+/// the keys pressed or released since then can be detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Keyboard {
-    keys: Keys,
+    pub(crate) keys: Keys,
+    pub(crate) previous: Keys,
 }
 
 impl Keyboard {
+    /// Creates a Keyboard with the provided keys state, also used as the previous one.
+    pub fn with_keys(keys: &Keys) -> Self {
+        Self {
+            keys: *keys,
+            previous: *keys,
+        }
+    }
+
     /// Returns the [`Keys`] array as a reference.
     pub fn keys_as_ref(&self) -> &Keys {
         &self.keys
     }
 
+    /// Returns the [`Keys`] array before the last latch as a reference.
+    pub fn previous_as_ref(&self) -> &Keys {
+        &self.previous
+    }
+
+    /// Keeps the current keys state as the previous one, so no key is pressed or released since then.
+    pub fn latch(&mut self) {
+        self.previous = self.keys;
+    }
+
+    /// Replaces the keys state, keeping the current one as the previous one.
+    pub fn update(&mut self, keys: &Keys) {
+        self.latch();
+        self.keys = *keys;
+    }
+
+    /// Tells if the key was pressed since the last latch.
+    pub fn is_just_pressed(&self, key: Key) -> bool {
+        self.keys[key as usize] && !self.previous[key as usize]
+    }
+
+    /// Tells if the key was released since the last latch.
+    pub fn is_just_released(&self, key: Key) -> bool {
+        !self.keys[key as usize] && self.previous[key as usize]
+    }
+
+    /// Returns the lowest key pressed since the last latch.
+    pub fn first_just_pressed(&self) -> Option<u8> {
+        (0..NUM_KEYS)
+            .find(|&idx| self.keys[idx] && !self.previous[idx])
+            .map(|idx| idx as u8)
+    }
+
+    /// Returns the lowest key released since the last latch.
+    pub fn first_just_released(&self) -> Option<u8> {
+        (0..NUM_KEYS)
+            .find(|&idx| !self.keys[idx] && self.previous[idx])
+            .map(|idx| idx as u8)
+    }
+
     /// Change a keys state to pressed.
     pub fn press_key(&mut self, key: Key) {
         self.keys[key as usize] = true;
@@ -56,11 +108,32 @@ impl Default for Keyboard {
     fn default() -> Self {
         Self {
             keys: [false; NUM_KEYS],
+            previous: [false; NUM_KEYS],
+        }
+    }
+}
+
+/// The state of an `Fx0A` instruction waiting for a key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KeyWait {
+    /// The keys state since the wait started. Keys already held then are ignored until released.
+    pub(crate) keyboard: Keyboard,
+    /// The key pressed during the wait, when waiting for its release.
+    pub(crate) pressed: Option<u8>,
+}
+
+impl KeyWait {
+    /// Starts waiting for a key with the provided keys state.
+    pub(crate) fn new(keys: &Keys) -> Self {
+        Self {
+            keyboard: Keyboard::with_keys(keys),
+            pressed: None,
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::needless_range_loop)]
 mod test_keyboard {
     use super::*;
 
@@ -103,4 +176,76 @@ mod test_keyboard {
             }
         }
     }
+
+    #[test]
+    fn test_edges() {
+        let mut keyboard = Keyboard::default();
+
+        keyboard.press_key(Key::A);
+        keyboard.press_key(Key::_3);
+
+        assert!(keyboard.is_just_pressed(Key::A), "key A should be just pressed");
+        assert_eq!(
+            keyboard.first_just_pressed(),
+            Some(0x3),
+            "lowest pressed key should be 3"
+        );
+        assert_eq!(keyboard.first_just_released(), None, "no key should be released");
+
+        keyboard.latch();
+
+        assert!(
+            !keyboard.is_just_pressed(Key::A),
+            "held key A should not be just pressed"
+        );
+        assert_eq!(
+            keyboard.first_just_pressed(),
+            None,
+            "held keys should not be just pressed"
+        );
+
+        keyboard.release_key(Key::A);
+
+        assert!(keyboard.is_just_released(Key::A), "key A should be just released");
+        assert!(!keyboard.is_just_released(Key::_3), "held key 3 should not be released");
+        assert_eq!(keyboard.first_just_released(), Some(0xA), "released key should be A");
+    }
+
+    #[test]
+    fn test_update() {
+        let mut keys = [false; NUM_KEYS];
+        keys[0x5] = true;
+        let mut keyboard = Keyboard::with_keys(&keys);
+
+        assert_eq!(
+            keyboard.first_just_pressed(),
+            None,
+            "initial keys should not be just pressed"
+        );
+
+        keys[0x1] = true;
+        keys[0x5] = false;
+        keyboard.update(&keys);
+
+        assert!(keyboard.previous_as_ref()[0x5], "previous state should be kept");
+        assert_eq!(keyboard.first_just_pressed(), Some(0x1), "key 1 should be just pressed");
+        assert_eq!(
+            keyboard.first_just_released(),
+            Some(0x5),
+            "key 5 should be just released"
+        );
+
+        keyboard.update(&keys);
+
+        assert_eq!(
+            keyboard.first_just_pressed(),
+            None,
+            "unchanged keys should have no edges"
+        );
+        assert_eq!(
+            keyboard.first_just_released(),
+            None,
+            "unchanged keys should have no edges"
+        );
+    }
 }
@@ -3,9 +3,41 @@
 //#![deny(clippy::all)]
 #![allow(dead_code)]
 
+mod assembler;
+mod debugger;
+mod disassembler;
+mod display;
+mod error;
+mod instruction;
 mod keyboard;
 mod machine;
+mod movie;
 mod operations;
+mod platform;
+mod quirks;
+mod rng;
+mod state;
+mod synth;
+mod timing;
+mod tracer;
+mod view;
+mod vram;
 
+pub use crate::assembler::*;
+pub use crate::debugger::*;
+pub use crate::disassembler::*;
+pub use crate::display::*;
+pub use crate::error::*;
+pub use crate::instruction::*;
 pub use crate::keyboard::*;
 pub use crate::machine::*;
+pub use crate::movie::*;
+pub use crate::platform::*;
+pub use crate::quirks::*;
+pub use crate::rng::SeededRng;
+pub use crate::state::{STATE_MAGIC, STATE_VERSION};
+pub use crate::synth::*;
+pub use crate::timing::*;
+pub use crate::tracer::Tracer;
+pub use crate::view::*;
+pub use crate::vram::*;
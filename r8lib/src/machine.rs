@@ -2,44 +2,74 @@
 
 use std::fmt;
 
-use log::debug;
+use log::{debug, warn};
 use rand::RngCore;
 
-use crate::keyboard::Keys;
+use crate::error::{MachineError, StateError};
+use crate::instruction::Instruction;
+use crate::keyboard::{KeyWait, Keys};
 use crate::operations::*;
+use crate::platform::Platform;
+use crate::quirks::Quirks;
+use crate::rng::{MachineRng, SeededRng};
+use crate::state;
+use crate::timing::Timing;
+use crate::tracer::Tracer;
+use crate::view::MachineState;
+use crate::vram::{Vram, FIRST_PLANE};
 
 // Sizes
 
 /// Machine RAM size.
 pub(crate) const MEMORY_SIZE: usize = 4096;
+/// XO-CHIP machine RAM size.
+pub(crate) const XO_MEMORY_SIZE: usize = 65536;
 /// Machine Stack size.
 pub(crate) const STACK_SIZE: usize = 16;
 /// Font in memory size.
 pub(crate) const FONT_MEMORY_SIZE: usize = 80;
 /// Font character size.
 pub(crate) const FONT_CHAR_SIZE: usize = 5;
+/// Big font in memory size.
+pub(crate) const BIG_FONT_MEMORY_SIZE: usize = 160;
+/// Big font character size.
+pub(crate) const BIG_FONT_CHAR_SIZE: usize = 10;
 /// Sprite width.
 pub(crate) const SPRITE_WIDTH: usize = 8;
+/// Big sprite width and height.
+pub(crate) const BIG_SPRITE_SIZE: usize = 16;
 /// VRAM width.
 pub(crate) const VRAM_WIDTH: usize = 64;
 /// VRAM height.
 pub(crate) const VRAM_HEIGHT: usize = 32;
+/// High resolution VRAM width.
+pub(crate) const HIRES_VRAM_WIDTH: usize = 128;
+/// High resolution VRAM height.
+pub(crate) const HIRES_VRAM_HEIGHT: usize = 64;
+/// XO-CHIP audio pattern buffer size.
+pub(crate) const AUDIO_PATTERN_SIZE: usize = 16;
 
 // Registers
 
 /// Number of general use registers.
 pub(crate) const GENERAL_REGISTER_NUMBER: usize = 16;
+/// Number of RPL user flags.
+pub(crate) const RPL_FLAGS_NUMBER: usize = 16;
 /// Zero register index.
 pub(crate) const ZERO: usize = 0x0;
 /// Carry register index.
 pub(crate) const CARRY: usize = 0xF;
 /// Initial PC value.
 pub(crate) const INITIAL_PC_VALUE: usize = 0x200;
+/// Initial XO-CHIP pitch register value, playing the audio pattern at 4000Hz.
+pub(crate) const INITIAL_PITCH_VALUE: u8 = 64;
 
 // Memory Addresses and Values
 
 /// Font initial address in memory.
 pub(crate) const FONT_INITIAL_ADDRESS: usize = 0;
+/// Big font initial address in memory.
+pub(crate) const BIG_FONT_INITIAL_ADDRESS: usize = FONT_INITIAL_ADDRESS + FONT_MEMORY_SIZE;
 /// ROM initial address in memory.
 pub(crate) const ROM_INITIAL_ADDRESS: usize = 512;
 /// Font values.
@@ -61,18 +91,74 @@ pub(crate) const FONT: [u8; FONT_MEMORY_SIZE] = [
     0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
     0xF0, 0x80, 0xF0, 0x80, 0x80, // F
 ];
+/// Big font values.
+pub(crate) const BIG_FONT: [u8; BIG_FONT_MEMORY_SIZE] = [
+    0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, // 0
+    0x18, 0x78, 0x78, 0x18, 0x18, 0x18, 0x18, 0x18, 0xFF, 0xFF, // 1
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // 2
+    0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 3
+    0xC3, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0x03, 0x03, // 4
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 5
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 6
+    0xFF, 0xFF, 0x03, 0x03, 0x06, 0x0C, 0x18, 0x18, 0x18, 0x18, // 7
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, // 8
+    0xFF, 0xFF, 0xC3, 0xC3, 0xFF, 0xFF, 0x03, 0x03, 0xFF, 0xFF, // 9
+    0x7E, 0xFF, 0xC3, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, // A
+    0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, 0xC3, 0xC3, 0xFC, 0xFC, // B
+    0x3C, 0xFF, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0xFF, 0x3C, // C
+    0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC, // D
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, // E
+    0xFF, 0xFF, 0xC0, 0xC0, 0xFF, 0xFF, 0xC0, 0xC0, 0xC0, 0xC0, // F
+];
 
-/// An array of [`bool`]s that represents the CHIP-8's screen.
-pub type Vram = [bool; VRAM_WIDTH * VRAM_HEIGHT];
 /// An array of [`u8`]s that represents a CHIP-8's ROM.
 pub type Rom = [u8];
+/// The SHA-1 digest of a CHIP-8's ROM, used to identify it.
+pub type RomHash = [u8; 20];
 
-/// An array of [`u8`]s that represents the CHIP-8's RAM.
-pub(crate) type Ram = [u8; MEMORY_SIZE];
+/// A vector of [`u8`]s that represents the CHIP-8's RAM, sized by the machine [`Platform`].
+pub(crate) type Ram = Vec<u8>;
 /// An array of [`u16`]s that represents the CHIP-8's stack.
 pub(crate) type Stack = [u16; STACK_SIZE];
 /// An array of [`u8`]s that represents the CHIP-8's general use registers.
 pub(crate) type GeneralRegisterBank = [u8; GENERAL_REGISTER_NUMBER];
+/// An array of [`u8`]s that represents the SUPER-CHIP's RPL user flags.
+pub(crate) type RplFlags = [u8; RPL_FLAGS_NUMBER];
+/// An array of [`u8`]s that represents the XO-CHIP's audio pattern, one bit per sample.
+pub type AudioPattern = [u8; AUDIO_PATTERN_SIZE];
+
+/// The outcome of a successful machine step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepOutcome {
+    /// An instruction was executed.
+    Executed,
+    /// An instruction was executed and the screen should be redrawn.
+    Redraw,
+    /// The machine is waiting for a key press and did not advance.
+    WaitingInput,
+    /// The machine is waiting for the next display refresh and did not advance.
+    WaitingDisplay,
+    /// The machine exited and will not run any other instruction.
+    Exited,
+}
+
+/// The result of running a frame with [`Machine::run_frame`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FrameResult {
+    /// The number of executed instructions.
+    pub instructions: u16,
+    /// Tells if the screen changed and should be redrawn.
+    pub redraw: bool,
+    /// Tells if the beep should be active until the next frame.
+    pub beep: bool,
+    /// Tells if the machine exited and will not run any other instruction.
+    pub exited: bool,
+}
+
+/// Returns the SHA-1 digest of the provided ROM.
+pub fn rom_hash(rom: &Rom) -> RomHash {
+    sha1_smol::Sha1::from(rom).digest().bytes()
+}
 
 /// Represents the CHIP-8 machine.
 pub struct Machine {
@@ -84,6 +170,8 @@ pub struct Machine {
     pub(crate) stack: Stack,
     /// The machine general register representation. These are the `vN` registers, where N is in range `[0, 8]`.
     pub(crate) v: GeneralRegisterBank,
+    /// The machine RPL user flags, used by SUPER-CHIP to persist registers.
+    pub(crate) rpl: RplFlags,
     /// The machine `I` register.
     pub(crate) i: usize,
     /// The machine Program Counter.
@@ -94,49 +182,375 @@ pub struct Machine {
     pub(crate) dt: u8,
     /// The machine Sound Timer.
     pub(crate) st: u8,
+    /// The bitmask of the screen planes selected for drawing.
+    pub(crate) planes: u8,
+    /// The XO-CHIP audio pattern buffer.
+    pub(crate) audio_pattern: AudioPattern,
+    /// The XO-CHIP audio pattern playback pitch.
+    pub(crate) pitch: u8,
     /// A flag to tell if the screen should be redrawn.
     pub(crate) draw: bool,
+    /// A flag to tell if the display was refreshed since the last sprite draw.
+    pub(crate) vblank: bool,
+    /// A flag to tell if the machine exited.
+    pub(crate) halted: bool,
+    /// The platform, or instruction set, used by the machine.
+    pub(crate) platform: Platform,
+    /// The behaviours used by the ambiguous instructions.
+    pub(crate) quirks: Quirks,
     /// A random number generator.
-    pub(crate) rng: Box<dyn RngCore>,
+    pub(crate) rng: MachineRng,
+    /// An optional tracer, writing every executed instruction.
+    pub(crate) tracer: Option<Tracer>,
+    /// The timing model charging the executed instructions.
+    pub(crate) timing: Timing,
+    /// The cycles spent in the current frame, as charged by the timing model.
+    pub(crate) frame_cycles: u32,
+    /// The state of the `Fx0A` instruction being waited, if any.
+    pub(crate) key_wait: Option<KeyWait>,
 }
 
 impl Machine {
     /// Creates a new Machine with the provided RNG.
     pub fn new(rng: Box<dyn RngCore>) -> Self {
         Self {
-            rng: Box::new(rng),
+            rng: MachineRng::Custom(rng),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a new Machine using a [`SeededRng`] created from the provided seed, so every run of a ROM with the same
+    /// inputs behaves the same way.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: MachineRng::Seeded(SeededRng::new(seed)),
             ..Default::default()
         }
     }
 
+    /// Replaces the machine RNG by a [`SeededRng`] created from the provided seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        debug!("set_seed, seed={}", seed);
+
+        self.rng = MachineRng::Seeded(SeededRng::new(seed));
+    }
+
+    /// Returns the platform used by the machine.
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Changes the platform used by the machine. The quirks are also replaced by the platform ones and the RAM is
+    /// resized to the platform memory size.
+    pub fn set_platform(&mut self, platform: Platform) {
+        debug!("set_platform, platform={:?}", platform);
+
+        self.platform = platform;
+        self.quirks = platform.quirks();
+        self.ram.resize(platform.memory_size(), 0);
+    }
+
+    /// Returns the quirks used by the machine.
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Changes the quirks used by the machine.
+    pub fn set_quirks(&mut self, quirks: Quirks) {
+        debug!("set_quirks, quirks={:?}", quirks);
+
+        self.quirks = quirks;
+        self.key_wait = None;
+    }
+
+    /// Returns the timing model used by the machine.
+    pub fn timing(&self) -> Timing {
+        self.timing
+    }
+
+    /// Changes the timing model used by the machine, starting a new frame.
+    pub fn set_timing(&mut self, timing: Timing) {
+        debug!("set_timing, timing={:?}", timing);
+
+        self.timing = timing;
+        self.frame_cycles = 0;
+    }
+
     /// Returns a reference to the machine VRAM. This value should be used to draw the screen.
     pub fn vram_as_ref(&self) -> &Vram {
         &self.vram
     }
 
+    /// Returns a read-only view of the machine registers, stack and memory.
+    pub fn state(&self) -> MachineState<'_> {
+        MachineState::new(self)
+    }
+
+    /// Sets the `Vx` general use register.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` isn't a register index, from `0x0` to `0xF`.
+    pub fn set_v(&mut self, x: usize, value: u8) {
+        debug!("set_v, x={}, value={}", x, value);
+
+        self.v[x] = value;
+    }
+
+    /// Sets the `I` register.
+    pub fn set_i(&mut self, value: usize) {
+        debug!("set_i, value={:#06x}", value);
+
+        self.i = value;
+    }
+
+    /// Sets the program counter, returning a [`MachineError::MemoryOutOfBounds`] if no instruction fits at `addr`.
+    pub fn set_pc(&mut self, addr: usize) -> Result<(), MachineError> {
+        debug!("set_pc, addr={:#06x}", addr);
+
+        self.check_ram_range(addr, 2)?;
+        self.pc = addr;
+        self.key_wait = None;
+
+        Ok(())
+    }
+
+    /// Sets the delay timer.
+    pub fn set_dt(&mut self, value: u8) {
+        debug!("set_dt, value={}", value);
+
+        self.dt = value;
+    }
+
+    /// Sets the sound timer.
+    pub fn set_st(&mut self, value: u8) {
+        debug!("set_st, value={}", value);
+
+        self.st = value;
+    }
+
+    /// Writes the provided bytes into the RAM starting at `addr`, returning a [`MachineError::MemoryOutOfBounds`] if
+    /// they don't fit.
+    pub fn write_ram(&mut self, addr: usize, data: &[u8]) -> Result<(), MachineError> {
+        debug!("write_ram, addr={:#06x}, len={}", addr, data.len());
+
+        self.check_ram_range(addr, data.len())?;
+        self.ram[addr..addr + data.len()].copy_from_slice(data);
+
+        Ok(())
+    }
+
+    /// Returns the XO-CHIP audio pattern, 128 1-bit samples played while the sound timer is active.
+    pub fn audio_pattern(&self) -> &AudioPattern {
+        &self.audio_pattern
+    }
+
+    /// Returns the XO-CHIP audio pattern pitch register.
+    pub fn pitch(&self) -> u8 {
+        self.pitch
+    }
+
+    /// Returns the XO-CHIP audio pattern playback rate, in samples per second, computed from the pitch register.
+    pub fn audio_playback_rate(&self) -> f32 {
+        4000.0 * 2f32.powf((self.pitch as f32 - 64.0) / 48.0)
+    }
+
+    /// Returns the XO-CHIP audio pattern and its playback rate, when the beep should play it instead of a tone.
+    ///
+    /// Only XO-CHIP machines play patterns, and only once a ROM loaded a non-silent one with `F002`.
+    pub fn audio(&self) -> Option<(AudioPattern, f32)> {
+        let playing = self.platform.supports_xo_chip() && self.audio_pattern.iter().any(|&byte| byte != 0);
+
+        playing.then(|| (self.audio_pattern, self.audio_playback_rate()))
+    }
+
+    /// Saves the whole machine state into a versioned binary save-state. The RNG state is only saved when the machine
+    /// uses a [`SeededRng`].
+    ///
+    /// The format layout is described by [`STATE_VERSION`](crate::STATE_VERSION).
+    pub fn save_state(&self) -> Vec<u8> {
+        debug!("save_state");
+
+        state::encode(self)
+    }
+
+    /// Restores the machine state from a save-state created by [`Machine::save_state`].
+    ///
+    /// The current RNG is kept when the save-state has no RNG state. Returns a [`StateError`] when the data isn't a
+    /// valid save-state. In that case the machine is left untouched.
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), StateError> {
+        debug!("load_state, len={}", data.len());
+
+        let mut loaded = state::decode(data)?;
+        if !matches!(loaded.rng, MachineRng::Seeded(_)) {
+            std::mem::swap(&mut loaded.rng, &mut self.rng);
+        }
+        loaded.tracer = self.tracer.take();
+        loaded.timing = self.timing;
+        *self = loaded;
+
+        Ok(())
+    }
+
     /// Loads the provided CHIP-8's ROM into the machine RAM/memory.
-    pub fn load_rom(&mut self, rom: &Rom) {
+    ///
+    /// Returns [`MachineError::RomTooLarge`] when the ROM doesn't fit in the RAM of the current platform, leaving the
+    /// machine untouched.
+    pub fn load_rom(&mut self, rom: &Rom) -> Result<(), MachineError> {
         debug!("load_rom, rom={:?}", rom);
 
+        let max = self.ram.len() - ROM_INITIAL_ADDRESS;
+        if rom.len() > max {
+            return Err(MachineError::RomTooLarge { len: rom.len(), max });
+        }
+
         self.ram[ROM_INITIAL_ADDRESS..ROM_INITIAL_ADDRESS + rom.len()].copy_from_slice(rom);
+        self.key_wait = None;
 
         debug!("load_rom_finished, ram={:?}", self.ram);
+
+        Ok(())
     }
 
     /// Does a machine instruction step.
-    pub fn step(&mut self, keys: &Keys) {
+    ///
+    /// Returns a [`MachineError`] when the instruction can't be executed. In that case the machine is left untouched,
+    /// pointing to the faulty instruction. Timing models with a frame budget return [`StepOutcome::WaitingDisplay`]
+    /// once the frame cycles are spent, until the next [`Machine::update_timers`].
+    pub fn step(&mut self, keys: &Keys) -> Result<StepOutcome, MachineError> {
         debug!("step_pc, pc={:#06x?}", self.pc);
 
+        if self.halted {
+            return Ok(StepOutcome::Exited);
+        }
+
+        if self
+            .timing
+            .frame_cycles()
+            .is_some_and(|budget| self.frame_cycles >= budget)
+        {
+            return Ok(StepOutcome::WaitingDisplay);
+        }
+
+        self.check_ram_range(self.pc, 2)?;
+
         let instr = (self.ram[self.pc] as u16) << 8 | self.ram[self.pc + 1] as u16;
 
         debug!("step_instruction, instr={:#06x?}", instr);
 
-        self.draw = false;
-        self.run_instruction(instr, keys);
+        let trace = self.tracer.as_ref().map(|tracer| tracer.line(&self.state(), instr));
+
+        let pc = self.pc;
+        let outcome = self.run_instruction(instr, keys);
+
+        // A failing instruction leaves the machine untouched, so the draw flag is only updated on success.
+        if let Ok(outcome) = outcome {
+            self.draw = outcome == StepOutcome::Redraw;
+        }
+
+        if matches!(outcome, Ok(StepOutcome::Executed | StepOutcome::Redraw)) {
+            self.charge_cycles(instr, pc);
+        }
+
+        // Waiting instructions are traced once they are executed.
+        if let Some(line) = trace {
+            if !matches!(outcome, Ok(StepOutcome::WaitingInput | StepOutcome::WaitingDisplay)) {
+                self.write_trace(&line);
+            }
+        }
+
+        outcome
     }
 
-    /// Updates the timers state.
+    /// Runs a 60Hz frame: executes up to `instructions_per_frame` instructions, then updates the timers once.
+    ///
+    /// The frame ends early when the machine exits or waits for the next display refresh, which is also how timing
+    /// models with a frame budget end it. Returns a [`MachineError`] when an instruction can't be executed, leaving
+    /// the timers untouched.
+    pub fn run_frame(&mut self, keys: &Keys, instructions_per_frame: u16) -> Result<FrameResult, MachineError> {
+        debug!("run_frame, instructions_per_frame={}", instructions_per_frame);
+
+        let mut result = FrameResult::default();
+
+        for _ in 0..instructions_per_frame {
+            match self.step(keys)? {
+                StepOutcome::Executed => result.instructions += 1,
+                StepOutcome::Redraw => {
+                    result.instructions += 1;
+                    result.redraw = true;
+                }
+                // The keys don't change until the next frame, so nothing else runs in this one.
+                StepOutcome::WaitingInput | StepOutcome::WaitingDisplay => break,
+                StepOutcome::Exited => {
+                    result.exited = true;
+                    break;
+                }
+            }
+        }
+
+        if !result.exited {
+            self.update_timers();
+        }
+        result.beep = self.should_beep();
+
+        debug!("run_frame_result, result={:?}", result);
+
+        Ok(result)
+    }
+
+    /// Installs or removes the tracer, writing a line for every executed instruction.
+    pub fn set_tracer(&mut self, tracer: Option<Tracer>) {
+        debug!("set_tracer, tracer={:?}", tracer);
+
+        self.tracer = tracer;
+    }
+
+    /// Returns the installed tracer.
+    pub fn tracer(&self) -> Option<&Tracer> {
+        self.tracer.as_ref()
+    }
+
+    /// Returns the installed tracer as mutable, to flush it.
+    pub fn tracer_mut(&mut self) -> Option<&mut Tracer> {
+        self.tracer.as_mut()
+    }
+
+    /// Charges the cycles of an executed instruction, previously at `pc`, to the current frame.
+    fn charge_cycles(&mut self, instr: u16, pc: usize) {
+        if self.timing == Timing::Fixed {
+            return;
+        }
+
+        let skipped = self.pc > pc + 2;
+        self.frame_cycles += self.timing.instruction_cycles(&Instruction::decode(instr), skipped);
+
+        // The display interrupt happens at the frame start, so a later `Dxyn` waits for the next one.
+        if self.timing.waits_display() {
+            self.vblank = false;
+        }
+
+        debug!("charge_cycles, frame_cycles={}", self.frame_cycles);
+    }
+
+    /// Writes a trace line, removing the tracer when it fails.
+    fn write_trace(&mut self, line: &str) {
+        if let Some(tracer) = &mut self.tracer {
+            if let Err(err) = tracer.write_line(line) {
+                warn!("trace_error, error={}", err);
+                self.tracer = None;
+            }
+        }
+    }
+
+    /// Updates the timers state. It should be called at 60Hz, the same rate of a display refresh.
+    ///
+    /// Cycles charged beyond the frame budget of the timing model are carried into the next frame.
     pub fn update_timers(&mut self) {
+        self.vblank = true;
+        if let Some(budget) = self.timing.frame_cycles() {
+            self.frame_cycles = self.frame_cycles.saturating_sub(budget);
+        }
+
         if self.dt > 0 {
             self.dt -= 1;
         }
@@ -162,83 +576,123 @@ impl Machine {
         self.st > 0
     }
 
+    /// Checks if `len` bytes starting at `addr` fit inside the machine RAM.
+    pub(crate) fn check_ram_range(&self, addr: usize, len: usize) -> Result<(), MachineError> {
+        if addr + len > self.ram.len() {
+            return Err(MachineError::MemoryOutOfBounds {
+                pc: self.pc,
+                addr: addr.max(self.ram.len()),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the size, in bytes, of the operation after the current one plus the current one size. XO-CHIP
+    /// platforms skip the whole 4-byte F000 nnnn operation.
+    fn next_instruction_size(&self, xo: bool) -> usize {
+        let next = (self.ram.get(self.pc + 2), self.ram.get(self.pc + 3));
+
+        if xo && next == (Some(&0xF0), Some(&0x00)) {
+            return 6;
+        }
+
+        4
+    }
+
     /// Runs the operation extracted from the machine RAM.
-    fn run_instruction(&mut self, instr: u16, keys: &Keys) {
+    fn run_instruction(&mut self, instr: u16, keys: &Keys) -> Result<StepOutcome, MachineError> {
         debug!("run_instruction, instr={:#06x?}, keys={:?}", instr, keys);
 
-        let nibbles = (
-            ((instr & 0xF000) >> 12) as u8,
-            ((instr & 0x0F00) >> 8) as u8,
-            ((instr & 0x00F0) >> 4) as u8,
-            (instr & 0x000F) as u8,
-        );
-
-        debug!("run_instruction_nibbles, nibbles={:?}", nibbles);
-
-        let nnn = instr & 0x0FFF;
-        let kk = (instr & 0x00FF) as u8;
-        let x = nibbles.1;
-        let y = nibbles.2;
-        let n = nibbles.3;
-
-        let op: Box<dyn Operation> = match nibbles {
-            (0x0, 0x0, 0xE, 0x0) => Box::new(Op00e0::new()),
-            (0x0, 0x0, 0xE, 0xE) => Box::new(Op00ee::new()),
-            (0x1, _, _, _) => Box::new(Op1nnn::new(nnn)),
-            (0x2, _, _, _) => Box::new(Op2nnn::new(nnn)),
-            (0x3, _, _, _) => Box::new(Op3xkk::new(x, kk)),
-            (0x4, _, _, _) => Box::new(Op4xkk::new(x, kk)),
-            (0x5, _, _, 0x0) => Box::new(Op5xy0::new(x, y)),
-            (0x6, _, _, _) => Box::new(Op6xkk::new(x, kk)),
-            (0x7, _, _, _) => Box::new(Op7xkk::new(x, kk)),
-            (0x8, _, _, 0x0) => Box::new(Op8xy0::new(x, y)),
-            (0x8, _, _, 0x1) => Box::new(Op8xy1::new(x, y)),
-            (0x8, _, _, 0x2) => Box::new(Op8xy2::new(x, y)),
-            (0x8, _, _, 0x3) => Box::new(Op8xy3::new(x, y)),
-            (0x8, _, _, 0x4) => Box::new(Op8xy4::new(x, y)),
-            (0x8, _, _, 0x5) => Box::new(Op8xy5::new(x, y)),
-            (0x8, _, _, 0x6) => Box::new(Op8xy6::new(x)),
-            (0x8, _, _, 0x7) => Box::new(Op8xy7::new(x, y)),
-            (0x8, _, _, 0xE) => Box::new(Op8xye::new(x)),
-            (0x9, _, _, 0x0) => Box::new(Op9xy0::new(x, y)),
-            (0xA, _, _, _) => Box::new(Opannn::new(nnn)),
-            (0xB, _, _, _) => Box::new(Opbnnn::new(nnn)),
-            (0xC, _, _, _) => Box::new(Opcxkk::new(x, kk)),
-            (0xD, _, _, _) => Box::new(Opdxyn::new(x, y, n)),
-            (0xE, _, 0x9, 0xE) => Box::new(Opex9e::new(x, keys)),
-            (0xE, _, 0xA, 0x1) => Box::new(Opexa1::new(x, keys)),
-            (0xF, _, 0x0, 0x7) => Box::new(Opfx07::new(x)),
-            (0xF, _, 0x0, 0xA) => Box::new(Opfx0a::new(x, keys)),
-            (0xF, _, 0x1, 0x5) => Box::new(Opfx15::new(x)),
-            (0xF, _, 0x1, 0x8) => Box::new(Opfx18::new(x)),
-            (0xF, _, 0x1, 0xE) => Box::new(Opfx1e::new(x)),
-            (0xF, _, 0x2, 0x9) => Box::new(Opfx29::new(x)),
-            (0xF, _, 0x3, 0x3) => Box::new(Opfx33::new(x)),
-            (0xF, _, 0x5, 0x5) => Box::new(Opfx55::new(x)),
-            (0xF, _, 0x6, 0x5) => Box::new(Opfx65::new(x)),
-            _ => Box::new(OpInvalid::new()),
+        let instruction = match Instruction::decode(instr) {
+            instruction if self.platform.supports(&instruction) => instruction,
+            _ => Instruction::Invalid { opcode: instr },
         };
 
-        let action = op.exec(self);
+        debug!("run_instruction_decoded, instruction={:?}", instruction);
+
+        let xo = self.platform.supports_xo_chip();
+
+        let action = match instruction {
+            Instruction::Scd { n } => Op00cn::new(n).exec(self),
+            Instruction::Scu { n } => Op00dn::new(n).exec(self),
+            Instruction::Cls => Op00e0::new().exec(self),
+            Instruction::Ret => Op00ee::new().exec(self),
+            Instruction::Scr => Op00fb::new().exec(self),
+            Instruction::Scl => Op00fc::new().exec(self),
+            Instruction::Exit => Op00fd::new().exec(self),
+            Instruction::Low => Op00fe::new().exec(self),
+            Instruction::High => Op00ff::new().exec(self),
+            Instruction::Jp { nnn } => Op1nnn::new(nnn).exec(self),
+            Instruction::Call { nnn } => Op2nnn::new(nnn).exec(self),
+            Instruction::SeByte { x, kk } => Op3xkk::new(x, kk).exec(self),
+            Instruction::SneByte { x, kk } => Op4xkk::new(x, kk).exec(self),
+            Instruction::SeReg { x, y } => Op5xy0::new(x, y).exec(self),
+            Instruction::SaveRange { x, y } => Op5xy2::new(x, y).exec(self),
+            Instruction::LoadRange { x, y } => Op5xy3::new(x, y).exec(self),
+            Instruction::LdByte { x, kk } => Op6xkk::new(x, kk).exec(self),
+            Instruction::AddByte { x, kk } => Op7xkk::new(x, kk).exec(self),
+            Instruction::LdReg { x, y } => Op8xy0::new(x, y).exec(self),
+            Instruction::Or { x, y } => Op8xy1::new(x, y).exec(self),
+            Instruction::And { x, y } => Op8xy2::new(x, y).exec(self),
+            Instruction::Xor { x, y } => Op8xy3::new(x, y).exec(self),
+            Instruction::AddReg { x, y } => Op8xy4::new(x, y).exec(self),
+            Instruction::Sub { x, y } => Op8xy5::new(x, y).exec(self),
+            Instruction::Shr { x, y } => Op8xy6::new(x, y).exec(self),
+            Instruction::Subn { x, y } => Op8xy7::new(x, y).exec(self),
+            Instruction::Shl { x, y } => Op8xye::new(x, y).exec(self),
+            Instruction::SneReg { x, y } => Op9xy0::new(x, y).exec(self),
+            Instruction::LdI { nnn } => Opannn::new(nnn).exec(self),
+            Instruction::JpV0 { nnn } => Opbnnn::new(nnn).exec(self),
+            Instruction::Rnd { x, kk } => Opcxkk::new(x, kk).exec(self),
+            Instruction::Drw { x, y, n } => Opdxyn::new(x, y, n).exec(self),
+            Instruction::Skp { x } => Opex9e::new(x, keys).exec(self),
+            Instruction::Sknp { x } => Opexa1::new(x, keys).exec(self),
+            Instruction::LdLongI => Opf000::new().exec(self),
+            Instruction::Plane { n } => Opfn01::new(n).exec(self),
+            Instruction::Audio => Opf002::new().exec(self),
+            Instruction::LdVxDt { x } => Opfx07::new(x).exec(self),
+            Instruction::LdVxK { x } => Opfx0a::new(x, keys).exec(self),
+            Instruction::LdDtVx { x } => Opfx15::new(x).exec(self),
+            Instruction::LdStVx { x } => Opfx18::new(x).exec(self),
+            Instruction::AddI { x } => Opfx1e::new(x).exec(self),
+            Instruction::LdF { x } => Opfx29::new(x).exec(self),
+            Instruction::LdHf { x } => Opfx30::new(x).exec(self),
+            Instruction::LdB { x } => Opfx33::new(x).exec(self),
+            Instruction::Pitch { x } => Opfx3a::new(x).exec(self),
+            Instruction::LdIVx { x } => Opfx55::new(x).exec(self),
+            Instruction::LdVxI { x } => Opfx65::new(x).exec(self),
+            Instruction::LdRVx { x } => Opfx75::new(x).exec(self),
+            Instruction::LdVxR { x } => Opfx85::new(x).exec(self),
+            Instruction::Invalid { opcode } => OpInvalid::new(opcode).exec(self),
+        };
 
         debug!("run_instruction_result, result={:?}", action);
 
-        match action {
+        match action? {
             OperationResult::Next => {
                 self.pc += 2;
             }
             OperationResult::NextAndRedraw => {
                 self.pc += 2;
                 self.draw = true;
+                return Ok(StepOutcome::Redraw);
             }
             OperationResult::SkipNext => {
-                self.pc += 4;
+                self.pc += self.next_instruction_size(xo);
             }
             OperationResult::JumpTo(addr) => {
                 self.pc = addr;
             }
-            OperationResult::WaitInput => (),
+            OperationResult::WaitInput => return Ok(StepOutcome::WaitingInput),
+            OperationResult::WaitDisplay => return Ok(StepOutcome::WaitingDisplay),
+            OperationResult::Exit => {
+                self.halted = true;
+                return Ok(StepOutcome::Exited);
+            }
         }
+
+        Ok(StepOutcome::Executed)
     }
 }
 
@@ -249,12 +703,24 @@ impl fmt::Debug for Machine {
             .field("vram", &self.vram)
             .field("stack", &self.stack)
             .field("v", &self.v)
+            .field("rpl", &self.rpl)
             .field("i", &self.i)
             .field("pc", &self.pc)
             .field("sp", &self.sp)
             .field("dt", &self.dt)
             .field("st", &self.st)
+            .field("planes", &self.planes)
+            .field("audio_pattern", &self.audio_pattern)
+            .field("pitch", &self.pitch)
             .field("draw", &self.draw)
+            .field("vblank", &self.vblank)
+            .field("halted", &self.halted)
+            .field("platform", &self.platform)
+            .field("quirks", &self.quirks)
+            .field("tracer", &self.tracer)
+            .field("timing", &self.timing)
+            .field("frame_cycles", &self.frame_cycles)
+            .field("key_wait", &self.key_wait)
             .finish()
     }
 }
@@ -262,28 +728,42 @@ impl fmt::Debug for Machine {
 impl Default for Machine {
     /// Creates a [`Machine`] with the default values.
     fn default() -> Self {
-        let mut ram = [0; MEMORY_SIZE];
+        let mut ram = vec![0; MEMORY_SIZE];
         ram[FONT_INITIAL_ADDRESS..FONT_MEMORY_SIZE].copy_from_slice(&FONT);
+        ram[BIG_FONT_INITIAL_ADDRESS..BIG_FONT_INITIAL_ADDRESS + BIG_FONT_MEMORY_SIZE].copy_from_slice(&BIG_FONT);
 
         Self {
             ram,
-            vram: [false; VRAM_WIDTH * VRAM_HEIGHT],
+            vram: Vram::default(),
             stack: [0; STACK_SIZE],
             v: [0; GENERAL_REGISTER_NUMBER],
+            rpl: [0; RPL_FLAGS_NUMBER],
             i: 0,
             pc: INITIAL_PC_VALUE,
             sp: 0,
             dt: 0,
             st: 0,
+            planes: FIRST_PLANE,
+            audio_pattern: [0; AUDIO_PATTERN_SIZE],
+            pitch: INITIAL_PITCH_VALUE,
             draw: false,
-            rng: Box::new(rand::thread_rng()),
+            vblank: false,
+            halted: false,
+            platform: Platform::default(),
+            quirks: Quirks::default(),
+            rng: MachineRng::Custom(Box::new(rand::thread_rng())),
+            tracer: None,
+            timing: Timing::default(),
+            frame_cycles: 0,
+            key_wait: None,
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test_machine {
-    use crate::{Key, Keyboard};
+    use crate::{Key, Keyboard, Resolution};
 
     use super::*;
 
@@ -293,7 +773,7 @@ mod test_machine {
 
         let rom = [0x1u8; 100];
 
-        machine.load_rom(&rom);
+        machine.load_rom(&rom).expect("rom should be loaded");
 
         assert_eq!(
             machine.ram[ROM_INITIAL_ADDRESS..ROM_INITIAL_ADDRESS + rom.len()],
@@ -303,6 +783,49 @@ mod test_machine {
     }
 
     #[test]
+    fn test_load_rom_too_large() {
+        let mut machine = Machine::default();
+
+        let rom = [0x1u8; MEMORY_SIZE - ROM_INITIAL_ADDRESS + 1];
+
+        assert_eq!(
+            machine.load_rom(&rom),
+            Err(MachineError::RomTooLarge {
+                len: rom.len(),
+                max: MEMORY_SIZE - ROM_INITIAL_ADDRESS
+            }),
+            "rom larger than the ram should be rejected"
+        );
+        assert!(
+            machine.ram[ROM_INITIAL_ADDRESS..].iter().all(|&byte| byte == 0),
+            "machine ram should be untouched"
+        );
+
+        machine.set_platform(Platform::XoChip);
+        assert!(machine.load_rom(&rom).is_ok(), "XO-CHIP ram should fit the rom");
+    }
+
+    #[test]
+    fn test_key_wait_reset() {
+        let mut machine = Machine::default();
+        machine.set_quirks(Quirks::cosmac_vip());
+        machine.load_rom(&[0xF1, 0x0A]).expect("rom should be loaded");
+
+        let mut keys = [false; 16];
+        keys[0x5] = true;
+        machine.step(&keys).expect("step should succeed");
+        assert!(machine.key_wait.is_some(), "machine should wait for a key");
+
+        machine.set_quirks(Quirks::cosmac_vip());
+        assert_eq!(machine.key_wait, None, "setting the quirks should stop waiting");
+
+        machine.step(&keys).expect("step should succeed");
+        machine.load_rom(&[0xF1, 0x0A]).expect("rom should be loaded");
+        assert_eq!(machine.key_wait, None, "loading a rom should stop waiting");
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_update_timers() {
         let mut machine = Machine::default();
 
@@ -313,6 +836,7 @@ mod test_machine {
 
         assert_eq!(machine.st, 4, "machine sound timer should be decremented");
         assert_eq!(machine.dt, 2, "machine delay timer should be decremented");
+        assert!(machine.vblank, "machine vblank flag should be set");
 
         machine.st = 0;
         machine.dt = 0;
@@ -323,6 +847,131 @@ mod test_machine {
         assert_eq!(machine.dt, 0, "machine delay timer should no update when equal to zero");
     }
 
+    #[test]
+    fn test_cosmac_vip_timing_budget() {
+        let mut machine = Machine::default();
+        machine.set_timing(Timing::CosmacVip);
+        machine
+            .load_rom(&[0x70, 0x01, 0x12, 0x00])
+            .expect("rom should be loaded");
+
+        let mut executed = 0;
+        while machine.step(&[false; 16]) == Ok(StepOutcome::Executed) {
+            executed += 1;
+        }
+
+        // 7xkk costs 50 cycles and 1nnn 52, so 18 loops fill the 1836 cycles of a frame.
+        assert_eq!(executed, 36, "machine should run until the frame cycles are spent");
+        assert_eq!(
+            machine.v[0], 18,
+            "machine should stop after the last charged instruction"
+        );
+        assert_eq!(
+            machine.step(&[false; 16]),
+            Ok(StepOutcome::WaitingDisplay),
+            "machine should wait the next frame"
+        );
+
+        machine.update_timers();
+
+        assert_eq!(
+            machine.step(&[false; 16]),
+            Ok(StepOutcome::Executed),
+            "machine should run again on the next frame"
+        );
+    }
+
+    #[test]
+    fn test_run_frame() {
+        let mut machine = Machine::default();
+        machine
+            .load_rom(&[0x70, 0x01, 0xF0, 0x18, 0x12, 0x00])
+            .expect("rom should be loaded");
+        machine.dt = 2;
+
+        let result = machine.run_frame(&[false; 16], 9).expect("frame should succeed");
+
+        assert_eq!(
+            result,
+            FrameResult {
+                instructions: 9,
+                redraw: false,
+                beep: true,
+                exited: false,
+            },
+            "frame should run the instructions and report the beep"
+        );
+        assert_eq!(
+            machine.v[0], 3,
+            "machine should run the provided number of instructions"
+        );
+        assert_eq!(machine.dt, 1, "machine timers should be updated once");
+        assert_eq!(
+            machine.st, 2,
+            "machine sound timer should be updated after the instructions"
+        );
+    }
+
+    #[test]
+    fn test_run_frame_redraw_and_exit() {
+        let mut machine = Machine::default();
+        machine.set_platform(Platform::SuperChip);
+        machine
+            .load_rom(&[0x00, 0xFB, 0x00, 0xFD, 0x12, 0x00])
+            .expect("rom should be loaded");
+        machine.dt = 2;
+
+        let result = machine.run_frame(&[false; 16], 9).expect("frame should succeed");
+
+        assert_eq!(
+            result,
+            FrameResult {
+                instructions: 1,
+                redraw: true,
+                beep: false,
+                exited: true,
+            },
+            "frame should stop when the machine exits"
+        );
+        assert_eq!(machine.dt, 2, "machine timers should not be updated after exiting");
+    }
+
+    #[test]
+    fn test_run_frame_waiting_input() {
+        let mut machine = Machine::default();
+        machine.load_rom(&[0xF0, 0x0A]).expect("rom should be loaded");
+
+        let result = machine.run_frame(&[false; 16], 9).expect("frame should succeed");
+
+        assert_eq!(result.instructions, 0, "frame should end while waiting a key");
+        assert_eq!(machine.pc, 0x200, "machine should keep waiting");
+    }
+
+    #[test]
+    fn test_cosmac_vip_timing_display_wait() {
+        let mut machine = Machine::default();
+        machine.set_timing(Timing::CosmacVip);
+        machine
+            .load_rom(&[0x60, 0x00, 0xD0, 0x01])
+            .expect("rom should be loaded");
+        machine.update_timers();
+
+        assert_eq!(machine.step(&[false; 16]), Ok(StepOutcome::Executed), "6xkk should run");
+        assert_eq!(
+            machine.step(&[false; 16]),
+            Ok(StepOutcome::WaitingDisplay),
+            "Dxyn should wait the next display interrupt, even without the quirk"
+        );
+
+        machine.update_timers();
+
+        assert_eq!(
+            machine.step(&[false; 16]),
+            Ok(StepOutcome::Redraw),
+            "Dxyn should draw at the frame start"
+        );
+    }
+
     #[test]
     fn test_step_with_simple_operation() {
         let mut machine = Machine::default();
@@ -333,8 +982,9 @@ mod test_machine {
         machine.ram[INITIAL_PC_VALUE] = 0x00;
         machine.ram[INITIAL_PC_VALUE + 1] = 0xE0;
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
         assert_eq!(machine.should_draw(), false, "machine vram should no be drawn");
         assert_eq!(
             machine.pc,
@@ -353,8 +1003,9 @@ mod test_machine {
         machine.ram[INITIAL_PC_VALUE] = 0xD0;
         machine.ram[INITIAL_PC_VALUE + 1] = 0x05;
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::Redraw), "should return Redraw");
         assert_eq!(machine.should_draw(), true, "machine vram should no be drawn");
         assert_eq!(
             machine.pc,
@@ -373,8 +1024,9 @@ mod test_machine {
         machine.ram[INITIAL_PC_VALUE] = 0x13;
         machine.ram[INITIAL_PC_VALUE + 1] = 0x45;
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
         assert_eq!(machine.should_draw(), false, "machine vram should no be drawn");
         assert_eq!(machine.pc, 0x345, "machine program counter should have jumped to 0x345");
     }
@@ -392,8 +1044,9 @@ mod test_machine {
         machine.v[0x1] = 1;
         machine.v[0x2] = 2;
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
         assert_eq!(machine.should_draw(), false, "machine vram should no be drawn");
         assert_eq!(
             machine.pc,
@@ -412,8 +1065,9 @@ mod test_machine {
         machine.ram[INITIAL_PC_VALUE] = 0xF0;
         machine.ram[INITIAL_PC_VALUE + 1] = 0x0A;
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::WaitingInput), "should return WaitingInput");
         assert_eq!(machine.should_draw(), false, "machine vram should no be drawn");
         assert_eq!(
             machine.pc, INITIAL_PC_VALUE,
@@ -422,8 +1076,9 @@ mod test_machine {
 
         keyboard.press_key(Key::_0);
 
-        machine.step(keyboard.keys_as_ref());
+        let result = machine.step(keyboard.keys_as_ref());
 
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
         assert_eq!(machine.should_draw(), false, "machine vram should no be drawn");
         assert_eq!(
             machine.pc,
@@ -431,4 +1086,263 @@ mod test_machine {
             "machine program counter should incremented by 2"
         );
     }
+
+    #[test]
+    fn test_step_with_pc_out_of_bounds() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        machine.pc = MEMORY_SIZE - 1;
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: MEMORY_SIZE - 1,
+                addr: MEMORY_SIZE
+            }),
+            "should return MemoryOutOfBounds"
+        );
+        assert_eq!(
+            machine.pc,
+            MEMORY_SIZE - 1,
+            "machine program counter should be the same"
+        );
+    }
+
+    #[test]
+    fn test_step_with_invalid_operation() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        // 5xy1 is not a valid operation
+        machine.ram[INITIAL_PC_VALUE] = 0x51;
+        machine.ram[INITIAL_PC_VALUE + 1] = 0x21;
+        machine.draw = true;
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(
+            result,
+            Err(MachineError::InvalidOpcode {
+                pc: INITIAL_PC_VALUE,
+                opcode: 0x5121
+            }),
+            "should return InvalidOpcode"
+        );
+        assert_eq!(
+            machine.pc, INITIAL_PC_VALUE,
+            "machine program counter should be the same"
+        );
+        assert!(machine.draw, "machine draw flag should be the same");
+    }
+
+    #[test]
+    fn test_step_with_super_chip_operation() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        // 00FF HIGH operation
+        machine.ram[INITIAL_PC_VALUE] = 0x00;
+        machine.ram[INITIAL_PC_VALUE + 1] = 0xFF;
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(
+            result,
+            Err(MachineError::InvalidOpcode {
+                pc: INITIAL_PC_VALUE,
+                opcode: 0x00FF
+            }),
+            "should return InvalidOpcode on CHIP-8 platform"
+        );
+
+        machine.set_platform(Platform::SuperChip);
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(result, Ok(StepOutcome::Redraw), "should return Redraw");
+        assert_eq!(
+            machine.vram_as_ref().resolution(),
+            Resolution::High,
+            "machine vram should be in high resolution"
+        );
+        assert_eq!(
+            machine.quirks(),
+            Quirks::super_chip(),
+            "machine quirks should be the SUPER-CHIP ones"
+        );
+    }
+
+    #[test]
+    fn test_step_with_exit_operation() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        machine.set_platform(Platform::SuperChip);
+        // 00FD EXIT operation
+        machine.ram[INITIAL_PC_VALUE] = 0x00;
+        machine.ram[INITIAL_PC_VALUE + 1] = 0xFD;
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(result, Ok(StepOutcome::Exited), "should return Exited");
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(result, Ok(StepOutcome::Exited), "should keep returning Exited");
+        assert_eq!(
+            machine.pc, INITIAL_PC_VALUE,
+            "machine program counter should be the same"
+        );
+    }
+
+    #[test]
+    fn test_set_platform_resizes_ram() {
+        let mut machine = Machine::default();
+
+        machine.set_platform(Platform::XoChip);
+
+        assert_eq!(machine.ram.len(), XO_MEMORY_SIZE, "machine ram should have 64 KiB");
+        assert_eq!(
+            machine.ram[..FONT_MEMORY_SIZE],
+            FONT,
+            "machine ram should keep the font"
+        );
+
+        machine.set_platform(Platform::Chip8);
+
+        assert_eq!(machine.ram.len(), MEMORY_SIZE, "machine ram should have 4 KiB");
+    }
+
+    #[test]
+    fn test_step_with_xo_chip_long_load() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        machine.set_platform(Platform::XoChip);
+        // F000 nnnn LD I, long operation
+        machine.ram[INITIAL_PC_VALUE..INITIAL_PC_VALUE + 4].copy_from_slice(&[0xF0, 0x00, 0xFF, 0xF0]);
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
+        assert_eq!(machine.i, 0xFFF0, "machine i should be 0xFFF0");
+        assert_eq!(
+            machine.pc,
+            INITIAL_PC_VALUE + 4,
+            "machine program counter should incremented by 4"
+        );
+    }
+
+    #[test]
+    fn test_step_with_xo_chip_skip_over_long_load() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        machine.set_platform(Platform::XoChip);
+        // 3xkk SE Vx, byte operation followed by a F000 nnnn LD I, long operation
+        machine.ram[INITIAL_PC_VALUE..INITIAL_PC_VALUE + 6].copy_from_slice(&[0x30, 0x00, 0xF0, 0x00, 0x12, 0x34]);
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(result, Ok(StepOutcome::Executed), "should return Executed");
+        assert_eq!(
+            machine.pc,
+            INITIAL_PC_VALUE + 6,
+            "machine program counter should incremented by 6"
+        );
+    }
+
+    #[test]
+    fn test_step_with_xo_chip_operation_on_super_chip() {
+        let mut machine = Machine::default();
+        let keyboard = Keyboard::default();
+
+        machine.set_platform(Platform::SuperChip);
+        // F000 nnnn LD I, long operation
+        machine.ram[INITIAL_PC_VALUE..INITIAL_PC_VALUE + 4].copy_from_slice(&[0xF0, 0x00, 0xFF, 0xF0]);
+
+        let result = machine.step(keyboard.keys_as_ref());
+
+        assert_eq!(
+            result,
+            Err(MachineError::InvalidOpcode {
+                pc: INITIAL_PC_VALUE,
+                opcode: 0xF000
+            }),
+            "should return InvalidOpcode on SUPER-CHIP platform"
+        );
+    }
+
+    #[test]
+    fn test_audio_playback_rate() {
+        let mut machine = Machine::default();
+
+        assert_eq!(
+            machine.audio_playback_rate(),
+            4000.0,
+            "default playback rate should be 4000Hz"
+        );
+
+        machine.pitch = 112;
+
+        assert_eq!(
+            machine.audio_playback_rate(),
+            8000.0,
+            "playback rate should double after 48 steps"
+        );
+    }
+
+    #[test]
+    fn test_audio() {
+        let mut machine = Machine::default();
+        machine.audio_pattern[0] = 0xF0;
+
+        assert_eq!(machine.audio(), None, "CHIP-8 should not play patterns");
+
+        machine.set_platform(Platform::XoChip);
+        assert_eq!(
+            machine.audio().map(|(pattern, _)| pattern[0]),
+            Some(0xF0),
+            "XO-CHIP should play the loaded pattern"
+        );
+
+        machine.audio_pattern = [0; AUDIO_PATTERN_SIZE];
+        assert_eq!(machine.audio(), None, "silent pattern should play the tone");
+    }
+
+    #[test]
+    fn test_save_and_load_state() {
+        let mut machine = Machine {
+            pc: 0x300,
+            i: 0x400,
+            ..Default::default()
+        };
+
+        machine.v[0x1] = 0x11;
+
+        let state = machine.save_state();
+        let mut loaded = Machine::default();
+
+        assert!(loaded.load_state(&state).is_ok(), "state should be loaded");
+        assert_eq!(loaded.pc, 0x300, "machine program counter should be restored");
+        assert_eq!(loaded.i, 0x400, "machine i should be restored");
+        assert_eq!(loaded.v[0x1], 0x11, "machine v[1] should be restored");
+        assert_eq!(loaded.save_state(), state, "machine state should be the same");
+    }
+
+    #[test]
+    fn test_load_invalid_state() {
+        let mut machine = Machine {
+            pc: 0x300,
+            ..Default::default()
+        };
+
+        let result = machine.load_state(b"NOPE");
+
+        assert_eq!(result, Err(StateError::InvalidMagic), "should return InvalidMagic");
+        assert_eq!(machine.pc, 0x300, "machine program counter should be the same");
+    }
 }
@@ -0,0 +1,272 @@
+//! CHIP-8's input movies, the recording of the keys pressed on every frame of a seeded run.
+
+use crate::error::MovieError;
+use crate::keyboard::{Keys, NUM_KEYS};
+use crate::machine::{rom_hash, Rom, RomHash};
+use crate::platform::Platform;
+use crate::quirks::Quirks;
+use crate::state::{decode_platform, decode_quirks, encode_platform, encode_quirks};
+
+/// The movie magic header.
+pub const MOVIE_MAGIC: [u8; 4] = *b"R8MV";
+/// The movie format version written by this crate.
+///
+/// A movie is a little-endian binary blob with the following layout:
+///
+/// | Size            | Field                                                                     |
+/// |-----------------|---------------------------------------------------------------------------|
+/// | 4               | The [`MOVIE_MAGIC`] header, `R8MV`.                                       |
+/// | 2               | The format version.                                                       |
+/// | 8               | The [`SeededRng`](crate::SeededRng) seed.                                 |
+/// | 20              | The ROM SHA-1 digest.                                                     |
+/// | 1               | The platform: `0` CHIP-8, `1` SUPER-CHIP, `2` XO-CHIP.                    |
+/// | 1               | The quirks bit flags, in the [`Quirks`] fields declaration order.         |
+/// | 2               | The number of instructions executed on every frame.                       |
+/// | 4               | The number of frames.                                                     |
+/// | 2 * frames      | The keys pressed on every frame, a bitmask where bit `n` is the key `n`.  |
+pub const MOVIE_VERSION: u16 = 1;
+
+/// The movie header size, in bytes.
+const HEADER_SIZE: usize = 42;
+
+/// A recording of a seeded run, with everything needed to replay it exactly.
+///
+/// A replay creates a machine with [`Machine::with_seed`](crate::Machine::with_seed), using the movie platform and
+/// quirks, loads the ROM and, for every frame, executes the movie instructions per frame with the frame keys and then
+/// updates the timers once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Movie {
+    /// The machine RNG seed.
+    seed: u64,
+    /// The digest of the recorded ROM.
+    rom_hash: RomHash,
+    /// The machine platform.
+    platform: Platform,
+    /// The machine quirks.
+    quirks: Quirks,
+    /// The number of instructions executed on every frame.
+    instructions_per_frame: u16,
+    /// The keys pressed on every frame.
+    frames: Vec<Keys>,
+}
+
+impl Movie {
+    /// Creates an empty movie for the provided ROM and machine settings.
+    pub fn new(seed: u64, rom: &Rom, platform: Platform, quirks: Quirks, instructions_per_frame: u16) -> Self {
+        Self {
+            seed,
+            rom_hash: rom_hash(rom),
+            platform,
+            quirks,
+            instructions_per_frame,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns the machine RNG seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the digest of the recorded ROM.
+    pub fn rom_hash(&self) -> &RomHash {
+        &self.rom_hash
+    }
+
+    /// Returns the machine platform.
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Returns the machine quirks.
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    /// Returns the number of instructions executed on every frame.
+    pub fn instructions_per_frame(&self) -> u16 {
+        self.instructions_per_frame
+    }
+
+    /// Returns the keys pressed on every frame.
+    pub fn frames(&self) -> &[Keys] {
+        &self.frames
+    }
+
+    /// Records the keys pressed on a new frame.
+    pub fn push_frame(&mut self, keys: &Keys) {
+        self.frames.push(*keys);
+    }
+
+    /// Tells if the movie was recorded with the provided ROM.
+    pub fn matches_rom(&self, rom: &Rom) -> bool {
+        self.rom_hash == rom_hash(rom)
+    }
+
+    /// Encodes the movie into its binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_SIZE + self.frames.len() * 2);
+
+        out.extend_from_slice(&MOVIE_MAGIC);
+        out.extend_from_slice(&MOVIE_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&self.rom_hash);
+        out.push(encode_platform(self.platform));
+        out.push(encode_quirks(self.quirks));
+        out.extend_from_slice(&self.instructions_per_frame.to_le_bytes());
+        out.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        self.frames.iter().for_each(|keys| {
+            let mask = (0..NUM_KEYS).filter(|&n| keys[n]).fold(0u16, |acc, n| acc | 1 << n);
+            out.extend_from_slice(&mask.to_le_bytes());
+        });
+
+        out
+    }
+
+    /// Decodes a movie from its binary format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, MovieError> {
+        if data.len() < HEADER_SIZE {
+            return Err(MovieError::InvalidLength {
+                len: data.len(),
+                expected: HEADER_SIZE,
+            });
+        }
+
+        if data[0..4] != MOVIE_MAGIC {
+            return Err(MovieError::InvalidMagic);
+        }
+
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        if version != MOVIE_VERSION {
+            return Err(MovieError::UnsupportedVersion { version });
+        }
+
+        let mut seed = [0; 8];
+        seed.copy_from_slice(&data[6..14]);
+        let mut rom_hash = [0; 20];
+        rom_hash.copy_from_slice(&data[14..34]);
+        let platform = decode_platform(data[34]).ok_or(MovieError::InvalidValue { field: "platform" })?;
+        let quirks = decode_quirks(data[35]);
+        let instructions_per_frame = u16::from_le_bytes([data[36], data[37]]);
+        let frame_count = u32::from_le_bytes([data[38], data[39], data[40], data[41]]) as usize;
+
+        let expected = HEADER_SIZE + frame_count * 2;
+        if data.len() != expected {
+            return Err(MovieError::InvalidLength {
+                len: data.len(),
+                expected,
+            });
+        }
+
+        let frames = data[HEADER_SIZE..]
+            .chunks_exact(2)
+            .map(|chunk| {
+                let mask = u16::from_le_bytes([chunk[0], chunk[1]]);
+                let mut keys = [false; NUM_KEYS];
+                keys.iter_mut()
+                    .enumerate()
+                    .for_each(|(n, key)| *key = mask & (1 << n) != 0);
+                keys
+            })
+            .collect();
+
+        Ok(Self {
+            seed: u64::from_le_bytes(seed),
+            rom_hash,
+            platform,
+            quirks,
+            instructions_per_frame,
+            frames,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_movie {
+    use crate::Machine;
+
+    use super::*;
+
+    /// A ROM that draws random 1-row sprites while the key 0 isn't pressed.
+    const ROM: [u8; 10] = [0xC0, 0x3F, 0xC1, 0x1F, 0xE5, 0x9E, 0xD0, 0x11, 0x12, 0x00];
+
+    #[test]
+    fn test_to_bytes_from_bytes() {
+        let mut movie = Movie::new(42, &ROM, Platform::SuperChip, Quirks::super_chip(), 9);
+        let mut keys = [false; NUM_KEYS];
+
+        movie.push_frame(&keys);
+        keys[0x5] = true;
+        keys[0xF] = true;
+        movie.push_frame(&keys);
+
+        let data = movie.to_bytes();
+
+        assert_eq!(data.len(), HEADER_SIZE + 4, "movie should have two frames");
+        assert_eq!(Movie::from_bytes(&data), Ok(movie), "movie should be the same");
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_magic() {
+        let mut data = Movie::new(42, &ROM, Platform::Chip8, Quirks::default(), 9).to_bytes();
+        data[0] = b'X';
+
+        assert_eq!(
+            Movie::from_bytes(&data),
+            Err(MovieError::InvalidMagic),
+            "should return InvalidMagic"
+        );
+    }
+
+    #[test]
+    fn test_from_bytes_invalid_length() {
+        let mut movie = Movie::new(42, &ROM, Platform::Chip8, Quirks::default(), 9);
+        movie.push_frame(&[false; NUM_KEYS]);
+
+        let data = movie.to_bytes();
+
+        assert_eq!(
+            Movie::from_bytes(&data[..data.len() - 1]),
+            Err(MovieError::InvalidLength {
+                len: HEADER_SIZE + 1,
+                expected: HEADER_SIZE + 2
+            }),
+            "should return InvalidLength"
+        );
+    }
+
+    #[test]
+    fn test_matches_rom() {
+        let movie = Movie::new(42, &ROM, Platform::Chip8, Quirks::default(), 9);
+
+        assert!(movie.matches_rom(&ROM), "movie should match the recorded rom");
+        assert!(!movie.matches_rom(&ROM[1..]), "movie should not match another rom");
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        let mut movie = Movie::new(42, &ROM, Platform::Chip8, Quirks::default(), 9);
+        (0..30).for_each(|n| {
+            let mut keys = [false; NUM_KEYS];
+            keys[0x0] = n % 7 == 0;
+            movie.push_frame(&keys);
+        });
+
+        let replay = |movie: &Movie| {
+            let mut machine = Machine::with_seed(movie.seed());
+            machine.set_platform(movie.platform());
+            machine.set_quirks(movie.quirks());
+            machine.load_rom(&ROM).expect("rom should be loaded");
+
+            for keys in movie.frames() {
+                machine
+                    .run_frame(keys, movie.instructions_per_frame())
+                    .expect("frame should succeed");
+            }
+
+            machine.save_state()
+        };
+
+        assert_eq!(replay(&movie), replay(&movie), "replays should end in the same state");
+    }
+}
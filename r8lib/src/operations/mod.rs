@@ -1,12 +1,21 @@
 //! The implementation of all CHIP-8 operations.
 
+mod op00cn;
+mod op00dn;
 mod op00e0;
 mod op00ee;
+mod op00fb;
+mod op00fc;
+mod op00fd;
+mod op00fe;
+mod op00ff;
 mod op1nnn;
 mod op2nnn;
 mod op3xkk;
 mod op4xkk;
 mod op5xy0;
+mod op5xy2;
+mod op5xy3;
 mod op6xkk;
 mod op7xkk;
 mod op8xy0;
@@ -25,26 +34,42 @@ mod opcxkk;
 mod opdxyn;
 mod opex9e;
 mod opexa1;
+mod opf000;
+mod opf002;
+mod opfn01;
 mod opfx07;
 mod opfx0a;
 mod opfx15;
 mod opfx18;
 mod opfx1e;
 mod opfx29;
+mod opfx30;
 mod opfx33;
+mod opfx3a;
 mod opfx55;
 mod opfx65;
+mod opfx75;
+mod opfx85;
 mod opinvalid;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
+pub(crate) use crate::operations::op00cn::Op00cn;
+pub(crate) use crate::operations::op00dn::Op00dn;
 pub(crate) use crate::operations::op00e0::Op00e0;
 pub(crate) use crate::operations::op00ee::Op00ee;
+pub(crate) use crate::operations::op00fb::Op00fb;
+pub(crate) use crate::operations::op00fc::Op00fc;
+pub(crate) use crate::operations::op00fd::Op00fd;
+pub(crate) use crate::operations::op00fe::Op00fe;
+pub(crate) use crate::operations::op00ff::Op00ff;
 pub(crate) use crate::operations::op1nnn::Op1nnn;
 pub(crate) use crate::operations::op2nnn::Op2nnn;
 pub(crate) use crate::operations::op3xkk::Op3xkk;
 pub(crate) use crate::operations::op4xkk::Op4xkk;
 pub(crate) use crate::operations::op5xy0::Op5xy0;
+pub(crate) use crate::operations::op5xy2::Op5xy2;
+pub(crate) use crate::operations::op5xy3::Op5xy3;
 pub(crate) use crate::operations::op6xkk::Op6xkk;
 pub(crate) use crate::operations::op7xkk::Op7xkk;
 pub(crate) use crate::operations::op8xy0::Op8xy0;
@@ -63,15 +88,22 @@ pub(crate) use crate::operations::opcxkk::Opcxkk;
 pub(crate) use crate::operations::opdxyn::Opdxyn;
 pub(crate) use crate::operations::opex9e::Opex9e;
 pub(crate) use crate::operations::opexa1::Opexa1;
+pub(crate) use crate::operations::opf000::Opf000;
+pub(crate) use crate::operations::opf002::Opf002;
+pub(crate) use crate::operations::opfn01::Opfn01;
 pub(crate) use crate::operations::opfx07::Opfx07;
 pub(crate) use crate::operations::opfx0a::Opfx0a;
 pub(crate) use crate::operations::opfx15::Opfx15;
 pub(crate) use crate::operations::opfx18::Opfx18;
 pub(crate) use crate::operations::opfx1e::Opfx1e;
 pub(crate) use crate::operations::opfx29::Opfx29;
+pub(crate) use crate::operations::opfx30::Opfx30;
 pub(crate) use crate::operations::opfx33::Opfx33;
+pub(crate) use crate::operations::opfx3a::Opfx3a;
 pub(crate) use crate::operations::opfx55::Opfx55;
 pub(crate) use crate::operations::opfx65::Opfx65;
+pub(crate) use crate::operations::opfx75::Opfx75;
+pub(crate) use crate::operations::opfx85::Opfx85;
 pub(crate) use crate::operations::opinvalid::OpInvalid;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -81,10 +113,12 @@ pub(crate) enum OperationResult {
     SkipNext,
     JumpTo(usize),
     WaitInput,
+    WaitDisplay,
+    Exit,
 }
 
 /// A trait for CHIP-8 operations.
 pub(crate) trait Operation {
-    /// Executes the operation.
-    fn exec(&self, machine: &mut Machine) -> OperationResult;
+    /// Executes the operation, returning a [`MachineError`] if the machine is left in a faulty state.
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError>;
 }
@@ -0,0 +1,60 @@
+//! The implementation of the 00Cn (SCD nibble) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 00Cn (SCD nibble) operation. Scroll the display down by `n` pixels.
+pub(crate) struct Op00cn {
+    /// The `n` operation parameter.
+    n: u8,
+}
+
+impl Op00cn {
+    // Creates a new Op00cn.
+    pub(crate) fn new(n: u8) -> Self {
+        Self { n }
+    }
+}
+
+impl Operation for Op00cn {
+    /// Execute the operation 00Cn (SCD nibble).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00cn, n={}", self.n);
+
+        machine.vram.scroll_down(machine.planes, self.n as usize);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00cn {
+    use crate::VRAM_WIDTH;
+
+    use super::*;
+
+    #[test]
+    fn test_op00cn_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram[0x0] = 1;
+
+        let op = Op00cn::new(0x3);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(machine.vram[0x0], 0, "machine vram at 0x0 should be turned off");
+        assert_eq!(
+            machine.vram[3 * VRAM_WIDTH],
+            1,
+            "machine vram pixel should be moved 3 rows down"
+        );
+    }
+}
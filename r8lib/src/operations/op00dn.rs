@@ -0,0 +1,60 @@
+//! The implementation of the 00Dn (SCU nibble) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 00Dn (SCU nibble) operation. Scroll the selected planes up by `n` pixels.
+pub(crate) struct Op00dn {
+    /// The `n` operation parameter.
+    n: u8,
+}
+
+impl Op00dn {
+    // Creates a new Op00dn.
+    pub(crate) fn new(n: u8) -> Self {
+        Self { n }
+    }
+}
+
+impl Operation for Op00dn {
+    /// Execute the operation 00Dn (SCU nibble).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00dn, n={}", self.n);
+
+        machine.vram.scroll_up(machine.planes, self.n as usize);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00dn {
+    use crate::VRAM_WIDTH;
+
+    use super::*;
+
+    #[test]
+    fn test_op00dn_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram[3 * VRAM_WIDTH] = 1;
+
+        let op = Op00dn::new(0x3);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(
+            machine.vram[3 * VRAM_WIDTH],
+            0,
+            "machine vram at 3 rows down should be turned off"
+        );
+        assert_eq!(machine.vram[0x0], 1, "machine vram pixel should be moved 3 rows up");
+    }
+}
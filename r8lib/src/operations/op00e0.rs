@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::{Machine, VRAM_HEIGHT, VRAM_WIDTH};
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -18,12 +18,12 @@ impl Op00e0 {
 
 impl Operation for Op00e0 {
     /// Execute the operation 00E0 (CLS).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_00e0");
 
-        machine.vram = [false; VRAM_WIDTH * VRAM_HEIGHT];
+        machine.vram.clear(machine.planes);
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -36,15 +36,19 @@ mod test_op00e0 {
         let mut machine = Machine::default();
 
         // Make VRAM dirty.
-        machine.vram.iter_mut().enumerate().for_each(|(i, v)| *v = i % 2 == 0);
+        machine
+            .vram
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v = (i % 2) as u8);
 
         let op = Op00e0::new();
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         machine
             .vram
             .iter()
-            .for_each(|v| assert_eq!(*v, false, "all VRAM values should be false"));
+            .for_each(|v| assert_eq!(*v, 0, "all VRAM values should be zero"));
     }
 }
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -18,13 +18,17 @@ impl Op00ee {
 
 impl Operation for Op00ee {
     /// Execute the operation 00EE (RET).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_00ee");
 
+        if machine.sp == 0 {
+            return Err(MachineError::StackUnderflow { pc: machine.pc });
+        }
+
         machine.pc = machine.stack[machine.sp] as usize;
         machine.sp -= 1;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -33,6 +37,7 @@ mod test_op00ee {
     use super::*;
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_op00ee_exec() {
         let mut machine = Machine::default();
 
@@ -43,11 +48,29 @@ mod test_op00ee {
         let op = Op00ee::new();
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(machine.sp, 0x1, "should decrement stack point in 1");
         assert_eq!(
             machine.pc, 0xF,
             "program counter should point to the values stored in the stack"
         );
     }
+
+    #[test]
+    fn test_op00ee_exec_stack_underflow() {
+        let mut machine = Machine {
+            pc: 0x1,
+            ..Default::default()
+        };
+
+        let op = Op00ee::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::StackUnderflow { pc: 0x1 }),
+            "should return StackUnderflow"
+        );
+        assert_eq!(machine.sp, 0x0, "stack pointer should not change");
+    }
 }
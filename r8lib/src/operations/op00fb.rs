@@ -0,0 +1,57 @@
+//! The implementation of the 00FB (SCR) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Number of pixels moved by the horizontal scroll operations.
+pub(crate) const SCROLL_STEP: usize = 4;
+
+/// Implements the 00FB (SCR) operation. Scroll the display right by 4 pixels.
+pub(crate) struct Op00fb;
+
+impl Op00fb {
+    // Creates a new Op00fb.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Op00fb {
+    /// Execute the operation 00FB (SCR).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00fb");
+
+        machine.vram.scroll_right(machine.planes, SCROLL_STEP);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00fb {
+    use super::*;
+
+    #[test]
+    fn test_op00fb_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram[0x0] = 1;
+
+        let op = Op00fb::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(machine.vram[0x0], 0, "machine vram at 0x0 should be turned off");
+        assert_eq!(
+            machine.vram[0x4], 1,
+            "machine vram pixel should be moved 4 pixels right"
+        );
+    }
+}
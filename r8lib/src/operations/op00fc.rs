@@ -0,0 +1,51 @@
+//! The implementation of the 00FC (SCL) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{op00fb::SCROLL_STEP, Operation, OperationResult};
+
+/// Implements the 00FC (SCL) operation. Scroll the display left by 4 pixels.
+pub(crate) struct Op00fc;
+
+impl Op00fc {
+    // Creates a new Op00fc.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Op00fc {
+    /// Execute the operation 00FC (SCL).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00fc");
+
+        machine.vram.scroll_left(machine.planes, SCROLL_STEP);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00fc {
+    use super::*;
+
+    #[test]
+    fn test_op00fc_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram[0x4] = 1;
+
+        let op = Op00fc::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(machine.vram[0x4], 0, "machine vram at 0x4 should be turned off");
+        assert_eq!(machine.vram[0x0], 1, "machine vram pixel should be moved 4 pixels left");
+    }
+}
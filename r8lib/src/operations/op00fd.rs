@@ -0,0 +1,41 @@
+//! The implementation of the 00FD (EXIT) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 00FD (EXIT) operation. Exit the interpreter.
+pub(crate) struct Op00fd;
+
+impl Op00fd {
+    // Creates a new Op00fd.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Op00fd {
+    /// Execute the operation 00FD (EXIT).
+    fn exec(&self, _: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00fd");
+
+        Ok(OperationResult::Exit)
+    }
+}
+
+#[cfg(test)]
+mod test_op00fd {
+    use super::*;
+
+    #[test]
+    fn test_op00fd_exec() {
+        let mut machine = Machine::default();
+
+        let op = Op00fd::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Exit), "should return Exit");
+    }
+}
@@ -0,0 +1,56 @@
+//! The implementation of the 00FE (LOW) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError, Resolution};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 00FE (LOW) operation. Disable high resolution mode.
+pub(crate) struct Op00fe;
+
+impl Op00fe {
+    // Creates a new Op00fe.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Op00fe {
+    /// Execute the operation 00FE (LOW).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00fe");
+
+        machine.vram.set_resolution(Resolution::Low);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00fe {
+    use super::*;
+
+    #[test]
+    fn test_op00fe_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram.set_resolution(Resolution::High);
+        machine.vram[0x0] = 1;
+
+        let op = Op00fe::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(
+            machine.vram.resolution(),
+            Resolution::Low,
+            "machine vram resolution should be low"
+        );
+        assert!(machine.vram.iter().all(|v| *v == 0), "machine vram should be cleared");
+    }
+}
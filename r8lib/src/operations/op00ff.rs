@@ -0,0 +1,56 @@
+//! The implementation of the 00FF (HIGH) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError, Resolution};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 00FF (HIGH) operation. Enable high resolution mode.
+pub(crate) struct Op00ff;
+
+impl Op00ff {
+    // Creates a new Op00ff.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Op00ff {
+    /// Execute the operation 00FF (HIGH).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_00ff");
+
+        machine.vram.set_resolution(Resolution::High);
+
+        Ok(OperationResult::NextAndRedraw)
+    }
+}
+
+#[cfg(test)]
+mod test_op00ff {
+    use super::*;
+
+    #[test]
+    fn test_op00ff_exec() {
+        let mut machine = Machine::default();
+
+        machine.vram.set_resolution(Resolution::Low);
+        machine.vram[0x0] = 1;
+
+        let op = Op00ff::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(
+            machine.vram.resolution(),
+            Resolution::High,
+            "machine vram resolution should be high"
+        );
+        assert!(machine.vram.iter().all(|v| *v == 0), "machine vram should be cleared");
+    }
+}
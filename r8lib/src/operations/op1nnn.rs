@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,10 +21,10 @@ impl Op1nnn {
 
 impl Operation for Op1nnn {
     /// Execute the operation 1nnn (JP addr).
-    fn exec(&self, _: &mut Machine) -> OperationResult {
+    fn exec(&self, _: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_1nnn, nnn={:#06x?}", self.nnn);
 
-        OperationResult::JumpTo(self.nnn as usize)
+        Ok(OperationResult::JumpTo(self.nnn as usize))
     }
 }
 
@@ -42,7 +42,7 @@ mod test_op1nnn {
 
         assert_eq!(
             result,
-            OperationResult::JumpTo(nnn as usize),
+            Ok(OperationResult::JumpTo(nnn as usize)),
             "should return JumpTo(nnn)"
         );
     }
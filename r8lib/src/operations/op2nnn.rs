@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError, STACK_SIZE};
 
 use super::{Operation, OperationResult};
 
@@ -21,13 +21,17 @@ impl Op2nnn {
 
 impl Operation for Op2nnn {
     /// Execute the operation 2nnn (CALL addr).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_2nnn, nnn={:#06x?}", self.nnn);
 
+        if machine.sp + 1 >= STACK_SIZE {
+            return Err(MachineError::StackOverflow { pc: machine.pc });
+        }
+
         machine.sp += 1;
         machine.stack[machine.sp] = machine.pc as u16;
 
-        OperationResult::JumpTo(self.nnn as usize)
+        Ok(OperationResult::JumpTo(self.nnn as usize))
     }
 }
 
@@ -48,7 +52,7 @@ mod test_op2nnn {
 
         assert_eq!(
             result,
-            OperationResult::JumpTo(nnn as usize),
+            Ok(OperationResult::JumpTo(nnn as usize)),
             "should return JumpTo(nnn)"
         );
         assert_eq!(machine.sp, 0x6, "stack pointer should be incremented by one");
@@ -57,4 +61,23 @@ mod test_op2nnn {
             "new stack position should points to old program counter value"
         );
     }
+
+    #[test]
+    fn test_op2nnn_exec_stack_overflow() {
+        let mut machine = Machine {
+            sp: STACK_SIZE - 1,
+            pc: 0xA,
+            ..Default::default()
+        };
+
+        let op = Op2nnn::new(0xF);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::StackOverflow { pc: 0xA }),
+            "should return StackOverflow"
+        );
+        assert_eq!(machine.sp, STACK_SIZE - 1, "stack pointer should not change");
+    }
 }
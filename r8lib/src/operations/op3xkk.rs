@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,14 +23,14 @@ impl Op3xkk {
 
 impl Operation for Op3xkk {
     /// Execute the operation 3xkk (SE Vx, byte).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_3xkk, x={}, kk={}", self.x, self.kk);
 
         if machine.v[self.x as usize] == self.kk {
-            return OperationResult::SkipNext;
+            return Ok(OperationResult::SkipNext);
         }
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -49,7 +49,7 @@ mod test_op3xkk {
         let op = Op3xkk::new(x, kk);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
     }
 
     #[test]
@@ -63,6 +63,6 @@ mod test_op3xkk {
         let op = Op3xkk::new(x, kk);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::SkipNext, "should return SkipNext");
+        assert_eq!(result, Ok(OperationResult::SkipNext), "should return SkipNext");
     }
 }
@@ -0,0 +1,110 @@
+//! The implementation of the 5xy2 (LD [I], Vx-Vy) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 5xy2 (LD [I], Vx-Vy) operation. Store registers `Vx` through `Vy` in memory starting at location `I`.
+///
+/// The registers are stored in reverse order when `x` is greater than `y`. `I` is never changed.
+pub(crate) struct Op5xy2 {
+    /// The `x` operation parameter.
+    x: u8,
+    /// The `y` operation parameter.
+    y: u8,
+}
+
+impl Op5xy2 {
+    // Creates a new Op5xy2.
+    pub(crate) fn new(x: u8, y: u8) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Operation for Op5xy2 {
+    /// Execute the operation 5xy2 (LD [I], Vx-Vy).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_5xy2, x={}, y={}", self.x, self.y);
+
+        let (x, y) = (self.x as usize, self.y as usize);
+        let len = x.abs_diff(y) + 1;
+
+        machine.check_ram_range(machine.i, len)?;
+
+        (0..len).for_each(|n| {
+            let reg = if x <= y { x + n } else { x - n };
+            machine.ram[machine.i + n] = machine.v[reg];
+        });
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_op5xy2 {
+    use super::*;
+
+    #[test]
+    fn test_op5xy2_exec() {
+        let mut machine = Machine {
+            i: 0x300,
+            ..Default::default()
+        };
+
+        (0..=0xF).for_each(|n| machine.v[n] = n as u8 + 1);
+
+        let op = Op5xy2::new(0x2, 0x4);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.ram[0x300..0x304],
+            [3, 4, 5, 0],
+            "machine ram should contain v[2..=4]"
+        );
+        assert_eq!(machine.i, 0x300, "machine i should not change");
+    }
+
+    #[test]
+    fn test_op5xy2_exec_reversed() {
+        let mut machine = Machine {
+            i: 0x300,
+            ..Default::default()
+        };
+
+        (0..=0xF).for_each(|n| machine.v[n] = n as u8 + 1);
+
+        let op = Op5xy2::new(0x4, 0x2);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.ram[0x300..0x304],
+            [5, 4, 3, 0],
+            "machine ram should contain v[2..=4] in reverse order"
+        );
+    }
+
+    #[test]
+    fn test_op5xy2_exec_out_of_bounds() {
+        let mut machine = Machine {
+            pc: 0x200,
+            i: 0xFFE,
+            ..Default::default()
+        };
+
+        let op = Op5xy2::new(0x0, 0x3);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! The implementation of the 5xy3 (LD Vx-Vy, [I]) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the 5xy3 (LD Vx-Vy, [I]) operation. Read registers `Vx` through `Vy` from memory starting at location
+/// `I`.
+///
+/// The registers are read in reverse order when `x` is greater than `y`. `I` is never changed.
+pub(crate) struct Op5xy3 {
+    /// The `x` operation parameter.
+    x: u8,
+    /// The `y` operation parameter.
+    y: u8,
+}
+
+impl Op5xy3 {
+    // Creates a new Op5xy3.
+    pub(crate) fn new(x: u8, y: u8) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Operation for Op5xy3 {
+    /// Execute the operation 5xy3 (LD Vx-Vy, [I]).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_5xy3, x={}, y={}", self.x, self.y);
+
+        let (x, y) = (self.x as usize, self.y as usize);
+        let len = x.abs_diff(y) + 1;
+
+        machine.check_ram_range(machine.i, len)?;
+
+        (0..len).for_each(|n| {
+            let reg = if x <= y { x + n } else { x - n };
+            machine.v[reg] = machine.ram[machine.i + n];
+        });
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_op5xy3 {
+    use super::*;
+
+    #[test]
+    fn test_op5xy3_exec() {
+        let mut machine = Machine {
+            i: 0x300,
+            ..Default::default()
+        };
+
+        machine.ram[0x300..0x303].copy_from_slice(&[1, 2, 3]);
+
+        let op = Op5xy3::new(0x2, 0x4);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.v[0x1..0x6],
+            [0, 1, 2, 3, 0],
+            "machine v[2..=4] should be loaded"
+        );
+        assert_eq!(machine.i, 0x300, "machine i should not change");
+    }
+
+    #[test]
+    fn test_op5xy3_exec_reversed() {
+        let mut machine = Machine {
+            i: 0x300,
+            ..Default::default()
+        };
+
+        machine.ram[0x300..0x303].copy_from_slice(&[1, 2, 3]);
+
+        let op = Op5xy3::new(0x4, 0x2);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.v[0x1..0x6],
+            [0, 3, 2, 1, 0],
+            "machine v[2..=4] should be loaded in reverse order"
+        );
+    }
+}
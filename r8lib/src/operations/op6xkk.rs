@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,12 +23,12 @@ impl Op6xkk {
 
 impl Operation for Op6xkk {
     /// Execute the operation 6xkk (LD Vx, byte).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_6xkk, x={}, kk={}", self.x, self.kk);
 
         machine.v[self.x as usize] = self.kk;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -45,7 +45,7 @@ mod test_op6xkk {
         let op = Op6xkk::new(x, kk);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], kk,
             "machine v[{:#02x?}] value should be {}",
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,13 +23,13 @@ impl Op7xkk {
 
 impl Operation for Op7xkk {
     /// Execute the operation 7xkk (ADD Vx, byte).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_7xkk, x={}, kk={}", self.x, self.kk);
 
         let ix = self.x as usize;
         machine.v[ix] = machine.v[ix].wrapping_add(self.kk);
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -48,7 +48,7 @@ mod test_op7xkk {
         let op = Op7xkk::new(x, kk);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0xC,
             "should add kk value into machine v[{:#02x?}]",
@@ -67,7 +67,7 @@ mod test_op7xkk {
         let op = Op7xkk::new(x, kk);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0x0,
             "should add kk value into machine v[{:#02x?}] by wrapping",
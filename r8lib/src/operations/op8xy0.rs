@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,12 +23,12 @@ impl Op8xy0 {
 
 impl Operation for Op8xy0 {
     /// Execute the operation 8xy0 (LD Vx, Vy).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_8xy0, x={}, y={}", self.x, self.y);
 
         machine.v[self.x as usize] = machine.v[self.y as usize];
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -47,7 +47,7 @@ mod test_op8xy0 {
         let op = Op8xy0::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], machine.v[y as usize],
             "machine v[{:#02x?}] value should be same as v[{:#02x?}] value",
@@ -2,11 +2,13 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError, CARRY};
 
 use super::{Operation, OperationResult};
 
 /// Implements the 8xy3 (XOR Vx, Vy) operation. Set `Vx = Vx XOR Vy`.
+///
+/// `VF` is reset to zero when the `vf_reset` quirk is enabled.
 pub(crate) struct Op8xy3 {
     /// The `x` operation parameter.
     x: u8,
@@ -23,12 +25,16 @@ impl Op8xy3 {
 
 impl Operation for Op8xy3 {
     /// Execute the operation 8xy3 (XOR Vx, Vy).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_8xy3, x={}, y={}", self.x, self.y);
 
         machine.v[self.x as usize] ^= machine.v[self.y as usize];
 
-        OperationResult::Next
+        if machine.quirks.vf_reset {
+            machine.v[CARRY] = 0;
+        }
+
+        Ok(OperationResult::Next)
     }
 }
 
@@ -48,7 +54,7 @@ mod test_op8xy3 {
         let op = Op8xy3::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0xA,
             "machine v[{:#02x?}] value should be updated with a bitwise XOR with v[{:#02x?}] value",
@@ -60,4 +66,22 @@ mod test_op8xy3 {
             y
         );
     }
+
+    #[test]
+    fn test_op8xy3_exec_with_vf_reset_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+        let y = 0x2;
+
+        machine.quirks.vf_reset = true;
+        machine.v[x as usize] = 0x3;
+        machine.v[y as usize] = 0x5;
+        machine.v[CARRY] = 0x1;
+
+        let op = Op8xy3::new(x, y);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(machine.v[CARRY], 0x0, "machine v[0xF] value should be reset to zero");
+    }
 }
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::{Machine, CARRY};
+use crate::{Machine, MachineError, CARRY};
 
 use super::{Operation, OperationResult};
 
@@ -23,7 +23,7 @@ impl Op8xy5 {
 
 impl Operation for Op8xy5 {
     /// Execute the operation 8xy5 (SUB Vx, Vy).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_8xy5, x={}, y={}", self.x, self.y);
 
         let ix = self.x as usize;
@@ -32,7 +32,7 @@ impl Operation for Op8xy5 {
         machine.v[ix] = result.0;
         machine.v[CARRY] = !result.1 as u8;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -52,7 +52,7 @@ mod test_op8xy5 {
         let op = Op8xy5::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0x3,
             "machine v[{:#02x?}] value should be updated by subtracting v[{:#02x?}] value",
@@ -78,7 +78,7 @@ mod test_op8xy5 {
         let op = Op8xy5::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0xFF,
             "machine v[{:#02x?}] value should be updated by subtracting v[{:#02x?}] value and wrapping",
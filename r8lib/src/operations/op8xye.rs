@@ -2,35 +2,44 @@
 
 use log::debug;
 
-use crate::{Machine, CARRY};
+use crate::{Machine, MachineError, CARRY};
 
 use super::{Operation, OperationResult};
 
 /// Implements the 8xyE (SHL Vx {, Vy}) operation. Set `Vx = Vx SHL 1`.
 ///
-/// Ignoring Vy value following modern interpreters implementation.
+/// `Vy` is ignored, following modern interpreters implementation, unless the `shift_uses_vy` quirk is enabled. In that
+/// case `Vx = Vy SHL 1`.
 pub(crate) struct Op8xye {
     /// The `x` operation parameter.
     x: u8,
+    /// The `y` operation parameter.
+    y: u8,
 }
 
 impl Op8xye {
     // Creates a new Op8xye.
-    pub(crate) fn new(x: u8) -> Self {
-        Self { x }
+    pub(crate) fn new(x: u8, y: u8) -> Self {
+        Self { x, y }
     }
 }
 
 impl Operation for Op8xye {
     /// Execute the operation 8xyE (SHL Vx {, Vy}).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
-        debug!("op_8xye, x={}", self.x);
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_8xye, x={}, y={}", self.x, self.y);
 
         let ix = self.x as usize;
-        machine.v[CARRY] = machine.v[ix] & 0x80;
-        machine.v[ix] <<= 0x01;
+        let value = if machine.quirks.shift_uses_vy {
+            machine.v[self.y as usize]
+        } else {
+            machine.v[ix]
+        };
 
-        OperationResult::Next
+        machine.v[ix] = value << 0x01;
+        machine.v[CARRY] = value >> 0x07;
+
+        Ok(OperationResult::Next)
     }
 }
 
@@ -42,22 +51,50 @@ mod test_op8xye {
     fn test_op8xye_exec() {
         let mut machine = Machine::default();
         let x = 0x1;
+        let y = 0x2;
 
         machine.v[x as usize] = 0x79;
+        machine.v[y as usize] = 0x81;
 
-        let op = Op8xye::new(x);
+        let op = Op8xye::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], 0xF2,
-            "machine v[{:#02x?}] value should be updated by right-shifting by one",
+            "machine v[{:#02x?}] value should be updated by left-shifting by one",
             x
         );
         assert_eq!(
             machine.v[CARRY], 0x0,
-            "machine v[0xF] value should be the least-significant bit of v[{:#02x?}]",
+            "machine v[0xF] value should be the most-significant bit of v[{:#02x?}]",
             x
         );
     }
+
+    #[test]
+    fn test_op8xye_exec_with_shift_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+        let y = 0x2;
+
+        machine.quirks.shift_uses_vy = true;
+        machine.v[x as usize] = 0x79;
+        machine.v[y as usize] = 0x81;
+
+        let op = Op8xye::new(x, y);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.v[x as usize], 0x2,
+            "machine v[{:#02x?}] value should be v[{:#02x?}] left-shifted by one",
+            x, y
+        );
+        assert_eq!(
+            machine.v[CARRY], 0x1,
+            "machine v[0xF] value should be the most-significant bit of v[{:#02x?}]",
+            y
+        );
+    }
 }
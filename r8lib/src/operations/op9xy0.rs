@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,14 +23,14 @@ impl Op9xy0 {
 
 impl Operation for Op9xy0 {
     /// Execute the operation 9xy0 (SNE Vx, Vy).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_9xy0, x={}, y={}", self.x, self.y);
 
         if machine.v[self.x as usize] != machine.v[self.y as usize] {
-            return OperationResult::SkipNext;
+            return Ok(OperationResult::SkipNext);
         }
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -50,7 +50,7 @@ mod test_op9xy0 {
         let op = Op9xy0::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::SkipNext, "should return SkipNext");
+        assert_eq!(result, Ok(OperationResult::SkipNext), "should return SkipNext");
     }
 
     #[test]
@@ -65,6 +65,6 @@ mod test_op9xy0 {
         let op = Op9xy0::new(x, y);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
     }
 }
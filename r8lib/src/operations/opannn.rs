@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opannn {
 
 impl Operation for Opannn {
     /// Execute the operation Annn (LD I, addr).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_annn, nnn={}", self.nnn);
 
         machine.i = self.nnn as usize;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -42,7 +42,7 @@ mod test_opannn {
         let op = Opannn::new(nnn);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.i, nnn as usize,
             "machine I register value should be equal to nnn"
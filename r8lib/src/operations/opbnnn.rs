@@ -2,11 +2,14 @@
 
 use log::debug;
 
-use crate::{Machine, ZERO};
+use crate::{Machine, MachineError, ZERO};
 
 use super::{Operation, OperationResult};
 
 /// Implements the Bnnn (JP V0, addr) operation. Jump to location `nnn + V0`.
+///
+/// The operation behaves as Bxnn (JP Vx, addr), jumping to location `xnn + Vx`, when the `jump_uses_vx` quirk is
+/// enabled.
 pub(crate) struct Opbnnn {
     /// The `nnn` operation parameter.
     nnn: u16,
@@ -21,10 +24,16 @@ impl Opbnnn {
 
 impl Operation for Opbnnn {
     /// Execute the operation Bnnn (JP V0, addr) .
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_bnnn, nnn={}", self.nnn);
 
-        OperationResult::JumpTo((self.nnn + machine.v[ZERO] as u16) as usize)
+        let offset = if machine.quirks.jump_uses_vx {
+            machine.v[((self.nnn & 0x0F00) >> 8) as usize]
+        } else {
+            machine.v[ZERO]
+        };
+
+        Ok(OperationResult::JumpTo((self.nnn + offset as u16) as usize))
     }
 }
 
@@ -45,8 +54,27 @@ mod test_opbnnn {
         let expected = (nnn + machine.v[ZERO] as u16) as usize;
         assert_eq!(
             result,
-            OperationResult::JumpTo(expected),
+            Ok(OperationResult::JumpTo(expected)),
             "should return JumpTo(nnn + v[0x0])"
         );
     }
+
+    #[test]
+    fn test_opbnnn_exec_with_jump_quirk() {
+        let mut machine = Machine::default();
+        let nnn = 0x211;
+
+        machine.quirks.jump_uses_vx = true;
+        machine.v[ZERO] = 0x5;
+        machine.v[0x2] = 0x7;
+
+        let op = Opbnnn::new(nnn);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::JumpTo(0x218)),
+            "should return JumpTo(xnn + v[x])"
+        );
+    }
 }
@@ -3,7 +3,7 @@
 use log::debug;
 use rand::Rng;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -24,13 +24,13 @@ impl Opcxkk {
 
 impl Operation for Opcxkk {
     /// Execute the operation Cxkk (RND Vx, byte).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_cxkk, x={}, kk={}", self.x, self.kk);
 
         let value = machine.rng.gen::<u8>();
         machine.v[self.x as usize] = value & self.kk;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -38,6 +38,8 @@ impl Operation for Opcxkk {
 mod test_opcxkk {
     use rand::rngs::mock::StepRng;
 
+    use crate::rng::MachineRng;
+
     use super::*;
 
     #[test]
@@ -46,7 +48,7 @@ mod test_opcxkk {
         let x = 0x1;
         let kk = 0xA;
 
-        machine.rng = Box::new(StepRng::new(4, 2));
+        machine.rng = MachineRng::Custom(Box::new(StepRng::new(4, 2)));
 
         let op = Opcxkk::new(x, kk);
 
@@ -54,7 +56,7 @@ mod test_opcxkk {
         for expc in expected {
             let result = op.exec(&mut machine);
 
-            assert_eq!(result, OperationResult::Next, "should return Next");
+            assert_eq!(result, Ok(OperationResult::Next), "should return Next");
             assert_eq!(machine.v[x as usize], expc, "machine v[{}] should be {}", x, expc);
         }
     }
@@ -2,11 +2,18 @@
 
 use log::debug;
 
-use crate::{Machine, CARRY, SPRITE_WIDTH, VRAM_HEIGHT, VRAM_WIDTH};
+use crate::{Machine, MachineError, BIG_SPRITE_SIZE, CARRY, FIRST_PLANE, SECOND_PLANE, SPRITE_WIDTH};
 
 use super::{Operation, OperationResult};
 
 /// Implements the Dxyn (DRW Vx, Vy, nibble) operation. Display a n-byte sprite starting at memory location `I` at `(Vx, Vy)`, set `VF = collision`.
+///
+/// SUPER-CHIP platforms draw a 16x16 sprite, made of 32 bytes, when `n` is zero. Sprites are clipped at the screen
+/// edges, unless the `sprite_wrap` quirk is enabled. When the `display_wait` quirk is enabled, the operation waits for
+/// the next display refresh before drawing.
+///
+/// The sprite is drawn in every selected plane. When more than one plane is selected, the sprite data of each plane is
+/// read sequentially from memory, starting with the first plane.
 pub(crate) struct Opdxyn {
     /// The `x` operation parameter.
     x: u8,
@@ -22,65 +29,120 @@ impl Opdxyn {
         Self { x, y, n }
     }
 
-    /// Returns the computed VRAM index using the provided `x` and `y` screen values.
-    fn vram_index(&self, x: usize, y: usize) -> Option<usize> {
-        if x >= VRAM_WIDTH || y >= VRAM_HEIGHT {
+    /// Returns the sprite width and height. SUPER-CHIP platforms draw a 16x16 sprite when `n` is zero.
+    fn sprite_size(&self, machine: &Machine) -> (usize, usize) {
+        if self.n == 0 && machine.platform.supports_super_chip() {
+            return (BIG_SPRITE_SIZE, BIG_SPRITE_SIZE);
+        }
+
+        (SPRITE_WIDTH, self.n as usize)
+    }
+
+    /// Returns the computed VRAM index using the provided `x` and `y` screen values, wrapping them around the screen
+    /// edges when the `sprite_wrap` quirk is enabled.
+    fn vram_index(&self, machine: &Machine, x: usize, y: usize) -> Option<usize> {
+        let width = machine.vram.width();
+        let height = machine.vram.height();
+
+        if machine.quirks.sprite_wrap {
+            return Some((y % height) * width + x % width);
+        }
+
+        if x >= width || y >= height {
             return None;
         }
 
-        Some(y * VRAM_WIDTH + x)
+        Some(y * width + x)
     }
 
-    /// Draws a sprite in the machine VRAM.
-    fn draw_sprite(&self, machine: &mut Machine, x: usize, y: usize, n: u8) {
-        debug!("draw_sprite, x={}, y={}, n={}", x, y, n);
+    /// Returns the bitmasks of the planes selected for drawing.
+    fn selected_planes(&self, machine: &Machine) -> Vec<u8> {
+        [FIRST_PLANE, SECOND_PLANE]
+            .into_iter()
+            .filter(|plane| machine.planes & plane != 0)
+            .collect()
+    }
 
-        for iy in 0..(n as usize) {
-            let data = machine.ram[machine.i + iy];
-            for ix in 0..SPRITE_WIDTH {
+    /// Draws a sprite in the provided plane of the machine VRAM, reading its data from `addr`.
+    fn draw_sprite(
+        &self,
+        machine: &mut Machine,
+        plane: u8,
+        addr: usize,
+        (x, y): (usize, usize),
+        (width, height): (usize, usize),
+    ) {
+        debug!(
+            "draw_sprite, plane={}, addr={:#06x?}, x={}, y={}, width={}, height={}",
+            plane, addr, x, y, width, height
+        );
+
+        let row_size = width / 8;
+        for iy in 0..height {
+            for ix in 0..width {
+                let data = machine.ram[addr + iy * row_size + ix / 8];
                 // Gets the `ix`-significative bit of `data`. We store 8 pixels inside a `u8` value.
-                let value = data & (0x80 >> ix) > 0;
+                let value = data & (0x80 >> (ix % 8)) > 0;
 
-                self.draw_pixel(machine, value, x + ix, y + iy);
+                self.draw_pixel(machine, plane, value, x + ix, y + iy);
             }
         }
     }
 
-    /// Draws a pixel in the machine VRAM.
-    fn draw_pixel(&self, machine: &mut Machine, value: bool, x: usize, y: usize) {
-        debug!("draw_pixel, x={}, y={}, value={}", x, y, value);
+    /// Draws a pixel in the provided plane of the machine VRAM.
+    fn draw_pixel(&self, machine: &mut Machine, plane: u8, value: bool, x: usize, y: usize) {
+        debug!("draw_pixel, plane={}, x={}, y={}, value={}", plane, x, y, value);
+
+        if !value {
+            return;
+        }
 
-        if let Some(idx) = self.vram_index(x, y) {
+        if let Some(idx) = self.vram_index(machine, x, y) {
             debug!("draw_pixel_ram_index, idx={}", idx);
 
-            if value && machine.vram[idx] {
+            if machine.vram[idx] & plane != 0 {
                 machine.v[CARRY] = 1;
             }
 
-            machine.vram[idx] ^= value;
+            machine.vram[idx] ^= plane;
         }
     }
 }
 
 impl Operation for Opdxyn {
     /// Execute the operation Dxyn (DRW Vx, Vy, nibble).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_dxyn, x={}, y={}, n={}", self.x, self.y, self.n);
 
+        if (machine.quirks.display_wait || machine.timing.waits_display()) && !machine.vblank {
+            return Ok(OperationResult::WaitDisplay);
+        }
+
         // Loop back if `x` and `y` values if they are greater than the VRAM sizes.
-        let sx = (machine.v[self.x as usize] as usize) % VRAM_WIDTH;
-        let sy = (machine.v[self.y as usize] as usize) % VRAM_HEIGHT;
+        let sx = (machine.v[self.x as usize] as usize) % machine.vram.width();
+        let sy = (machine.v[self.y as usize] as usize) % machine.vram.height();
+        let (width, height) = self.sprite_size(machine);
+        let planes = self.selected_planes(machine);
+        let sprite_len = width / 8 * height;
+
+        machine.check_ram_range(machine.i, sprite_len * planes.len())?;
 
         machine.v[CARRY] = 0;
 
-        self.draw_sprite(machine, sx, sy, self.n);
+        for (idx, plane) in planes.into_iter().enumerate() {
+            let addr = machine.i + idx * sprite_len;
+            self.draw_sprite(machine, plane, addr, (sx, sy), (width, height));
+        }
+        machine.vblank = false;
 
-        OperationResult::NextAndRedraw
+        Ok(OperationResult::NextAndRedraw)
     }
 }
 
 #[cfg(test)]
 mod test_opdxyn {
+    use crate::{Platform, Resolution, ALL_PLANES, HIRES_VRAM_HEIGHT, HIRES_VRAM_WIDTH, VRAM_HEIGHT, VRAM_WIDTH};
+
     use super::*;
 
     struct TestCase {
@@ -135,14 +197,14 @@ mod test_opdxyn {
             machine.v[x as usize] = case.x;
             machine.v[y as usize] = case.y;
 
-            machine.vram.iter_mut().for_each(|v| *v = false);
+            machine.vram.iter_mut().for_each(|v| *v = 0);
 
             let op = Opdxyn::new(x, y, n);
             let result = op.exec(&mut machine);
 
             assert_eq!(
                 result,
-                OperationResult::NextAndRedraw,
+                Ok(OperationResult::NextAndRedraw),
                 "should return NextAndRedraw in test case {}",
                 i
             );
@@ -155,9 +217,12 @@ mod test_opdxyn {
             for pos in 0..VRAM_WIDTH * VRAM_HEIGHT {
                 let value = case.expected_positions.contains(&pos);
                 assert_eq!(
-                    machine.vram[pos], value,
+                    machine.vram[pos] != 0,
+                    value,
                     "machine vram at {:#02x?} index should be {} in test case {}",
-                    pos, !value, i
+                    pos,
+                    !value,
+                    i
                 );
             }
         }
@@ -180,13 +245,17 @@ mod test_opdxyn {
         machine.ram[0xFF3] = 0b10000001;
         machine.ram[0xFF4] = 0b11111111;
         // The vram has initial values.
-        machine.vram[0x48] = true;
-        machine.vram[0x141] = true;
+        machine.vram[0x48] = FIRST_PLANE;
+        machine.vram[0x141] = FIRST_PLANE;
 
         let op = Opdxyn::new(x, y, n);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::NextAndRedraw, "should return NextAndRedraw");
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
         assert_eq!(
             machine.v[CARRY], 1,
             "machine v[0xF] should be 0 because there was no collision"
@@ -199,10 +268,213 @@ mod test_opdxyn {
         for pos in 0..VRAM_WIDTH * VRAM_HEIGHT {
             let value = expected_positions.contains(&pos);
             assert_eq!(
-                machine.vram[pos], value,
+                machine.vram[pos] != 0,
+                value,
                 "machine vram at {:#02x?} index should be {}",
-                pos, !value
+                pos,
+                !value
             );
         }
     }
+
+    #[test]
+    fn test_opdxyn_exec_out_of_bounds() {
+        let mut machine = Machine {
+            pc: 0x200,
+            i: 0xFFE,
+            ..Default::default()
+        };
+
+        let op = Opdxyn::new(0x1, 0x2, 0x5);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+        assert!(machine.vram.iter().all(|v| *v == 0), "machine vram should not change");
+    }
+
+    #[test]
+    fn test_opdxyn_exec_with_wrap_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+        let y = 0x2;
+        let n = 0x5;
+
+        machine.quirks.sprite_wrap = true;
+        machine.v[x as usize] = 60;
+        machine.v[y as usize] = 28;
+        machine.i = 0xFF0;
+        // The sprite.
+        machine.ram[0xFF0] = 0b11111111;
+        machine.ram[0xFF1] = 0b10000001;
+        machine.ram[0xFF2] = 0b10011001;
+        machine.ram[0xFF3] = 0b10000001;
+        machine.ram[0xFF4] = 0b11111111;
+
+        let op = Opdxyn::new(x, y, n);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+
+        let expected_positions = [
+            0x73Cusize, 0x73D, 0x73E, 0x73F, 0x700, 0x701, 0x702, 0x703, 0x77C, 0x743, 0x7BC, 0x7BF, 0x780, 0x783,
+            0x7FC, 0x7C3, 0x3C, 0x3D, 0x3E, 0x3F, 0x0, 0x1, 0x2, 0x3,
+        ];
+        for pos in 0..VRAM_WIDTH * VRAM_HEIGHT {
+            let value = expected_positions.contains(&pos);
+            assert_eq!(
+                machine.vram[pos] != 0,
+                value,
+                "machine vram at {:#02x?} index should be {}",
+                pos,
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_opdxyn_exec_with_display_wait_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+        let y = 0x2;
+        let n = 0x5;
+
+        machine.quirks.display_wait = true;
+
+        let op = Opdxyn::new(x, y, n);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::WaitDisplay), "should return WaitDisplay");
+
+        machine.vblank = true;
+
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert!(!machine.vblank, "machine vblank flag should be cleared after drawing");
+    }
+
+    #[test]
+    fn test_opdxyn_exec_big_sprite() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+        let y = 0x2;
+
+        machine.platform = Platform::SuperChip;
+        machine.vram.set_resolution(Resolution::High);
+        machine.v[x as usize] = 120;
+        machine.v[y as usize] = 60;
+        machine.i = 0xFE0;
+        // The sprite, a 16x16 square outline.
+        machine.ram[0xFE0..0x1000].iter_mut().for_each(|v| *v = 0);
+        machine.ram[0xFE0] = 0xFF;
+        machine.ram[0xFE1] = 0xFF;
+        (1..15).for_each(|row| {
+            machine.ram[0xFE0 + row * 2] = 0x80;
+            machine.ram[0xFE0 + row * 2 + 1] = 0x01;
+        });
+        machine.ram[0xFFE] = 0xFF;
+        machine.ram[0xFFF] = 0xFF;
+
+        let op = Opdxyn::new(x, y, 0);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+
+        // Only the top-left 8x4 part of the sprite is visible.
+        for py in 0..HIRES_VRAM_HEIGHT {
+            for px in 0..HIRES_VRAM_WIDTH {
+                let value = (py == 60 && px >= 120) || (px == 120 && py >= 60);
+                assert_eq!(
+                    machine.vram[py * HIRES_VRAM_WIDTH + px] != 0,
+                    value,
+                    "machine vram at ({}, {}) should be {}",
+                    px,
+                    py,
+                    value
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_opdxyn_exec_zero_rows_on_chip8() {
+        let mut machine = Machine {
+            i: 0xFE0,
+            ..Default::default()
+        };
+
+        machine.ram[0xFE0] = 0xFF;
+
+        let op = Opdxyn::new(0x1, 0x2, 0);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert!(machine.vram.iter().all(|v| *v == 0), "machine vram should not change");
+    }
+
+    #[test]
+    fn test_opdxyn_exec_with_planes() {
+        let mut machine = Machine {
+            platform: Platform::XoChip,
+            planes: ALL_PLANES,
+            i: 0xFF0,
+            ..Default::default()
+        };
+
+        // The first plane sprite.
+        machine.ram[0xFF0] = 0b11000000;
+        // The second plane sprite.
+        machine.ram[0xFF1] = 0b10100000;
+        // The vram has an initial value in the second plane.
+        machine.vram[0x2] = SECOND_PLANE;
+
+        let op = Opdxyn::new(0x1, 0x2, 1);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::NextAndRedraw),
+            "should return NextAndRedraw"
+        );
+        assert_eq!(
+            machine.v[CARRY], 1,
+            "machine v[0xF] should be 1 because there was a collision in the second plane"
+        );
+        assert_eq!(
+            machine.vram[0x0], ALL_PLANES,
+            "machine vram at 0x0 should be in both planes"
+        );
+        assert_eq!(
+            machine.vram[0x1], FIRST_PLANE,
+            "machine vram at 0x1 should be in the first plane"
+        );
+        assert_eq!(machine.vram[0x2], 0, "machine vram at 0x2 should be turned off");
+        assert!(
+            machine.vram[0x3..].iter().all(|v| *v == 0),
+            "machine vram should not change after 0x2"
+        );
+    }
 }
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::{Keys, Machine};
+use crate::{Keys, Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -23,14 +23,15 @@ impl<'a> Opex9e<'a> {
 
 impl Operation for Opex9e<'_> {
     /// Execute the operation Ex9E (SKP Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_ex9e, x={}, keys={:?}", self.x, self.keys);
 
-        if self.keys[machine.v[self.x as usize] as usize] {
-            return OperationResult::SkipNext;
+        // Only the lowest nibble of `Vx` addresses a key, like the original hardware does.
+        if self.keys[(machine.v[self.x as usize] & 0xF) as usize] {
+            return Ok(OperationResult::SkipNext);
         }
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -52,7 +53,7 @@ mod test_opex9e {
         let op = Opex9e::new(x, &keys);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::SkipNext, "should return SkipNext");
+        assert_eq!(result, Ok(OperationResult::SkipNext), "should return SkipNext");
     }
 
     #[test]
@@ -67,6 +68,6 @@ mod test_opex9e {
         let op = Opex9e::new(x, &keys);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
     }
 }
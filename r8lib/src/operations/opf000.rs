@@ -0,0 +1,79 @@
+//! The implementation of the F000 nnnn (LD I, long nnnn) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the F000 nnnn (LD I, long nnnn) operation. Set `I = nnnn`, where `nnnn` is the 16-bit word following
+/// the operation in memory.
+pub(crate) struct Opf000;
+
+impl Opf000 {
+    // Creates a new Opf000.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Opf000 {
+    /// Execute the operation F000 nnnn (LD I, long nnnn).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_f000");
+
+        let addr = machine.pc + 2;
+
+        machine.check_ram_range(addr, 2)?;
+
+        machine.i = (machine.ram[addr] as usize) << 8 | machine.ram[addr + 1] as usize;
+
+        Ok(OperationResult::JumpTo(machine.pc + 4))
+    }
+}
+
+#[cfg(test)]
+mod test_opf000 {
+    use super::*;
+
+    #[test]
+    fn test_opf000_exec() {
+        let mut machine = Machine {
+            pc: 0x200,
+            ..Default::default()
+        };
+
+        machine.ram[0x202] = 0xAB;
+        machine.ram[0x203] = 0xCD;
+
+        let op = Opf000::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Ok(OperationResult::JumpTo(0x204)),
+            "should return JumpTo(0x204)"
+        );
+        assert_eq!(machine.i, 0xABCD, "machine i should be 0xABCD");
+    }
+
+    #[test]
+    fn test_opf000_exec_out_of_bounds() {
+        let mut machine = Machine::default();
+        let pc = machine.ram.len() - 2;
+
+        machine.pc = pc;
+
+        let op = Opf000::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc,
+                addr: machine.ram.len()
+            }),
+            "should return MemoryOutOfBounds"
+        );
+    }
+}
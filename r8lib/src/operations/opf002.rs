@@ -0,0 +1,81 @@
+//! The implementation of the F002 (AUDIO) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError, AUDIO_PATTERN_SIZE};
+
+use super::{Operation, OperationResult};
+
+/// Implements the F002 (AUDIO) operation. Load the 16-byte audio pattern buffer from memory starting at location `I`.
+pub(crate) struct Opf002;
+
+impl Opf002 {
+    // Creates a new Opf002.
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+impl Operation for Opf002 {
+    /// Execute the operation F002 (AUDIO).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_f002");
+
+        machine.check_ram_range(machine.i, AUDIO_PATTERN_SIZE)?;
+
+        machine
+            .audio_pattern
+            .copy_from_slice(&machine.ram[machine.i..machine.i + AUDIO_PATTERN_SIZE]);
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opf002 {
+    use super::*;
+
+    #[test]
+    fn test_opf002_exec() {
+        let mut machine = Machine {
+            i: 0x300,
+            ..Default::default()
+        };
+
+        (0..AUDIO_PATTERN_SIZE).for_each(|n| machine.ram[0x300 + n] = n as u8 + 1);
+
+        let op = Opf002::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        (0..AUDIO_PATTERN_SIZE).for_each(|n| {
+            assert_eq!(
+                machine.audio_pattern[n],
+                n as u8 + 1,
+                "machine audio pattern[{}] should be loaded",
+                n
+            )
+        });
+    }
+
+    #[test]
+    fn test_opf002_exec_out_of_bounds() {
+        let mut machine = Machine {
+            pc: 0x200,
+            i: 0xFF8,
+            ..Default::default()
+        };
+
+        let op = Opf002::new();
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! The implementation of the Fn01 (PLANE n) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError, ALL_PLANES};
+
+use super::{Operation, OperationResult};
+
+/// Implements the Fn01 (PLANE n) operation. Select the screen planes used by the drawing operations, where `n` is a
+/// bitmask of the planes.
+pub(crate) struct Opfn01 {
+    /// The `n` operation parameter.
+    n: u8,
+}
+
+impl Opfn01 {
+    // Creates a new Opfn01.
+    pub(crate) fn new(n: u8) -> Self {
+        Self { n }
+    }
+}
+
+impl Operation for Opfn01 {
+    /// Execute the operation Fn01 (PLANE n).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_fn01, n={}", self.n);
+
+        machine.planes = self.n & ALL_PLANES;
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opfn01 {
+    use super::*;
+
+    #[test]
+    fn test_opfn01_exec() {
+        let mut machine = Machine::default();
+
+        let op = Opfn01::new(0x3);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(machine.planes, ALL_PLANES, "machine planes should be both planes");
+
+        let op = Opfn01::new(0x0);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(machine.planes, 0, "machine planes should be none");
+    }
+}
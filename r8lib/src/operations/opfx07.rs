@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opfx07 {
 
 impl Operation for Opfx07 {
     /// Execute the operation Fx07 (LD Vx, DT).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx07, x={}, dt={}", self.x, machine.dt);
 
         machine.v[self.x as usize] = machine.dt;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -45,7 +45,7 @@ mod test_opfx07 {
         let op = Opfx07::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], machine.dt,
             "machine v[{:#02x?}] value should be same as delay timer",
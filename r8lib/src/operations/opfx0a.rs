@@ -2,11 +2,16 @@
 
 use log::debug;
 
-use crate::{Keys, Machine};
+use crate::keyboard::KeyWait;
+use crate::{Keys, Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
 /// Implements the Fx0A (LD Vx, K) operation. Wait for a key press, store the value of the key in `Vx`.
+///
+/// When multiple keys are pressed at once, the lowest one is stored. With the `key_wait_release` quirk, only keys pressed
+/// after the wait started count, and the key is stored once it's released, like the COSMAC VIP did. A held key then
+/// doesn't go through several waits.
 pub(crate) struct Opfx0a<'a> {
     /// The `x` operation parameter.
     x: u8,
@@ -23,24 +28,67 @@ impl<'a> Opfx0a<'a> {
 
 impl Operation for Opfx0a<'_> {
     /// Execute the operation Fx0A (LD Vx, K).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx0a, x={}, keys={:?}", self.x, self.keys);
 
-        if let Some(pos) = self.keys.iter().position(|&v| v) {
-            machine.v[self.x as usize] = pos as u8;
-            return OperationResult::Next;
+        if !machine.quirks.key_wait_release {
+            if let Some(pos) = self.keys.iter().position(|&v| v) {
+                machine.v[self.x as usize] = pos as u8;
+                return Ok(OperationResult::Next);
+            }
+
+            return Ok(OperationResult::WaitInput);
         }
 
-        OperationResult::WaitInput
+        let Some(wait) = &mut machine.key_wait else {
+            machine.key_wait = Some(KeyWait::new(self.keys));
+            return Ok(OperationResult::WaitInput);
+        };
+
+        wait.keyboard.update(self.keys);
+
+        let key = match wait.pressed {
+            None => {
+                wait.pressed = wait.keyboard.first_just_pressed();
+                None
+            }
+            Some(pressed) => (!self.keys[pressed as usize]).then_some(pressed),
+        };
+
+        match key {
+            Some(key) => {
+                machine.v[self.x as usize] = key;
+                machine.key_wait = None;
+                Ok(OperationResult::Next)
+            }
+            None => Ok(OperationResult::WaitInput),
+        }
     }
 }
 
 #[cfg(test)]
 mod test_opfx0a {
     use crate::keyboard::Key;
+    use crate::Quirks;
 
     use super::*;
 
+    /// Executes the operation with the provided keys.
+    fn exec(machine: &mut Machine, x: u8, keys: &Keys) -> Result<OperationResult, MachineError> {
+        Opfx0a::new(x, keys).exec(machine)
+    }
+
+    /// Creates a machine waiting for key releases.
+    fn release_machine() -> Machine {
+        let mut machine = Machine::default();
+        machine.set_quirks(Quirks {
+            key_wait_release: true,
+            ..Quirks::default()
+        });
+
+        machine
+    }
+
     #[test]
     fn test_opfx0a_exec() {
         let mut machine = Machine::default();
@@ -54,7 +102,7 @@ mod test_opfx0a {
         let op = Opfx0a::new(x, &keys);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.v[x as usize], key as u8,
             "machine v[{:#02x?}] value should be the same as the pressed key",
@@ -75,11 +123,141 @@ mod test_opfx0a {
         let op = Opfx0a::new(x, &keys);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::WaitInput, "should return WaitInput");
+        assert_eq!(result, Ok(OperationResult::WaitInput), "should return WaitInput");
         assert_eq!(
             machine.v[x as usize], 0x1,
             "machine v[{:#02x?}] value should not change",
             x
         );
     }
+
+    #[test]
+    fn test_opfx0a_exec_multiple_keys() {
+        let mut machine = Machine::default();
+        let mut keys = Keys::default();
+        let x = 0x1;
+
+        keys[Key::E as usize] = true;
+        keys[Key::_7 as usize] = true;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::Next),
+            "press should end the wait"
+        );
+        assert_eq!(
+            machine.v[x as usize], 0x7,
+            "machine v[{:#02x?}] should be the lowest pressed key",
+            x
+        );
+    }
+
+    #[test]
+    fn test_opfx0a_exec_on_release() {
+        let mut machine = release_machine();
+        let mut keys = Keys::default();
+        let x = 0x1;
+
+        exec(&mut machine, x, &keys).expect("exec should succeed");
+
+        keys[Key::B as usize] = true;
+        keys[Key::D as usize] = true;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::WaitInput),
+            "press should not end the wait"
+        );
+
+        keys[Key::D as usize] = false;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::WaitInput),
+            "releasing another key should not end the wait"
+        );
+
+        keys[Key::B as usize] = false;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::Next),
+            "release should end the wait"
+        );
+        assert_eq!(
+            machine.v[x as usize], 0xB,
+            "machine v[{:#02x?}] should be the released key",
+            x
+        );
+        assert_eq!(machine.key_wait, None, "machine should stop waiting");
+    }
+
+    #[test]
+    fn test_opfx0a_exec_on_release_ignores_held_key() {
+        let mut machine = release_machine();
+        let mut keys = Keys::default();
+        let x = 0x1;
+
+        keys[Key::_5 as usize] = true;
+
+        for _ in 0..3 {
+            let result = exec(&mut machine, x, &keys);
+
+            assert_eq!(
+                result,
+                Ok(OperationResult::WaitInput),
+                "held key should not end the wait"
+            );
+        }
+
+        keys[Key::_5 as usize] = false;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::WaitInput),
+            "release of a held key should not end the wait"
+        );
+
+        keys[Key::_5 as usize] = true;
+        exec(&mut machine, x, &keys).expect("exec should succeed");
+        keys[Key::_5 as usize] = false;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::Next),
+            "new press and release should end the wait"
+        );
+        assert_eq!(
+            machine.v[x as usize], 0x5,
+            "machine v[{:#02x?}] should be the released key",
+            x
+        );
+    }
+
+    #[test]
+    fn test_opfx0a_exec_on_release_multiple_keys() {
+        let mut machine = release_machine();
+        let mut keys = Keys::default();
+        let x = 0x1;
+
+        keys[Key::C as usize] = true;
+        exec(&mut machine, x, &keys).expect("exec should succeed");
+
+        keys[Key::E as usize] = true;
+        keys[Key::_7 as usize] = true;
+        exec(&mut machine, x, &keys).expect("exec should succeed");
+
+        keys[Key::_7 as usize] = false;
+
+        assert_eq!(
+            exec(&mut machine, x, &keys),
+            Ok(OperationResult::Next),
+            "release should end the wait"
+        );
+        assert_eq!(
+            machine.v[x as usize], 0x7,
+            "machine v[{:#02x?}] should be the lowest newly pressed key",
+            x
+        );
+    }
 }
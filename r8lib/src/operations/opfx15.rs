@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opfx15 {
 
 impl Operation for Opfx15 {
     /// Execute the operation Fx15 (LD DT, Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx15, x={}", self.x);
 
         machine.dt = machine.v[self.x as usize];
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -45,7 +45,7 @@ mod test_opfx15 {
         let op = Opfx15::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.dt, machine.v[x as usize],
             "machine delay timer value should be same as v[{:#02x?}]",
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opfx18 {
 
 impl Operation for Opfx18 {
     /// Execute the operation Fx18 (LD ST, Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx15, x={}", self.x);
 
         machine.st = machine.v[self.x as usize];
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -45,7 +45,7 @@ mod test_opfx18 {
         let op = Opfx18::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.st, machine.v[x as usize],
             "machine sound timer value should be same as v[{:#02x?}]",
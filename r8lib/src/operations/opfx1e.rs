@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opfx1e {
 
 impl Operation for Opfx1e {
     /// Execute the operation Fx1E (ADD I, Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx1e, x={}", self.x);
 
         machine.i += machine.v[self.x as usize] as usize;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -45,7 +45,7 @@ mod test_opfx1e {
         let op = Opfx1e::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.i, 0xFFF,
             "machine i register value should be summed with v[{:#02x?}] value",
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::{Machine, FONT_CHAR_SIZE};
+use crate::{Machine, MachineError, FONT_CHAR_SIZE};
 
 use super::{Operation, OperationResult};
 
@@ -21,12 +21,12 @@ impl Opfx29 {
 
 impl Operation for Opfx29 {
     /// Execute the operation Fx29 (LD F, Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx29, x={}", self.x);
 
         machine.i = machine.v[self.x as usize] as usize * FONT_CHAR_SIZE;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -44,7 +44,7 @@ mod test_opfx29 {
         let op = Opfx29::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.i,
             (machine.v[x as usize] as usize) * FONT_CHAR_SIZE,
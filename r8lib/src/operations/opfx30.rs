@@ -0,0 +1,56 @@
+//! The implementation of the Fx30 (LD HF, Vx) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError, BIG_FONT_CHAR_SIZE, BIG_FONT_INITIAL_ADDRESS};
+
+use super::{Operation, OperationResult};
+
+/// Implements the Fx30 (LD HF, Vx) operation. Set `I = location of the big sprite for digit Vx`.
+pub(crate) struct Opfx30 {
+    /// The `x` operation parameter.
+    x: u8,
+}
+
+impl Opfx30 {
+    // Creates a new Opfx30.
+    pub(crate) fn new(x: u8) -> Self {
+        Self { x }
+    }
+}
+
+impl Operation for Opfx30 {
+    /// Execute the operation Fx30 (LD HF, Vx).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_fx30, x={}", self.x);
+
+        let digit = (machine.v[self.x as usize] & 0xF) as usize;
+        machine.i = BIG_FONT_INITIAL_ADDRESS + digit * BIG_FONT_CHAR_SIZE;
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opfx30 {
+    use super::*;
+
+    #[test]
+    fn test_opfx30_exec() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+
+        machine.v[x as usize] = 0x9;
+
+        let op = Opfx30::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.i,
+            BIG_FONT_INITIAL_ADDRESS + 0x9 * BIG_FONT_CHAR_SIZE,
+            "machine I register should point to the big sprite of v[{:#02x?}]",
+            x
+        );
+    }
+}
@@ -2,7 +2,7 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
@@ -21,16 +21,18 @@ impl Opfx33 {
 
 impl Operation for Opfx33 {
     /// Execute the operation Fx33 (LD B, Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx33, x={}", self.x);
 
+        machine.check_ram_range(machine.i, 3)?;
+
         let vx = machine.v[self.x as usize];
 
         machine.ram[machine.i] = vx / 100 % 10;
         machine.ram[machine.i + 1] = vx / 10 % 10;
         machine.ram[machine.i + 2] = vx % 10;
 
-        OperationResult::Next
+        Ok(OperationResult::Next)
     }
 }
 
@@ -49,7 +51,7 @@ mod test_opfx33 {
         let op = Opfx33::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         assert_eq!(
             machine.ram[machine.i], 0x2,
             "machine ram value at i should be the hundred part of v[{:#02x?}]",
@@ -68,4 +70,27 @@ mod test_opfx33 {
             x
         );
     }
+
+    #[test]
+    fn test_opfx33_exec_out_of_bounds() {
+        let mut machine = Machine::default();
+        let x = 0x1;
+
+        machine.pc = 0x200;
+        machine.i = 0xFFE;
+        machine.v[x as usize] = 0xFF;
+
+        let op = Opfx33::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+        assert_eq!(machine.ram[0xFFE], 0x0, "machine ram value at i should not change");
+    }
 }
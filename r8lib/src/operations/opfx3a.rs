@@ -0,0 +1,50 @@
+//! The implementation of the Fx3A (PITCH Vx) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the Fx3A (PITCH Vx) operation. Set the audio pattern playback pitch register to `Vx`.
+pub(crate) struct Opfx3a {
+    /// The `x` operation parameter.
+    x: u8,
+}
+
+impl Opfx3a {
+    // Creates a new Opfx3a.
+    pub(crate) fn new(x: u8) -> Self {
+        Self { x }
+    }
+}
+
+impl Operation for Opfx3a {
+    /// Execute the operation Fx3A (PITCH Vx).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_fx3a, x={}", self.x);
+
+        machine.pitch = machine.v[self.x as usize];
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opfx3a {
+    use super::*;
+
+    #[test]
+    fn test_opfx3a_exec() {
+        let mut machine = Machine::default();
+        let x = 0x2;
+
+        machine.v[x as usize] = 0x70;
+
+        let op = Opfx3a::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(machine.pitch, 0x70, "machine pitch should be 0x70");
+    }
+}
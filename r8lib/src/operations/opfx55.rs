@@ -2,11 +2,13 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
 /// Implements the Fx55 (LD [I], Vx) operation. Store registers `V0` through `Vx` in memory starting at location `I`.
+///
+/// `I` is set to `I + x + 1` when the `load_store_increments_i` quirk is enabled.
 pub(crate) struct Opfx55 {
     /// The `x` operation parameter.
     x: u8,
@@ -21,12 +23,18 @@ impl Opfx55 {
 
 impl Operation for Opfx55 {
     /// Execute the operation Fx55 (LD [I], Vx).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx55, x={}", self.x);
 
+        machine.check_ram_range(machine.i, self.x as usize + 1)?;
+
         (0..=self.x).for_each(|n| machine.ram[machine.i + n as usize] = machine.v[n as usize]);
 
-        OperationResult::Next
+        if machine.quirks.load_store_increments_i {
+            machine.i += self.x as usize + 1;
+        }
+
+        Ok(OperationResult::Next)
     }
 }
 
@@ -47,7 +55,7 @@ mod test_opfx55 {
         let op = Opfx55::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         (0..=x as usize).for_each(|n| {
             assert_eq!(
                 machine.ram[machine.i + n],
@@ -60,4 +68,45 @@ mod test_opfx55 {
         ((x as usize + 1)..=CARRY)
             .for_each(|n| assert_eq!(machine.ram[machine.i + n], 0, "machine ram at i+{} should be 0", n));
     }
+
+    #[test]
+    fn test_opfx55_exec_out_of_bounds() {
+        let mut machine = Machine::default();
+        let x = 0x5u8;
+
+        machine.pc = 0x200;
+        machine.i = 0xFFD;
+        (0..=x as usize).for_each(|n| machine.v[n] = n as u8 + 1);
+
+        let op = Opfx55::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+        (0xFFD..0x1000).for_each(|n| assert_eq!(machine.ram[n], 0, "machine ram at {:#06x?} should be 0", n));
+    }
+
+    #[test]
+    fn test_opfx55_exec_with_load_store_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x5u8;
+
+        machine.quirks.load_store_increments_i = true;
+        machine.i = 0xFF0;
+
+        let op = Opfx55::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.i, 0xFF6,
+            "machine I register should point after the last stored value"
+        );
+    }
 }
@@ -2,11 +2,13 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
 /// Implements the Fx65 (LD Vx, [I]) operation. Read registers `V0` through `Vx` from memory starting at location `I`.
+///
+/// `I` is set to `I + x + 1` when the `load_store_increments_i` quirk is enabled.
 pub(crate) struct Opfx65 {
     /// The `x` operation parameter.
     x: u8,
@@ -21,12 +23,18 @@ impl Opfx65 {
 
 impl Operation for Opfx65 {
     /// Execute the operation Fx65 (LD Vx, [I]).
-    fn exec(&self, machine: &mut Machine) -> OperationResult {
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
         debug!("op_fx65, x={}", self.x);
 
+        machine.check_ram_range(machine.i, self.x as usize + 1)?;
+
         (0..=self.x).for_each(|n| machine.v[n as usize] = machine.ram[machine.i + n as usize]);
 
-        OperationResult::Next
+        if machine.quirks.load_store_increments_i {
+            machine.i += self.x as usize + 1;
+        }
+
+        Ok(OperationResult::Next)
     }
 }
 
@@ -42,17 +50,17 @@ mod test_opfx65 {
         let x = 0x5;
 
         machine.i = 0xFF0;
-        (0..=CARRY as usize).for_each(|n| machine.v[n] = 0);
+        (0..=CARRY).for_each(|n| machine.v[n] = 0);
         (0..=x as usize).for_each(|n| machine.ram[machine.i + n] = n as u8);
 
         let op = Opfx65::new(x);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
         (0..=x as usize).for_each(|n| {
             assert_eq!(
                 machine.v[n],
-                machine.ram[machine.i + n] as u8,
+                machine.ram[machine.i + n],
                 "machine v[{}] should be equal to ram value at i+{}",
                 n,
                 n
@@ -60,4 +68,45 @@ mod test_opfx65 {
         });
         ((x as usize + 1)..=CARRY).for_each(|n| assert_eq!(machine.v[n], 0, "machine v[{}] should be equal to 0", n));
     }
+
+    #[test]
+    fn test_opfx65_exec_out_of_bounds() {
+        let mut machine = Machine::default();
+        let x = 0x5;
+
+        machine.pc = 0x200;
+        machine.i = 0xFFD;
+        machine.ram[0xFFD] = 0xA;
+
+        let op = Opfx65::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(
+            result,
+            Err(MachineError::MemoryOutOfBounds {
+                pc: 0x200,
+                addr: 0x1000
+            }),
+            "should return MemoryOutOfBounds"
+        );
+        (0..=CARRY).for_each(|n| assert_eq!(machine.v[n], 0, "machine v[{}] should be equal to 0", n));
+    }
+
+    #[test]
+    fn test_opfx65_exec_with_load_store_quirk() {
+        let mut machine = Machine::default();
+        let x = 0x5;
+
+        machine.quirks.load_store_increments_i = true;
+        machine.i = 0xFF0;
+
+        let op = Opfx65::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        assert_eq!(
+            machine.i, 0xFF6,
+            "machine I register should point after the last loaded value"
+        );
+    }
 }
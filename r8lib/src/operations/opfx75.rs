@@ -0,0 +1,52 @@
+//! The implementation of the Fx75 (LD R, Vx) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the Fx75 (LD R, Vx) operation. Store registers `V0` through `Vx` in the RPL user flags.
+pub(crate) struct Opfx75 {
+    /// The `x` operation parameter.
+    x: u8,
+}
+
+impl Opfx75 {
+    // Creates a new Opfx75.
+    pub(crate) fn new(x: u8) -> Self {
+        Self { x }
+    }
+}
+
+impl Operation for Opfx75 {
+    /// Execute the operation Fx75 (LD R, Vx).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_fx75, x={}", self.x);
+
+        (0..=self.x as usize).for_each(|n| machine.rpl[n] = machine.v[n]);
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opfx75 {
+    use super::*;
+
+    #[test]
+    fn test_opfx75_exec() {
+        let mut machine = Machine::default();
+        let x = 0x3;
+
+        (0..=0xF).for_each(|n| machine.v[n] = n as u8 + 1);
+
+        let op = Opfx75::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        (0..=x as usize).for_each(|n| assert_eq!(machine.rpl[n], n as u8 + 1, "machine rpl[{}] should be v[{}]", n, n));
+        (x as usize + 1..machine.rpl.len())
+            .for_each(|n| assert_eq!(machine.rpl[n], 0, "machine rpl[{}] should not change", n));
+    }
+}
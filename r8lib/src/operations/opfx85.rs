@@ -0,0 +1,51 @@
+//! The implementation of the Fx85 (LD Vx, R) operation.
+
+use log::debug;
+
+use crate::{Machine, MachineError};
+
+use super::{Operation, OperationResult};
+
+/// Implements the Fx85 (LD Vx, R) operation. Read registers `V0` through `Vx` from the RPL user flags.
+pub(crate) struct Opfx85 {
+    /// The `x` operation parameter.
+    x: u8,
+}
+
+impl Opfx85 {
+    // Creates a new Opfx85.
+    pub(crate) fn new(x: u8) -> Self {
+        Self { x }
+    }
+}
+
+impl Operation for Opfx85 {
+    /// Execute the operation Fx85 (LD Vx, R).
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_fx85, x={}", self.x);
+
+        (0..=self.x as usize).for_each(|n| machine.v[n] = machine.rpl[n]);
+
+        Ok(OperationResult::Next)
+    }
+}
+
+#[cfg(test)]
+mod test_opfx85 {
+    use super::*;
+
+    #[test]
+    fn test_opfx85_exec() {
+        let mut machine = Machine::default();
+        let x = 0x3;
+
+        (0..machine.rpl.len()).for_each(|n| machine.rpl[n] = n as u8 + 1);
+
+        let op = Opfx85::new(x);
+        let result = op.exec(&mut machine);
+
+        assert_eq!(result, Ok(OperationResult::Next), "should return Next");
+        (0..=x as usize).for_each(|n| assert_eq!(machine.v[n], n as u8 + 1, "machine v[{}] should be rpl[{}]", n, n));
+        (x as usize + 1..=0xF).for_each(|n| assert_eq!(machine.v[n], 0, "machine v[{}] should not change", n));
+    }
+}
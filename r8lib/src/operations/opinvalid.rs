@@ -2,25 +2,32 @@
 
 use log::debug;
 
-use crate::Machine;
+use crate::{Machine, MachineError};
 
 use super::{Operation, OperationResult};
 
 /// Implements a dummy invalid operation.
-pub(crate) struct OpInvalid;
+pub(crate) struct OpInvalid {
+    /// The unknown opcode.
+    opcode: u16,
+}
 
 impl OpInvalid {
     // Creates a new OpInvalid.
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn new(opcode: u16) -> Self {
+        Self { opcode }
     }
 }
 
 impl Operation for OpInvalid {
-    /// Skips the current invalid operation.
-    fn exec(&self, _: &mut Machine) -> OperationResult {
-        debug!("op_invalid");
-        OperationResult::Next
+    /// Reports the current invalid operation.
+    fn exec(&self, machine: &mut Machine) -> Result<OperationResult, MachineError> {
+        debug!("op_invalid, opcode={:#06x?}", self.opcode);
+
+        Err(MachineError::InvalidOpcode {
+            pc: machine.pc,
+            opcode: self.opcode,
+        })
     }
 }
 
@@ -30,11 +37,21 @@ mod test_opinvalid {
 
     #[test]
     fn test_opinvalid_exec() {
-        let mut machine = Machine::default();
+        let mut machine = Machine {
+            pc: 0x202,
+            ..Default::default()
+        };
 
-        let op = OpInvalid::new();
+        let op = OpInvalid::new(0x5121);
         let result = op.exec(&mut machine);
 
-        assert_eq!(result, OperationResult::Next, "should return Next");
+        assert_eq!(
+            result,
+            Err(MachineError::InvalidOpcode {
+                pc: 0x202,
+                opcode: 0x5121
+            }),
+            "should return InvalidOpcode"
+        );
     }
 }
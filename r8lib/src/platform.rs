@@ -0,0 +1,92 @@
+//! CHIP-8's platforms, the instruction set variants a ROM can be written for.
+
+use crate::instruction::Instruction;
+use crate::machine::{MEMORY_SIZE, XO_MEMORY_SIZE};
+use crate::quirks::Quirks;
+
+/// The CHIP-8 variants supported by the machine.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    /// The original CHIP-8 instruction set.
+    #[default]
+    Chip8,
+    /// The SUPER-CHIP 1.1 instruction set, adding a high resolution mode, scrolling and a big font.
+    SuperChip,
+    /// The XO-CHIP instruction set, extending SUPER-CHIP with 64 KiB of memory, two screen planes and pattern audio.
+    XoChip,
+}
+
+impl Platform {
+    /// Returns the quirks used by default on the platform.
+    pub fn quirks(&self) -> Quirks {
+        match self {
+            Platform::Chip8 => Quirks::default(),
+            Platform::SuperChip => Quirks::super_chip(),
+            Platform::XoChip => Quirks::xo_chip(),
+        }
+    }
+
+    /// Returns the RAM size, in bytes, available on the platform.
+    pub fn memory_size(&self) -> usize {
+        match self {
+            Platform::Chip8 | Platform::SuperChip => MEMORY_SIZE,
+            Platform::XoChip => XO_MEMORY_SIZE,
+        }
+    }
+
+    /// Tells if the platform supports the SUPER-CHIP instructions.
+    pub fn supports_super_chip(&self) -> bool {
+        matches!(self, Platform::SuperChip | Platform::XoChip)
+    }
+
+    /// Tells if the platform supports the XO-CHIP instructions.
+    pub fn supports_xo_chip(&self) -> bool {
+        matches!(self, Platform::XoChip)
+    }
+
+    /// Tells if the platform supports the provided instruction.
+    pub fn supports(&self, instruction: &Instruction) -> bool {
+        match instruction {
+            Instruction::Scd { .. }
+            | Instruction::Scr
+            | Instruction::Scl
+            | Instruction::Exit
+            | Instruction::Low
+            | Instruction::High
+            | Instruction::LdHf { .. }
+            | Instruction::LdRVx { .. }
+            | Instruction::LdVxR { .. } => self.supports_super_chip(),
+            Instruction::Scu { .. }
+            | Instruction::SaveRange { .. }
+            | Instruction::LoadRange { .. }
+            | Instruction::LdLongI
+            | Instruction::Plane { .. }
+            | Instruction::Audio
+            | Instruction::Pitch { .. } => self.supports_xo_chip(),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_platform {
+    use super::*;
+
+    #[test]
+    fn test_supports() {
+        let cases = [
+            (Instruction::Cls, [true, true, true]),
+            (Instruction::High, [false, true, true]),
+            (Instruction::LdHf { x: 1 }, [false, true, true]),
+            (Instruction::Scu { n: 1 }, [false, false, true]),
+            (Instruction::LdLongI, [false, false, true]),
+            (Instruction::Invalid { opcode: 0x5121 }, [true, true, true]),
+        ];
+
+        for (instruction, expected) in cases {
+            let supported = [Platform::Chip8, Platform::SuperChip, Platform::XoChip].map(|p| p.supports(&instruction));
+
+            assert_eq!(supported, expected, "{:?} support should match", instruction);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! CHIP-8's quirks, the behaviours that differ between interpreters.
+
+/// A set of toggles for the CHIP-8 instructions whose behaviour changed between interpreters.
+///
+/// The default value keeps the behaviour of the original `r8` implementation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// `8xy6` and `8xyE` shift `Vy` and store the result in `Vx`, instead of shifting `Vx` in place.
+    pub shift_uses_vy: bool,
+    /// `Fx55` and `Fx65` leave `I` pointing right after the last accessed memory location.
+    pub load_store_increments_i: bool,
+    /// `Bnnn` behaves as `Bxnn`, jumping to `xnn + Vx` instead of `nnn + V0`.
+    pub jump_uses_vx: bool,
+    /// `8xy1`, `8xy2` and `8xy3` reset `VF` to zero.
+    pub vf_reset: bool,
+    /// `Dxyn` wraps sprites around the screen edges instead of clipping them.
+    pub sprite_wrap: bool,
+    /// `Dxyn` waits for the next display refresh, signaled by a timers update, before drawing.
+    pub display_wait: bool,
+    /// `Fx0A` stores a key pressed during the wait once it's released, instead of any held key right away.
+    pub key_wait_release: bool,
+}
+
+impl Quirks {
+    /// Returns the quirks of the original COSMAC VIP interpreter, the only one waiting for a key release in `Fx0A`.
+    pub fn cosmac_vip() -> Self {
+        Self {
+            shift_uses_vy: true,
+            load_store_increments_i: true,
+            jump_uses_vx: false,
+            vf_reset: true,
+            sprite_wrap: false,
+            display_wait: true,
+            key_wait_release: true,
+        }
+    }
+
+    /// Returns the quirks of the CHIP-48 interpreter for the HP-48 calculators.
+    pub fn chip48() -> Self {
+        Self {
+            shift_uses_vy: false,
+            load_store_increments_i: true,
+            jump_uses_vx: true,
+            vf_reset: false,
+            sprite_wrap: false,
+            display_wait: false,
+            key_wait_release: false,
+        }
+    }
+
+    /// Returns the quirks of the SUPER-CHIP 1.1 interpreter.
+    pub fn super_chip() -> Self {
+        Self {
+            shift_uses_vy: false,
+            load_store_increments_i: false,
+            jump_uses_vx: true,
+            vf_reset: false,
+            sprite_wrap: false,
+            display_wait: false,
+            key_wait_release: false,
+        }
+    }
+
+    /// Returns the quirks of the XO-CHIP extension, as implemented by Octo.
+    pub fn xo_chip() -> Self {
+        Self {
+            shift_uses_vy: true,
+            load_store_increments_i: true,
+            jump_uses_vx: false,
+            vf_reset: false,
+            sprite_wrap: true,
+            display_wait: false,
+            key_wait_release: false,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! CHIP-8's random number generators.
+
+use rand::RngCore;
+
+/// A small seedable random number generator, based on SplitMix64.
+///
+/// Its whole state is a single [`u64`], so machines using it can be saved, restored and replayed deterministically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeededRng {
+    /// The generator state.
+    state: u64,
+}
+
+impl SeededRng {
+    /// Creates a new generator from the provided seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the current generator state. A generator created with it as the seed continues the same sequence.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.chunks_mut(8).for_each(|chunk| {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        });
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+
+        Ok(())
+    }
+}
+
+/// The random number generator used by the machine.
+pub(crate) enum MachineRng {
+    /// A seedable generator, whose state is kept in save-states.
+    Seeded(SeededRng),
+    /// A generator provided by the user, whose state can't be saved.
+    Custom(Box<dyn RngCore>),
+}
+
+impl RngCore for MachineRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            MachineRng::Seeded(rng) => rng.next_u32(),
+            MachineRng::Custom(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            MachineRng::Seeded(rng) => rng.next_u64(),
+            MachineRng::Custom(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            MachineRng::Seeded(rng) => rng.fill_bytes(dest),
+            MachineRng::Custom(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            MachineRng::Seeded(rng) => rng.try_fill_bytes(dest),
+            MachineRng::Custom(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_rng {
+    use super::*;
+
+    #[test]
+    fn test_seeded_rng_is_deterministic() {
+        let mut first = SeededRng::new(42);
+        let mut second = SeededRng::new(42);
+        let mut other = SeededRng::new(43);
+
+        let values: Vec<u64> = (0..8).map(|_| first.next_u64()).collect();
+
+        (0..8).for_each(|n| assert_eq!(second.next_u64(), values[n], "value {} should be the same", n));
+        assert_ne!(
+            other.next_u64(),
+            values[0],
+            "a different seed should give a different value"
+        );
+    }
+
+    #[test]
+    fn test_seeded_rng_state() {
+        let mut rng = SeededRng::new(42);
+        rng.next_u64();
+
+        let mut restored = SeededRng::new(rng.state());
+
+        assert_eq!(
+            restored.next_u64(),
+            rng.next_u64(),
+            "restored generator should continue the sequence"
+        );
+    }
+
+    #[test]
+    fn test_seeded_rng_fill_bytes() {
+        let mut rng = SeededRng::new(42);
+        let mut expected = SeededRng::new(42);
+        let mut bytes = [0u8; 10];
+
+        rng.fill_bytes(&mut bytes);
+
+        assert_eq!(
+            bytes[..8],
+            expected.next_u64().to_le_bytes(),
+            "first bytes should come from the first value"
+        );
+        assert_eq!(
+            bytes[8..],
+            expected.next_u64().to_le_bytes()[..2],
+            "last bytes should come from the second value"
+        );
+    }
+}
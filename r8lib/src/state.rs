@@ -0,0 +1,507 @@
+//! CHIP-8's machine save-state format.
+
+use crate::error::StateError;
+use crate::keyboard::{KeyWait, Keyboard, Keys, NUM_KEYS};
+use crate::machine::{Machine, AUDIO_PATTERN_SIZE, GENERAL_REGISTER_NUMBER, RPL_FLAGS_NUMBER, STACK_SIZE};
+use crate::platform::Platform;
+use crate::quirks::Quirks;
+use crate::rng::{MachineRng, SeededRng};
+use crate::vram::Resolution;
+
+/// The save-state magic header.
+pub const STATE_MAGIC: [u8; 4] = *b"R8ST";
+/// The save-state format version written by this crate.
+///
+/// A save-state is a little-endian binary blob with the following layout:
+///
+/// | Size           | Field                                                                       |
+/// |----------------|-----------------------------------------------------------------------------|
+/// | 4              | The [`STATE_MAGIC`] header, `R8ST`.                                         |
+/// | 2              | The format version.                                                         |
+/// | 1              | The platform: `0` CHIP-8, `1` SUPER-CHIP, `2` XO-CHIP.                      |
+/// | 1              | The quirks bit flags, in the [`Quirks`] fields declaration order.           |
+/// | 4              | The `PC` register.                                                          |
+/// | 4              | The `I` register.                                                           |
+/// | 1              | The `SP` register.                                                          |
+/// | 1              | The delay timer.                                                            |
+/// | 1              | The sound timer.                                                            |
+/// | 1              | The machine bit flags: `0x1` draw, `0x2` vblank, `0x4` halted.              |
+/// | 1              | The selected planes bitmask.                                                |
+/// | 1              | The XO-CHIP pitch register.                                                 |
+/// | 16             | The `V0` through `VF` registers.                                            |
+/// | 16             | The RPL user flags.                                                         |
+/// | 32             | The call stack, 16 `u16` values.                                            |
+/// | 16             | The XO-CHIP audio pattern.                                                  |
+/// | 1              | The screen resolution: `0` low, `1` high.                                   |
+/// | 8192           | The screen pixels, a plane bitmask per pixel, using the high resolution.    |
+/// | 4              | The RAM size, which must match the platform one.                            |
+/// | RAM size       | The RAM contents.                                                           |
+/// | 1              | The `Fx0A` key wait: `0` none, `1` waiting a press, `2` waiting a release.  |
+/// | 4 or 0         | The key wait keys and previous keys, a `u16` bitmask each, while waiting.   |
+/// | 1 or 0         | The key whose release is waited, present when the key wait is `2`.         |
+/// | 1              | The RNG kind: `0` when the RNG isn't seedable and its state isn't saved.    |
+/// | 8 or 0         | The [`SeededRng`](crate::SeededRng) state, present when the RNG kind is `1`. |
+pub const STATE_VERSION: u16 = 2;
+
+/// Machine flag set when the screen should be redrawn.
+const DRAW_FLAG: u8 = 0x1;
+/// Machine flag set when the display was refreshed since the last sprite draw.
+const VBLANK_FLAG: u8 = 0x2;
+/// Machine flag set when the machine exited.
+const HALTED_FLAG: u8 = 0x4;
+/// Key wait kind used when no `Fx0A` instruction is waiting.
+const NO_KEY_WAIT: u8 = 0;
+/// Key wait kind used when an `Fx0A` instruction waits for a key press.
+const KEY_WAIT_PRESS: u8 = 1;
+/// Key wait kind used when an `Fx0A` instruction waits for the release of the pressed key.
+const KEY_WAIT_RELEASE: u8 = 2;
+/// RNG kind used when the RNG state isn't saved.
+const NO_RNG: u8 = 0;
+/// RNG kind used when the machine uses a [`SeededRng`].
+const SEEDED_RNG: u8 = 1;
+
+/// Encodes the machine state into a save-state.
+pub(crate) fn encode(machine: &Machine) -> Vec<u8> {
+    let mut out = Vec::with_capacity(machine.ram.len() + machine.vram.pixels.len() + 128);
+
+    out.extend_from_slice(&STATE_MAGIC);
+    out.extend_from_slice(&STATE_VERSION.to_le_bytes());
+    out.push(encode_platform(machine.platform));
+    out.push(encode_quirks(machine.quirks));
+    out.extend_from_slice(&(machine.pc as u32).to_le_bytes());
+    out.extend_from_slice(&(machine.i as u32).to_le_bytes());
+    out.push(machine.sp as u8);
+    out.push(machine.dt);
+    out.push(machine.st);
+    out.push(encode_flags(&[
+        (machine.draw, DRAW_FLAG),
+        (machine.vblank, VBLANK_FLAG),
+        (machine.halted, HALTED_FLAG),
+    ]));
+    out.push(machine.planes);
+    out.push(machine.pitch);
+    out.extend_from_slice(&machine.v);
+    out.extend_from_slice(&machine.rpl);
+    machine
+        .stack
+        .iter()
+        .for_each(|addr| out.extend_from_slice(&addr.to_le_bytes()));
+    out.extend_from_slice(&machine.audio_pattern);
+    out.push(match machine.vram.resolution {
+        Resolution::Low => 0,
+        Resolution::High => 1,
+    });
+    out.extend_from_slice(&machine.vram.pixels);
+    out.extend_from_slice(&(machine.ram.len() as u32).to_le_bytes());
+    out.extend_from_slice(&machine.ram);
+    match &machine.key_wait {
+        Some(wait) => {
+            out.push(if wait.pressed.is_some() {
+                KEY_WAIT_RELEASE
+            } else {
+                KEY_WAIT_PRESS
+            });
+            out.extend_from_slice(&encode_keys(&wait.keyboard.keys).to_le_bytes());
+            out.extend_from_slice(&encode_keys(&wait.keyboard.previous).to_le_bytes());
+            out.extend(wait.pressed);
+        }
+        None => out.push(NO_KEY_WAIT),
+    }
+    match &machine.rng {
+        MachineRng::Seeded(rng) => {
+            out.push(SEEDED_RNG);
+            out.extend_from_slice(&rng.state().to_le_bytes());
+        }
+        MachineRng::Custom(_) => out.push(NO_RNG),
+    }
+
+    out
+}
+
+/// Decodes a save-state into a new machine. The returned machine uses a default RNG when the save-state has no RNG
+/// state.
+pub(crate) fn decode(data: &[u8]) -> Result<Machine, StateError> {
+    let mut reader = Reader { data };
+
+    if reader.bytes(STATE_MAGIC.len())? != STATE_MAGIC {
+        return Err(StateError::InvalidMagic);
+    }
+
+    let version = reader.u16()?;
+    if version != STATE_VERSION {
+        return Err(StateError::UnsupportedVersion { version });
+    }
+
+    let mut machine = Machine::default();
+
+    machine.set_platform(decode_platform(reader.u8()?).ok_or(StateError::InvalidValue { field: "platform" })?);
+    machine.quirks = decode_quirks(reader.u8()?);
+    machine.pc = reader.u32()? as usize;
+    machine.i = reader.u32()? as usize;
+    machine.sp = reader.u8()? as usize;
+    if machine.sp >= STACK_SIZE {
+        return Err(StateError::InvalidValue { field: "sp" });
+    }
+    machine.dt = reader.u8()?;
+    machine.st = reader.u8()?;
+
+    let flags = reader.u8()?;
+    machine.draw = flags & DRAW_FLAG != 0;
+    machine.vblank = flags & VBLANK_FLAG != 0;
+    machine.halted = flags & HALTED_FLAG != 0;
+
+    machine.planes = reader.u8()?;
+    machine.pitch = reader.u8()?;
+    machine.v.copy_from_slice(reader.bytes(GENERAL_REGISTER_NUMBER)?);
+    machine.rpl.copy_from_slice(reader.bytes(RPL_FLAGS_NUMBER)?);
+    for addr in machine.stack.iter_mut() {
+        *addr = reader.u16()?;
+    }
+    machine.audio_pattern.copy_from_slice(reader.bytes(AUDIO_PATTERN_SIZE)?);
+
+    machine.vram.resolution = match reader.u8()? {
+        0 => Resolution::Low,
+        1 => Resolution::High,
+        _ => return Err(StateError::InvalidValue { field: "resolution" }),
+    };
+    let pixels_len = machine.vram.pixels.len();
+    machine.vram.pixels.copy_from_slice(reader.bytes(pixels_len)?);
+
+    let ram_len = reader.u32()? as usize;
+    if ram_len != machine.ram.len() {
+        return Err(StateError::InvalidValue { field: "ram" });
+    }
+    machine.ram.copy_from_slice(reader.bytes(ram_len)?);
+
+    let kind = reader.u8()?;
+    machine.key_wait = match kind {
+        NO_KEY_WAIT => None,
+        KEY_WAIT_PRESS | KEY_WAIT_RELEASE => {
+            let keys = decode_keys(reader.u16()?);
+            let previous = decode_keys(reader.u16()?);
+            let pressed = match kind {
+                KEY_WAIT_RELEASE => match reader.u8()? {
+                    key if (key as usize) < NUM_KEYS => Some(key),
+                    _ => return Err(StateError::InvalidValue { field: "key_wait" }),
+                },
+                _ => None,
+            };
+
+            Some(KeyWait {
+                keyboard: Keyboard { keys, previous },
+                pressed,
+            })
+        }
+        _ => return Err(StateError::InvalidValue { field: "key_wait" }),
+    };
+
+    match reader.u8()? {
+        NO_RNG => (),
+        SEEDED_RNG => machine.rng = MachineRng::Seeded(SeededRng::new(reader.u64()?)),
+        _ => return Err(StateError::InvalidValue { field: "rng" }),
+    }
+
+    if !reader.data.is_empty() {
+        return Err(StateError::TrailingBytes { len: reader.data.len() });
+    }
+
+    Ok(machine)
+}
+
+/// Encodes the platform as a single byte.
+pub(crate) fn encode_platform(platform: Platform) -> u8 {
+    match platform {
+        Platform::Chip8 => 0,
+        Platform::SuperChip => 1,
+        Platform::XoChip => 2,
+    }
+}
+
+/// Decodes the platform from a single byte.
+pub(crate) fn decode_platform(value: u8) -> Option<Platform> {
+    match value {
+        0 => Some(Platform::Chip8),
+        1 => Some(Platform::SuperChip),
+        2 => Some(Platform::XoChip),
+        _ => None,
+    }
+}
+
+/// Encodes the quirks as bit flags.
+pub(crate) fn encode_quirks(quirks: Quirks) -> u8 {
+    encode_flags(&[
+        (quirks.shift_uses_vy, 0x01),
+        (quirks.load_store_increments_i, 0x02),
+        (quirks.jump_uses_vx, 0x04),
+        (quirks.vf_reset, 0x08),
+        (quirks.sprite_wrap, 0x10),
+        (quirks.display_wait, 0x20),
+        (quirks.key_wait_release, 0x40),
+    ])
+}
+
+/// Decodes the quirks from bit flags.
+pub(crate) fn decode_quirks(value: u8) -> Quirks {
+    Quirks {
+        shift_uses_vy: value & 0x01 != 0,
+        load_store_increments_i: value & 0x02 != 0,
+        jump_uses_vx: value & 0x04 != 0,
+        vf_reset: value & 0x08 != 0,
+        sprite_wrap: value & 0x10 != 0,
+        display_wait: value & 0x20 != 0,
+        key_wait_release: value & 0x40 != 0,
+    }
+}
+
+/// Encodes the keys state as a bitmask, the key `n` in the bit `n`.
+fn encode_keys(keys: &Keys) -> u16 {
+    keys.iter()
+        .enumerate()
+        .filter(|(_, pressed)| **pressed)
+        .fold(0, |acc, (key, _)| acc | 1 << key)
+}
+
+/// Decodes the keys state from a bitmask.
+fn decode_keys(value: u16) -> Keys {
+    let mut keys = Keys::default();
+    keys.iter_mut()
+        .enumerate()
+        .for_each(|(key, pressed)| *pressed = value & 1 << key != 0);
+
+    keys
+}
+
+/// Packs the provided `(enabled, bit)` pairs into a single byte.
+fn encode_flags(flags: &[(bool, u8)]) -> u8 {
+    flags
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .fold(0, |acc, (_, bit)| acc | bit)
+}
+
+/// A cursor over the save-state bytes.
+struct Reader<'a> {
+    /// The bytes not read yet.
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Reads the next `len` bytes.
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        if self.data.len() < len {
+            return Err(StateError::Truncated);
+        }
+
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+
+        Ok(head)
+    }
+
+    /// Reads the next byte.
+    fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    /// Reads the next little-endian `u16`.
+    fn u16(&mut self) -> Result<u16, StateError> {
+        let bytes = self.bytes(2)?;
+
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Reads the next little-endian `u32`.
+    fn u32(&mut self) -> Result<u32, StateError> {
+        let bytes = self.bytes(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads the next little-endian `u64`.
+    fn u64(&mut self) -> Result<u64, StateError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.bytes(8)?);
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod test_state {
+    use rand::RngCore;
+
+    use crate::{StepOutcome, FIRST_PLANE, SECOND_PLANE};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let mut machine = Machine::default();
+
+        machine.set_platform(Platform::XoChip);
+        machine.quirks.display_wait = true;
+        machine.pc = 0x1234;
+        machine.i = 0xFFF0;
+        machine.sp = 2;
+        machine.stack[0] = 0x202;
+        machine.stack[1] = 0x302;
+        machine.dt = 10;
+        machine.st = 20;
+        machine.halted = true;
+        machine.planes = SECOND_PLANE;
+        machine.pitch = 100;
+        machine.v[0x3] = 0x33;
+        machine.rpl[0x4] = 0x44;
+        machine.audio_pattern[0x5] = 0x55;
+        machine.vram.set_resolution(Resolution::High);
+        machine.vram[0x10] = FIRST_PLANE;
+        machine.ram[0xFFFF] = 0xAB;
+
+        let loaded = decode(&encode(&machine)).expect("state should be decoded");
+
+        assert_eq!(loaded.platform, Platform::XoChip, "platform should be restored");
+        assert_eq!(loaded.quirks, machine.quirks, "quirks should be restored");
+        assert_eq!(loaded.pc, 0x1234, "pc should be restored");
+        assert_eq!(loaded.i, 0xFFF0, "i should be restored");
+        assert_eq!(loaded.sp, 2, "sp should be restored");
+        assert_eq!(loaded.stack, machine.stack, "stack should be restored");
+        assert_eq!(loaded.dt, 10, "dt should be restored");
+        assert_eq!(loaded.st, 20, "st should be restored");
+        assert!(loaded.halted, "halted flag should be restored");
+        assert!(!loaded.draw, "draw flag should be restored");
+        assert_eq!(loaded.planes, SECOND_PLANE, "planes should be restored");
+        assert_eq!(loaded.pitch, 100, "pitch should be restored");
+        assert_eq!(loaded.v, machine.v, "v registers should be restored");
+        assert_eq!(loaded.rpl, machine.rpl, "rpl flags should be restored");
+        assert_eq!(
+            loaded.audio_pattern, machine.audio_pattern,
+            "audio pattern should be restored"
+        );
+        assert_eq!(loaded.vram, machine.vram, "vram should be restored");
+        assert_eq!(loaded.ram, machine.ram, "ram should be restored");
+    }
+
+    #[test]
+    fn test_encode_decode_key_wait() {
+        let mut machine = Machine::default();
+        machine.set_quirks(Quirks::cosmac_vip());
+        machine.load_rom(&[0xF1, 0x0A]).expect("rom should be loaded");
+
+        let mut keys = [false; NUM_KEYS];
+        machine.step(&keys).expect("step should succeed");
+        keys[0x5] = true;
+        machine.step(&keys).expect("step should succeed");
+
+        let mut loaded = decode(&encode(&machine)).expect("state should be decoded");
+        assert_eq!(loaded.key_wait, machine.key_wait, "key wait should be restored");
+
+        keys[0x5] = false;
+        assert_eq!(
+            loaded.step(&keys),
+            Ok(StepOutcome::Executed),
+            "key release should end the restored wait"
+        );
+        assert_eq!(loaded.v[0x1], 0x5, "released key should be stored");
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
+    fn test_decode_invalid_key_wait() {
+        let mut machine = Machine::default();
+        machine.key_wait = Some(KeyWait {
+            keyboard: Keyboard::default(),
+            pressed: Some(NUM_KEYS as u8),
+        });
+
+        assert_eq!(
+            decode(&encode(&machine)).err(),
+            Some(StateError::InvalidValue { field: "key_wait" }),
+            "should return InvalidValue"
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_magic() {
+        let mut data = encode(&Machine::default());
+        data[0] = b'X';
+
+        assert_eq!(
+            decode(&data).err(),
+            Some(StateError::InvalidMagic),
+            "should return InvalidMagic"
+        );
+    }
+
+    #[test]
+    fn test_decode_unsupported_version() {
+        let mut data = encode(&Machine::default());
+        data[4..6].copy_from_slice(&99u16.to_le_bytes());
+
+        assert_eq!(
+            decode(&data).err(),
+            Some(StateError::UnsupportedVersion { version: 99 }),
+            "should return UnsupportedVersion"
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated() {
+        let data = encode(&Machine::default());
+
+        assert_eq!(
+            decode(&data[..data.len() - 1]).err(),
+            Some(StateError::Truncated),
+            "should return Truncated"
+        );
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() {
+        let mut data = encode(&Machine::default());
+        data.push(0);
+
+        assert_eq!(
+            decode(&data).err(),
+            Some(StateError::TrailingBytes { len: 1 }),
+            "should return TrailingBytes"
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_platform() {
+        let mut data = encode(&Machine::default());
+        data[6] = 0xFF;
+
+        assert_eq!(
+            decode(&data).err(),
+            Some(StateError::InvalidValue { field: "platform" }),
+            "should return InvalidValue"
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_sp() {
+        let mut data = encode(&Machine::default());
+        data[16] = STACK_SIZE as u8;
+
+        assert_eq!(
+            decode(&data).err(),
+            Some(StateError::InvalidValue { field: "sp" }),
+            "should return InvalidValue"
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_seeded_rng() {
+        let mut machine = Machine::with_seed(42);
+        machine.rng.next_u64();
+
+        let mut loaded = decode(&encode(&machine)).expect("state should be decoded");
+
+        assert_eq!(
+            loaded.rng.next_u64(),
+            machine.rng.next_u64(),
+            "rng should continue the same sequence"
+        );
+    }
+}
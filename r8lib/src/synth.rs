@@ -0,0 +1,348 @@
+//! CHIP-8's beep synthesizer, rendering the tone into samples independently of the audio device.
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use crate::machine::AudioPattern;
+
+/// The default tone frequency, in Hz.
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+/// The default tone volume, from `0.0` to `1.0`.
+pub const DEFAULT_VOLUME: f32 = 0.5;
+/// The default time taken by the tone to fade in.
+pub const DEFAULT_ATTACK: Duration = Duration::from_millis(5);
+/// The default time taken by the tone to fade out.
+pub const DEFAULT_RELEASE: Duration = Duration::from_millis(10);
+
+/// The 1-bit samples of an XO-CHIP audio pattern.
+const PATTERN_BITS: usize = 128;
+
+/// The shape of the tone wave.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    /// A square wave, the buzz of the original hardware.
+    Square,
+    /// A sine wave, the softest tone.
+    #[default]
+    Sine,
+    /// A triangle wave.
+    Triangle,
+}
+
+impl Waveform {
+    /// Returns the wave value at `phase`, from `0.0` to `1.0` through a period.
+    pub fn value(&self, phase: f32) -> f32 {
+        match self {
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Sine => (phase * TAU).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (phase - 0.25 - (phase - 0.25).round()).abs(),
+        }
+    }
+}
+
+/// The tone played while the sound timer is active.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    /// The shape of the wave.
+    pub waveform: Waveform,
+    /// The wave frequency, in Hz.
+    pub frequency: f32,
+    /// The peak amplitude, from `0.0` to `1.0`.
+    pub volume: f32,
+    /// The time taken to fade in when the tone starts.
+    pub attack: Duration,
+    /// The time taken to fade out when the tone stops.
+    pub release: Duration,
+}
+
+impl Default for Tone {
+    /// Creates a sine tone at [`DEFAULT_FREQUENCY`] and [`DEFAULT_VOLUME`], with a short attack and release.
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::default(),
+            frequency: DEFAULT_FREQUENCY,
+            volume: DEFAULT_VOLUME,
+            attack: DEFAULT_ATTACK,
+            release: DEFAULT_RELEASE,
+        }
+    }
+}
+
+/// The beep synthesizer, rendering a [`Tone`] as mono samples from `-1.0` to `1.0`.
+///
+/// The tone is turned on and off by a gate, and its linear attack and release envelope avoids the clicks of starting
+/// and stopping the wave abruptly. The wave keeps its phase while silent, so it always resumes smoothly.
+///
+/// An XO-CHIP audio pattern replaces the tone while set, looping its 128 1-bit samples at the pattern playback rate.
+/// It follows the same envelope and volume.
+#[derive(Debug, Clone)]
+pub struct Synth {
+    /// The rendered tone.
+    tone: Tone,
+    /// The samples rendered per second.
+    sample_rate: u32,
+    /// The position of the wave through its period, from `0.0` to `1.0`.
+    phase: f32,
+    /// The envelope level, from `0.0` (silent) to `1.0`.
+    level: f32,
+    /// Tells if the tone is on.
+    gate: bool,
+    /// The XO-CHIP audio pattern replacing the tone, with its playback rate in samples per second.
+    pattern: Option<(AudioPattern, f32)>,
+    /// The position through the audio pattern, from `0.0` to `1.0`.
+    pattern_phase: f32,
+}
+
+impl Synth {
+    /// Creates a silent synthesizer rendering `tone` at `sample_rate` samples per second.
+    pub fn new(tone: Tone, sample_rate: u32) -> Self {
+        Self {
+            tone,
+            sample_rate: sample_rate.max(1),
+            phase: 0.0,
+            level: 0.0,
+            gate: false,
+            pattern: None,
+            pattern_phase: 0.0,
+        }
+    }
+
+    /// Returns the rendered tone.
+    pub fn tone(&self) -> &Tone {
+        &self.tone
+    }
+
+    /// Returns the samples rendered per second.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Turns the tone on or off. The change follows the envelope.
+    pub fn set_gate(&mut self, gate: bool) {
+        self.gate = gate;
+    }
+
+    /// Plays the provided XO-CHIP audio pattern and playback rate instead of the tone, or the tone when `None`.
+    pub fn set_pattern(&mut self, pattern: Option<(AudioPattern, f32)>) {
+        self.pattern = pattern;
+    }
+
+    /// Tells if the tone is off and fully faded out.
+    pub fn is_silent(&self) -> bool {
+        !self.gate && self.level == 0.0
+    }
+
+    /// Renders the next sample.
+    pub fn next_sample(&mut self) -> f32 {
+        let (target, duration) = if self.gate {
+            (1.0, self.tone.attack)
+        } else {
+            (0.0, self.tone.release)
+        };
+        let step = 1.0 / (duration.as_secs_f32() * self.sample_rate as f32).max(1.0);
+        self.level = if self.level < target {
+            (self.level + step).min(target)
+        } else {
+            (self.level - step).max(target)
+        };
+
+        let value = match &self.pattern {
+            Some((pattern, rate)) => {
+                let bit = (self.pattern_phase * PATTERN_BITS as f32) as usize % PATTERN_BITS;
+                self.pattern_phase =
+                    (self.pattern_phase + rate / PATTERN_BITS as f32 / self.sample_rate as f32).fract();
+
+                if pattern[bit / 8] & (0x80 >> (bit % 8)) != 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            None => self.tone.waveform.value(self.phase),
+        };
+        let sample = value * self.tone.volume.clamp(0.0, 1.0) * self.level;
+        self.phase = (self.phase + self.tone.frequency / self.sample_rate as f32).fract();
+
+        sample
+    }
+
+    /// Fills `samples` with the next rendered samples.
+    pub fn render(&mut self, samples: &mut [f32]) {
+        samples.iter_mut().for_each(|sample| *sample = self.next_sample());
+    }
+}
+
+#[cfg(test)]
+mod test_synth {
+    use super::*;
+
+    // Every wave phase step is exact in binary at this rate, with 100Hz and 250Hz tones.
+    const SAMPLE_RATE: u32 = 6400;
+
+    fn tone(waveform: Waveform) -> Tone {
+        Tone {
+            waveform,
+            frequency: 100.0,
+            volume: 1.0,
+            attack: Duration::ZERO,
+            release: Duration::ZERO,
+        }
+    }
+
+    fn render(synth: &mut Synth, len: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; len];
+        synth.render(&mut samples);
+
+        samples
+    }
+
+    #[test]
+    fn test_silent() {
+        let mut synth = Synth::new(Tone::default(), SAMPLE_RATE);
+
+        assert!(synth.is_silent(), "synth should start silent");
+        assert!(
+            render(&mut synth, 100).iter().all(|&sample| sample == 0.0),
+            "silent synth should render zeros"
+        );
+    }
+
+    #[test]
+    fn test_waveforms() {
+        let quarter = (SAMPLE_RATE / 100 / 4) as usize;
+
+        let mut synth = Synth::new(tone(Waveform::Square), SAMPLE_RATE);
+        synth.set_gate(true);
+        let samples = render(&mut synth, quarter * 4);
+        assert!(
+            samples[..quarter * 2].iter().all(|&sample| sample == 1.0),
+            "square should be high first"
+        );
+        assert!(
+            samples[quarter * 2..].iter().all(|&sample| sample == -1.0),
+            "square should be low last"
+        );
+
+        let mut synth = Synth::new(tone(Waveform::Sine), SAMPLE_RATE);
+        synth.set_gate(true);
+        let samples = render(&mut synth, quarter * 4 + 1);
+        assert!(
+            (samples[quarter] - 1.0).abs() < 1e-3,
+            "sine should peak at a quarter period"
+        );
+        assert!(
+            (samples[quarter * 3] + 1.0).abs() < 1e-3,
+            "sine should dip at three quarters"
+        );
+        assert!(
+            samples[quarter * 4].abs() < 1e-3,
+            "sine should cross zero after a period"
+        );
+
+        let mut synth = Synth::new(tone(Waveform::Triangle), SAMPLE_RATE);
+        synth.set_gate(true);
+        let samples = render(&mut synth, quarter * 4);
+        assert!(samples[0].abs() < 1e-3, "triangle should start at zero");
+        assert!(
+            (samples[quarter] - 1.0).abs() < 1e-3,
+            "triangle should peak at a quarter period"
+        );
+        assert!(
+            samples[quarter * 2].abs() < 1e-3,
+            "triangle should cross zero at half a period"
+        );
+        assert!(
+            (samples[quarter / 2] - 0.5).abs() < 1e-3,
+            "triangle should rise linearly"
+        );
+    }
+
+    #[test]
+    fn test_frequency() {
+        let tone = Tone {
+            frequency: 250.0,
+            ..tone(Waveform::Square)
+        };
+        let mut synth = Synth::new(tone, SAMPLE_RATE);
+        synth.set_gate(true);
+
+        let samples = render(&mut synth, SAMPLE_RATE as usize);
+        let rising = samples.windows(2).filter(|pair| pair[0] < 0.0 && pair[1] > 0.0).count();
+
+        assert_eq!(rising, 249, "square should rise once per period after the first");
+    }
+
+    #[test]
+    fn test_volume() {
+        let tone = Tone {
+            volume: 0.25,
+            ..tone(Waveform::Square)
+        };
+        let mut synth = Synth::new(tone, SAMPLE_RATE);
+        synth.set_gate(true);
+
+        let samples = render(&mut synth, 100);
+
+        assert!(
+            samples.iter().all(|sample| sample.abs() == 0.25),
+            "samples should be scaled by the volume"
+        );
+    }
+
+    #[test]
+    fn test_pattern() {
+        let mut pattern = [0; 16];
+        pattern[0] = 0b1010_0000;
+        let mut synth = Synth::new(tone(Waveform::Sine), SAMPLE_RATE);
+        synth.set_gate(true);
+
+        // Two samples per pattern bit.
+        synth.set_pattern(Some((pattern, SAMPLE_RATE as f32 / 2.0)));
+        let samples = render(&mut synth, 8);
+        assert_eq!(
+            samples,
+            [1.0, 1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0],
+            "pattern bits should be played in order"
+        );
+
+        synth.set_pattern(None);
+        assert!(
+            render(&mut synth, 1)[0].abs() < 1.0,
+            "tone should be played without a pattern"
+        );
+    }
+
+    #[test]
+    fn test_envelope() {
+        let tone = Tone {
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(20),
+            ..tone(Waveform::Square)
+        };
+        let mut synth = Synth::new(tone, SAMPLE_RATE);
+
+        synth.set_gate(true);
+        let levels: Vec<f32> = render(&mut synth, 64).iter().map(|sample| sample.abs()).collect();
+        assert!(levels[0] < 0.05, "attack should start near silence");
+        assert!(
+            levels.windows(2).all(|pair| pair[1] > pair[0]),
+            "attack should rise over 10ms"
+        );
+        assert_eq!(levels[63], 1.0, "tone should reach full volume after the attack");
+
+        synth.set_gate(false);
+        let levels: Vec<f32> = render(&mut synth, 128).iter().map(|sample| sample.abs()).collect();
+        assert!(
+            levels.windows(2).all(|pair| pair[1] < pair[0]),
+            "release should fade over 20ms"
+        );
+        assert_eq!(levels[127], 0.0, "release should end silent");
+        assert!(synth.is_silent(), "synth should be silent after the release");
+    }
+}
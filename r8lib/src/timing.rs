@@ -0,0 +1,137 @@
+//! CHIP-8's timing models, how much of a frame every instruction takes to run.
+
+use crate::instruction::Instruction;
+
+/// The COSMAC VIP machine cycles in a 60Hz frame. The CDP1802 runs at 1.76 MHz, taking 8 clocks per machine cycle.
+pub const VIP_FRAME_CYCLES: u32 = 3668;
+/// The COSMAC VIP machine cycles taken every frame by the display interrupt and the CDP1861 DMA transfer.
+pub const VIP_DISPLAY_CYCLES: u32 = 1832;
+/// The COSMAC VIP machine cycles left to run instructions in a frame.
+pub const VIP_INSTRUCTION_CYCLES: u32 = VIP_FRAME_CYCLES - VIP_DISPLAY_CYCLES;
+/// The COSMAC VIP machine cycles taken by the interpreter to fetch and decode an instruction.
+const VIP_FETCH_CYCLES: u32 = 40;
+
+/// How the time spent running instructions is measured.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    /// Every instruction takes the same time. Frontends run a fixed number of instructions per frame.
+    #[default]
+    Fixed,
+    /// Every instruction is charged the machine cycles it takes on the original COSMAC VIP interpreter, and `Dxyn`
+    /// waits for the display interrupt. The machine waits for the next frame once [`VIP_INSTRUCTION_CYCLES`] are
+    /// spent, so the instructions run per frame depend on the instructions themselves.
+    CosmacVip,
+}
+
+impl Timing {
+    /// Returns the cycles available to run instructions in a frame, or `None` when the instructions aren't charged.
+    pub fn frame_cycles(&self) -> Option<u32> {
+        match self {
+            Timing::Fixed => None,
+            Timing::CosmacVip => Some(VIP_INSTRUCTION_CYCLES),
+        }
+    }
+
+    /// Tells if `Dxyn` waits for the next display refresh before drawing, whatever the quirks.
+    pub fn waits_display(&self) -> bool {
+        matches!(self, Timing::CosmacVip)
+    }
+
+    /// Returns the cycles taken by the provided instruction. `skipped` tells if a skip instruction skipped the next
+    /// one.
+    pub fn instruction_cycles(&self, instruction: &Instruction, skipped: bool) -> u32 {
+        match self {
+            Timing::Fixed => 0,
+            Timing::CosmacVip => VIP_FETCH_CYCLES + vip_cycles(instruction, skipped),
+        }
+    }
+}
+
+/// Returns the COSMAC VIP execution cycles of an instruction, after it was fetched and decoded.
+///
+/// The values approximate the ones measured on the original interpreter. Drawing and memory transfers depend on the
+/// sprite rows or registers involved. Instructions unknown to the VIP take the cost of a jump.
+fn vip_cycles(instruction: &Instruction, skipped: bool) -> u32 {
+    let skip = if skipped { 4 } else { 0 };
+
+    match *instruction {
+        Instruction::Cls => 24,
+        Instruction::Ret => 10,
+        Instruction::Jp { .. } => 12,
+        Instruction::Call { .. } => 26,
+        Instruction::SeByte { .. } | Instruction::SneByte { .. } => 10 + skip,
+        Instruction::SeReg { .. } | Instruction::SneReg { .. } => 14 + skip,
+        Instruction::LdByte { .. } => 6,
+        Instruction::AddByte { .. } => 10,
+        Instruction::LdReg { .. }
+        | Instruction::Or { .. }
+        | Instruction::And { .. }
+        | Instruction::Xor { .. }
+        | Instruction::AddReg { .. }
+        | Instruction::Sub { .. }
+        | Instruction::Shr { .. }
+        | Instruction::Subn { .. }
+        | Instruction::Shl { .. } => 44,
+        Instruction::LdI { .. } => 12,
+        Instruction::JpV0 { .. } => 22,
+        Instruction::Rnd { .. } => 36,
+        Instruction::Drw { n, .. } => 26 + 46 * n.max(1) as u32,
+        Instruction::Skp { .. } | Instruction::Sknp { .. } => 14 + skip,
+        Instruction::LdVxDt { .. } | Instruction::LdVxK { .. } => 10,
+        Instruction::LdDtVx { .. } | Instruction::LdStVx { .. } => 10,
+        Instruction::AddI { .. } | Instruction::LdF { .. } => 16,
+        Instruction::LdB { .. } => 84,
+        Instruction::LdIVx { x } | Instruction::LdVxI { x } => 14 + 14 * (x as u32 + 1),
+        _ => 12,
+    }
+}
+
+#[cfg(test)]
+mod test_timing {
+    use super::*;
+
+    #[test]
+    fn test_fixed() {
+        let timing = Timing::Fixed;
+
+        assert_eq!(timing.frame_cycles(), None, "fixed timing should have no frame budget");
+        assert!(!timing.waits_display(), "fixed timing should follow the quirks");
+        assert_eq!(
+            timing.instruction_cycles(&Instruction::Cls, false),
+            0,
+            "fixed timing should not charge instructions"
+        );
+    }
+
+    #[test]
+    fn test_cosmac_vip() {
+        let timing = Timing::CosmacVip;
+
+        assert_eq!(
+            timing.frame_cycles(),
+            Some(1836),
+            "frame budget should skip the display interrupt"
+        );
+        assert!(timing.waits_display(), "drawing should wait the display interrupt");
+        assert_eq!(
+            timing.instruction_cycles(&Instruction::LdByte { x: 0, kk: 0 }, false),
+            46,
+            "6xkk should be charged the fetch and its execution"
+        );
+        assert_eq!(
+            timing.instruction_cycles(&Instruction::SeByte { x: 0, kk: 0 }, true),
+            timing.instruction_cycles(&Instruction::SeByte { x: 0, kk: 0 }, false) + 4,
+            "taken skips should cost more"
+        );
+        assert!(
+            timing.instruction_cycles(&Instruction::Drw { x: 0, y: 0, n: 15 }, false)
+                > timing.instruction_cycles(&Instruction::Drw { x: 0, y: 0, n: 1 }, false),
+            "taller sprites should cost more"
+        );
+        assert!(
+            timing.instruction_cycles(&Instruction::LdIVx { x: 15 }, false)
+                > timing.instruction_cycles(&Instruction::LdIVx { x: 0 }, false),
+            "storing more registers should cost more"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! CHIP-8's execution tracer, writing the machine state before every executed instruction.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::instruction::Instruction;
+use crate::view::MachineState;
+
+/// Writes a line for every instruction executed by a machine.
+///
+/// Each line has the number of instructions executed before it, followed by the machine state right before the
+/// instruction is executed, in a fixed-width layout that can be diffed against other traces:
+///
+/// ```text
+/// 00000000 PC=0200 OP=6120 LD V1, 0x20          V=00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 I=0000 SP=0 DT=00 ST=00
+/// ```
+///
+/// A tracer is installed with [`Machine::set_tracer`](crate::Machine::set_tracer). Instructions waiting for a key
+/// press or a display refresh aren't traced until they're executed.
+pub struct Tracer {
+    /// Where the lines are written.
+    writer: Box<dyn Write>,
+    /// The number of traced instructions.
+    cycle: u64,
+}
+
+impl Tracer {
+    /// Creates a tracer writing into the provided writer. Use a buffered writer for files.
+    pub fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+            cycle: 0,
+        }
+    }
+
+    /// Returns the number of traced instructions.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Formats the trace line of the provided machine state, executing `opcode`.
+    pub(crate) fn line(&self, state: &MachineState, opcode: u16) -> String {
+        TraceLine {
+            cycle: self.cycle,
+            state,
+            opcode,
+        }
+        .to_string()
+    }
+
+    /// Writes a trace line, counting a new traced instruction.
+    pub(crate) fn write_line(&mut self, line: &str) -> io::Result<()> {
+        self.cycle += 1;
+
+        writeln!(self.writer, "{}", line)
+    }
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer").field("cycle", &self.cycle).finish()
+    }
+}
+
+/// A single trace line.
+struct TraceLine<'a, 'b> {
+    /// The number of instructions executed before this one.
+    cycle: u64,
+    /// The machine state before executing the instruction.
+    state: &'b MachineState<'a>,
+    /// The executed opcode.
+    opcode: u16,
+}
+
+impl fmt::Display for TraceLine<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = Instruction::decode(self.opcode).to_string();
+
+        write!(
+            f,
+            "{:08} PC={:04X} OP={:04X} {:<20} V=",
+            self.cycle,
+            self.state.pc(),
+            self.opcode,
+            mnemonic
+        )?;
+        for (n, value) in self.state.v().iter().enumerate() {
+            let separator = if n == 0 { "" } else { " " };
+            write!(f, "{}{:02X}", separator, value)?;
+        }
+        write!(
+            f,
+            " I={:04X} SP={:X} DT={:02X} ST={:02X}",
+            self.state.i(),
+            self.state.sp(),
+            self.state.dt(),
+            self.state.st()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_tracer {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::{Machine, StepOutcome};
+
+    use super::*;
+
+    /// A writer whose contents can be read after being moved into a tracer.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let buffer = SharedBuffer::default();
+        let mut machine = Machine::default();
+        machine
+            .load_rom(&[0x61, 0x20, 0xA1, 0x23])
+            .expect("rom should be loaded");
+        machine.set_tracer(Some(Tracer::new(buffer.clone())));
+
+        machine.step(&[false; 16]).expect("step should succeed");
+        machine.step(&[false; 16]).expect("step should succeed");
+
+        let trace = String::from_utf8(buffer.0.borrow().clone()).expect("trace should be utf-8");
+        let zeros = "00 00 00 00 00 00 00 00 00 00 00 00 00 00";
+
+        assert_eq!(
+            trace.lines().collect::<Vec<_>>(),
+            [
+                format!("00000000 PC=0200 OP=6120 LD V1, 0x20          V=00 00 {zeros} I=0000 SP=0 DT=00 ST=00"),
+                format!("00000001 PC=0202 OP=A123 LD I, 0x123          V=00 20 {zeros} I=0000 SP=0 DT=00 ST=00"),
+            ],
+            "trace should match"
+        );
+        assert_eq!(
+            machine.tracer().map(Tracer::cycle),
+            Some(2),
+            "two instructions should be traced"
+        );
+    }
+
+    #[test]
+    fn test_trace_skips_waiting() {
+        let buffer = SharedBuffer::default();
+        let mut machine = Machine::default();
+        machine.load_rom(&[0xF0, 0x0A]).expect("rom should be loaded");
+        machine.set_tracer(Some(Tracer::new(buffer.clone())));
+
+        assert_eq!(
+            machine.step(&[false; 16]),
+            Ok(StepOutcome::WaitingInput),
+            "machine should wait a key"
+        );
+        assert!(buffer.0.borrow().is_empty(), "waiting instruction should not be traced");
+    }
+}
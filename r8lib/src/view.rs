@@ -0,0 +1,157 @@
+//! CHIP-8's machine read-only view, for debuggers, overlays and test harnesses.
+
+use crate::machine::Machine;
+
+/// A read-only view of the machine registers, call stack, timers and memory.
+#[derive(Debug, Clone, Copy)]
+pub struct MachineState<'a> {
+    /// The viewed machine.
+    machine: &'a Machine,
+}
+
+impl<'a> MachineState<'a> {
+    /// Creates a view of the provided machine.
+    pub(crate) fn new(machine: &'a Machine) -> Self {
+        Self { machine }
+    }
+
+    /// Returns the general use registers, `V0` through `VF`.
+    pub fn v(&self) -> &'a [u8] {
+        &self.machine.v
+    }
+
+    /// Returns the `I` register.
+    pub fn i(&self) -> usize {
+        self.machine.i
+    }
+
+    /// Returns the program counter.
+    pub fn pc(&self) -> usize {
+        self.machine.pc
+    }
+
+    /// Returns the stack pointer, the number of subroutine calls in the call stack.
+    pub fn sp(&self) -> usize {
+        self.machine.sp
+    }
+
+    /// Returns the addresses of the subroutine calls in the call stack, the most recent last.
+    pub fn stack(&self) -> &'a [u16] {
+        // The first stack slot is never used, the stack pointer points to the most recent call.
+        &self.machine.stack[1..=self.machine.sp]
+    }
+
+    /// Returns the delay timer.
+    pub fn dt(&self) -> u8 {
+        self.machine.dt
+    }
+
+    /// Returns the sound timer.
+    pub fn st(&self) -> u8 {
+        self.machine.st
+    }
+
+    /// Returns the SUPER-CHIP RPL user flags.
+    pub fn rpl(&self) -> &'a [u8] {
+        &self.machine.rpl
+    }
+
+    /// Returns the XO-CHIP planes selected for drawing.
+    pub fn planes(&self) -> u8 {
+        self.machine.planes
+    }
+
+    /// Returns the whole RAM.
+    pub fn ram(&self) -> &'a [u8] {
+        &self.machine.ram
+    }
+
+    /// Returns `len` bytes of RAM starting at `addr`, or `None` if they don't fit inside the RAM.
+    pub fn ram_slice(&self, addr: usize, len: usize) -> Option<&'a [u8]> {
+        self.machine.ram.get(addr..addr.checked_add(len)?)
+    }
+
+    /// Returns the opcode at the program counter, or `None` if it doesn't fit inside the RAM.
+    pub fn opcode(&self) -> Option<u16> {
+        let bytes = self.ram_slice(self.pc(), 2)?;
+
+        Some((bytes[0] as u16) << 8 | bytes[1] as u16)
+    }
+
+    /// Tells if the machine exited and won't run any other instruction.
+    pub fn is_halted(&self) -> bool {
+        self.machine.halted
+    }
+}
+
+#[cfg(test)]
+mod test_view {
+    use crate::MachineError;
+
+    use super::*;
+
+    #[test]
+    fn test_state() {
+        let mut machine = Machine::default();
+        machine
+            .load_rom(&[0x22, 0x04, 0x00, 0xE0, 0x00, 0xEE])
+            .expect("rom should be loaded");
+        machine.step(&[false; 16]).expect("step should succeed");
+
+        let state = machine.state();
+
+        assert_eq!(state.pc(), 0x204, "pc should be at the subroutine");
+        assert_eq!(state.sp(), 1, "sp should have a single call");
+        assert_eq!(state.stack(), [0x200], "stack should have the caller address");
+        assert_eq!(state.opcode(), Some(0x00EE), "opcode should be the return");
+        assert_eq!(
+            state.ram_slice(0x200, 2),
+            Some(&[0x22, 0x04][..]),
+            "ram slice should match"
+        );
+        assert_eq!(
+            state.ram_slice(state.ram().len() - 1, 2),
+            None,
+            "ram slice should be out of bounds"
+        );
+    }
+
+    #[test]
+    fn test_setters() {
+        let mut machine = Machine::default();
+
+        machine.set_v(0xF, 0x12);
+        machine.set_i(0x345);
+        machine.set_dt(6);
+        machine.set_st(7);
+        machine.set_pc(0x300).expect("pc should be set");
+        machine.write_ram(0x300, &[0xAB, 0xCD]).expect("ram should be written");
+
+        let state = machine.state();
+
+        assert_eq!(state.v()[0xF], 0x12, "VF should be set");
+        assert_eq!(state.i(), 0x345, "I should be set");
+        assert_eq!((state.dt(), state.st()), (6, 7), "timers should be set");
+        assert_eq!(state.pc(), 0x300, "pc should be set");
+        assert_eq!(state.opcode(), Some(0xABCD), "ram should be written");
+    }
+
+    #[test]
+    fn test_setters_out_of_bounds() {
+        let mut machine = Machine::default();
+        let len = machine.state().ram().len();
+
+        assert!(
+            matches!(machine.set_pc(len - 1), Err(MachineError::MemoryOutOfBounds { .. })),
+            "pc should not be set"
+        );
+        assert!(
+            matches!(
+                machine.write_ram(len - 1, &[0x00, 0x00]),
+                Err(MachineError::MemoryOutOfBounds { .. })
+            ),
+            "ram should not be written"
+        );
+        assert_eq!(machine.state().pc(), 0x200, "pc should be unchanged");
+    }
+}
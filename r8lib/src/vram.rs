@@ -0,0 +1,279 @@
+//! CHIP-8's screen representation.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::machine::{HIRES_VRAM_HEIGHT, HIRES_VRAM_WIDTH, VRAM_HEIGHT, VRAM_WIDTH};
+
+/// CHIP-8's screen resolutions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The original 64x32 resolution.
+    #[default]
+    Low,
+    /// The SUPER-CHIP 128x64 resolution.
+    High,
+}
+
+impl Resolution {
+    /// Returns the screen width, in pixels.
+    pub fn width(&self) -> usize {
+        match self {
+            Resolution::Low => VRAM_WIDTH,
+            Resolution::High => HIRES_VRAM_WIDTH,
+        }
+    }
+
+    /// Returns the screen height, in pixels.
+    pub fn height(&self) -> usize {
+        match self {
+            Resolution::Low => VRAM_HEIGHT,
+            Resolution::High => HIRES_VRAM_HEIGHT,
+        }
+    }
+}
+
+/// Bitmask of the first screen plane, the only one used by CHIP-8 and SUPER-CHIP.
+pub const FIRST_PLANE: u8 = 0b01;
+/// Bitmask of the second screen plane, added by XO-CHIP.
+pub const SECOND_PLANE: u8 = 0b10;
+/// Bitmask of every screen plane.
+pub const ALL_PLANES: u8 = FIRST_PLANE | SECOND_PLANE;
+
+/// The CHIP-8's screen state.
+///
+/// It dereferences to a row-major slice of [`u8`]s, sized by the current [`Resolution`]. Each pixel value is a bitmask
+/// of the planes where it is lit, so it ranges from `0` (off) to [`ALL_PLANES`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Vram {
+    /// The current screen resolution.
+    pub(crate) resolution: Resolution,
+    /// The screen pixels, big enough to hold the highest resolution.
+    pub(crate) pixels: [u8; HIRES_VRAM_WIDTH * HIRES_VRAM_HEIGHT],
+}
+
+impl Vram {
+    /// Returns the current screen resolution.
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Returns the current screen width, in pixels.
+    pub fn width(&self) -> usize {
+        self.resolution.width()
+    }
+
+    /// Returns the current screen height, in pixels.
+    pub fn height(&self) -> usize {
+        self.resolution.height()
+    }
+
+    /// Changes the screen resolution, clearing every plane.
+    pub(crate) fn set_resolution(&mut self, resolution: Resolution) {
+        self.resolution = resolution;
+        self.clear(ALL_PLANES);
+    }
+
+    /// Turns off every screen pixel in the `planes` bitmask.
+    pub(crate) fn clear(&mut self, planes: u8) {
+        self.pixels.iter_mut().for_each(|p| *p &= !planes);
+    }
+
+    /// Scrolls the `planes` bitmask up by `n` pixels.
+    pub(crate) fn scroll_up(&mut self, planes: u8, n: usize) {
+        let (width, height) = (self.width(), self.height());
+
+        for y in 0..height {
+            for x in 0..width {
+                let src = if y + n < height {
+                    self.pixels[(y + n) * width + x]
+                } else {
+                    0
+                };
+                self.move_pixel(planes, src, y * width + x);
+            }
+        }
+    }
+
+    /// Scrolls the `planes` bitmask down by `n` pixels.
+    pub(crate) fn scroll_down(&mut self, planes: u8, n: usize) {
+        let (width, height) = (self.width(), self.height());
+
+        for y in (0..height).rev() {
+            for x in 0..width {
+                let src = if y >= n { self.pixels[(y - n) * width + x] } else { 0 };
+                self.move_pixel(planes, src, y * width + x);
+            }
+        }
+    }
+
+    /// Scrolls the `planes` bitmask left by `n` pixels.
+    pub(crate) fn scroll_left(&mut self, planes: u8, n: usize) {
+        let (width, height) = (self.width(), self.height());
+
+        for y in 0..height {
+            for x in 0..width {
+                let src = if x + n < width {
+                    self.pixels[y * width + x + n]
+                } else {
+                    0
+                };
+                self.move_pixel(planes, src, y * width + x);
+            }
+        }
+    }
+
+    /// Scrolls the `planes` bitmask right by `n` pixels.
+    pub(crate) fn scroll_right(&mut self, planes: u8, n: usize) {
+        let (width, height) = (self.width(), self.height());
+
+        for y in 0..height {
+            for x in (0..width).rev() {
+                let src = if x >= n { self.pixels[y * width + x - n] } else { 0 };
+                self.move_pixel(planes, src, y * width + x);
+            }
+        }
+    }
+
+    /// Replaces the `planes` bits of the pixel at `idx` with the ones from `src`.
+    fn move_pixel(&mut self, planes: u8, src: u8, idx: usize) {
+        self.pixels[idx] = (self.pixels[idx] & !planes) | (src & planes);
+    }
+}
+
+impl Deref for Vram {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.pixels[..self.width() * self.height()]
+    }
+}
+
+impl DerefMut for Vram {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let len = self.width() * self.height();
+        &mut self.pixels[..len]
+    }
+}
+
+impl fmt::Debug for Vram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Vram")
+            .field("resolution", &self.resolution)
+            .field("pixels", &self.deref())
+            .finish()
+    }
+}
+
+impl Default for Vram {
+    /// Creates a low resolution [`Vram`] with every pixel turned off.
+    fn default() -> Self {
+        Self {
+            resolution: Resolution::default(),
+            pixels: [0; HIRES_VRAM_WIDTH * HIRES_VRAM_HEIGHT],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_vram {
+    use super::*;
+
+    /// Creates a VRAM with the provided resolution and lit pixels.
+    fn vram_with(resolution: Resolution, lit: &[(usize, usize, u8)]) -> Vram {
+        let mut vram = Vram::default();
+        vram.set_resolution(resolution);
+        lit.iter()
+            .for_each(|(x, y, planes)| vram.pixels[y * resolution.width() + x] = *planes);
+        vram
+    }
+
+    #[test]
+    fn test_set_resolution() {
+        let mut vram = vram_with(Resolution::Low, &[(1, 1, ALL_PLANES)]);
+
+        vram.set_resolution(Resolution::High);
+
+        assert_eq!(vram.resolution(), Resolution::High, "vram resolution should be high");
+        assert_eq!(vram.len(), 128 * 64, "vram should have 128x64 pixels");
+        assert!(vram.iter().all(|p| *p == 0), "vram should be cleared");
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut vram = vram_with(Resolution::Low, &[(1, 1, ALL_PLANES), (2, 2, FIRST_PLANE)]);
+
+        vram.clear(FIRST_PLANE);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::Low, &[(1, 1, SECOND_PLANE)]),
+            "vram should have only the first plane cleared"
+        );
+    }
+
+    #[test]
+    fn test_scroll_up() {
+        let mut vram = vram_with(Resolution::Low, &[(1, 1, FIRST_PLANE), (2, 30, FIRST_PLANE)]);
+
+        vram.scroll_up(FIRST_PLANE, 2);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::Low, &[(2, 28, FIRST_PLANE)]),
+            "vram should be scrolled up by 2"
+        );
+    }
+
+    #[test]
+    fn test_scroll_down() {
+        let mut vram = vram_with(Resolution::Low, &[(1, 1, FIRST_PLANE), (2, 30, FIRST_PLANE)]);
+
+        vram.scroll_down(FIRST_PLANE, 2);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::Low, &[(1, 3, FIRST_PLANE)]),
+            "vram should be scrolled down by 2"
+        );
+    }
+
+    #[test]
+    fn test_scroll_left() {
+        let mut vram = vram_with(Resolution::High, &[(1, 1, FIRST_PLANE), (10, 2, FIRST_PLANE)]);
+
+        vram.scroll_left(FIRST_PLANE, 4);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::High, &[(6, 2, FIRST_PLANE)]),
+            "vram should be scrolled left by 4"
+        );
+    }
+
+    #[test]
+    fn test_scroll_right() {
+        let mut vram = vram_with(Resolution::High, &[(1, 1, FIRST_PLANE), (125, 2, FIRST_PLANE)]);
+
+        vram.scroll_right(FIRST_PLANE, 4);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::High, &[(5, 1, FIRST_PLANE)]),
+            "vram should be scrolled right by 4"
+        );
+    }
+
+    #[test]
+    fn test_scroll_only_selected_planes() {
+        let mut vram = vram_with(Resolution::Low, &[(1, 1, ALL_PLANES)]);
+
+        vram.scroll_right(SECOND_PLANE, 4);
+
+        assert_eq!(
+            *vram,
+            *vram_with(Resolution::Low, &[(1, 1, FIRST_PLANE), (5, 1, SECOND_PLANE)]),
+            "vram should have only the second plane scrolled"
+        );
+    }
+}